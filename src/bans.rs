@@ -1,12 +1,15 @@
 pub mod cfg;
 mod diags;
+mod dominators;
 mod graph;
+mod remediation;
+mod report;
 
 use self::cfg::{ValidBuildConfig, ValidConfig, ValidTreeSkip};
 use crate::{
     cfg::{PackageSpec, Reason, Span, Spanned},
     diag::{self, CfgCoord, FileId, KrateCoord},
-    Kid, Krate, Krates, LintLevel,
+    is_workspace_member, Kid, Krate, Krates, LintLevel,
 };
 use anyhow::Error;
 pub use diags::Code;
@@ -64,14 +67,234 @@ impl SpecsAndReasons {
     }
 }
 
+/// Crate entries whose names may also be glob patterns, and which may be
+/// restricted to crates with particular categories or keywords, or from a
+/// particular source
+struct KrateMatchers {
+    specs: SpecsAndReasons,
+    /// The compiled glob for each entry whose name is a glob pattern
+    globs: Vec<Option<globset::GlobMatcher>>,
+    tags: Vec<cfg::CrateTags>,
+    /// The source each entry is restricted to, if any
+    sources: Vec<Option<url::Url>>,
+}
+
+impl KrateMatchers {
+    fn new(entries: Vec<(SpecAndReason, cfg::CrateTags, Option<url::Url>)>) -> Self {
+        let mut specs = Vec::with_capacity(entries.len());
+        let mut tags = Vec::with_capacity(entries.len());
+        let mut sources = Vec::with_capacity(entries.len());
+        for (specr, tag, source) in entries {
+            specs.push(specr);
+            tags.push(tag);
+            sources.push(source);
+        }
+
+        let globs = specs
+            .iter()
+            .map(|sr| {
+                let name = &sr.spec.name.value;
+                if cfg::is_glob(name) {
+                    // Invalid patterns have already been removed during validation
+                    globset::Glob::new(name)
+                        .ok()
+                        .map(|glob| glob.compile_matcher())
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            specs: SpecsAndReasons(specs),
+            globs,
+            tags,
+            sources,
+        }
+    }
+
+    #[inline]
+    fn is_match(&self, index: usize, krate: &Krate) -> bool {
+        let spec = &self.specs.0[index].spec;
+        let name_matches = match &self.globs[index] {
+            Some(glob) => {
+                glob.is_match(&krate.name)
+                    && crate::match_req(&krate.version, spec.version_req.as_ref())
+            }
+            None => crate::match_krate(krate, spec),
+        };
+
+        name_matches
+            && self.tags[index].matches(krate)
+            && self.sources[index]
+                .as_ref()
+                .map_or(true, |src| krate.matches_url(src, false))
+    }
+
+    /// Returns the entries that match the specified crate
+    fn matches(&self, krate: &Krate) -> Option<Vec<ReqMatch<'_>>> {
+        let matches: Vec<_> = (0..self.specs.0.len())
+            .filter(|index| self.is_match(*index, krate))
+            .map(|index| ReqMatch {
+                specr: &self.specs.0[index],
+                index,
+            })
+            .collect();
+
+        if matches.is_empty() {
+            None
+        } else {
+            Some(matches)
+        }
+    }
+}
+
+/// Restrictions on which of the crates matched by a deny entry it applies to
+#[derive(Default)]
+struct DenyFilter {
+    kinds: Vec<cfg::EdgeKind>,
+    default_features: Option<bool>,
+    targets: Vec<&'static str>,
+    /// Crates that are approved despite matching the entry
+    exempt: Vec<PackageSpec>,
+}
+
 struct SkipRoot {
     specr: SpecAndReason,
+    /// The configured depth, if any
+    max_depth: Option<usize>,
     skip_crates: Vec<Kid>,
+    /// The depth at which each skipped crate was found, in the same units as
+    /// the configured depth
+    depths: Vec<usize>,
     skip_hits: BitVec,
 }
 
 use bitvec::prelude::*;
 
+/// The entries of a single `[bans.overrides]` member
+struct OverrideSpecs {
+    deny: Vec<SpecAndReason>,
+    exempt: Vec<SpecAndReason>,
+    skip: Vec<SpecAndReason>,
+}
+
+// Overrides only apply to the crates reachable from the workspace member they
+// are specified for, so track which members each crate is reachable from
+struct MemberOverrides {
+    specs: Vec<OverrideSpecs>,
+    /// For each crate, the index of the overrides of each member it is
+    /// reachable from, or `None` for members without any overrides
+    reached_by: Vec<Vec<Option<usize>>>,
+}
+
+impl MemberOverrides {
+    fn build(
+        overrides: Vec<cfg::MemberOverride>,
+        krates: &Krates,
+        cfg_file_id: FileId,
+    ) -> (Self, Pack) {
+        let mut pack = Pack::new(Check::Bans);
+
+        if overrides.is_empty() {
+            return (
+                Self {
+                    specs: Vec::new(),
+                    reached_by: Vec::new(),
+                },
+                pack,
+            );
+        }
+
+        let to_specr = |spec: PackageSpec| SpecAndReason {
+            spec,
+            reason: None,
+            use_instead: None,
+            file_id: cfg_file_id,
+        };
+
+        let mut names = Vec::with_capacity(overrides.len());
+        let mut specs = Vec::with_capacity(overrides.len());
+        for mo in overrides {
+            if !is_member_name(krates, &mo.member.value) {
+                pack.push(diags::UnmatchedOverride {
+                    member_cfg: CfgCoord {
+                        file: cfg_file_id,
+                        span: mo.member.span,
+                    },
+                });
+            }
+
+            names.push(mo.member.value);
+            specs.push(OverrideSpecs {
+                deny: mo.deny.into_iter().map(to_specr).collect(),
+                exempt: mo.exempt.into_iter().map(to_specr).collect(),
+                skip: mo.skip.into_iter().map(to_specr).collect(),
+            });
+        }
+
+        let mut reached_by = vec![Vec::new(); krates.len()];
+        for node in krates.workspace_members() {
+            let krates::Node::Krate { id, krate, .. } = node else {
+                continue;
+            };
+            let Some(nid) = krates.nid_for_kid(id) else {
+                continue;
+            };
+
+            let oi = names.iter().position(|name| *name == krate.name);
+            reached_by[nid.index()].push(oi);
+            for dep in report::reachable(krates, nid) {
+                if dep != nid {
+                    reached_by[dep.index()].push(oi);
+                }
+            }
+        }
+
+        (Self { specs, reached_by }, pack)
+    }
+
+    /// Gets the override `deny` entry for the crate, if any member it is
+    /// reachable from bans it
+    fn denied(&self, i: usize, krate: &Krate) -> Option<&SpecAndReason> {
+        self.reached_by.get(i)?.iter().flatten().find_map(|oi| {
+            self.specs[*oi]
+                .deny
+                .iter()
+                .find(|specr| crate::match_krate(krate, &specr.spec))
+        })
+    }
+
+    /// Gets the override `exempt` entry for the crate, if every member it is
+    /// reachable from exempts it
+    fn exempted(&self, i: usize, krate: &Krate) -> Option<&SpecAndReason> {
+        self.relaxed(i, krate, |os| os.exempt.as_slice())
+    }
+
+    /// Gets the override `skip` entry for the crate, if every member it is
+    /// reachable from skips it
+    fn skipped(&self, i: usize, krate: &Krate) -> Option<&SpecAndReason> {
+        self.relaxed(i, krate, |os| os.skip.as_slice())
+    }
+
+    fn relaxed(
+        &self,
+        i: usize,
+        krate: &Krate,
+        entries: fn(&OverrideSpecs) -> &[SpecAndReason],
+    ) -> Option<&SpecAndReason> {
+        let mut relaxed = None;
+        for oi in self.reached_by.get(i)? {
+            let specr = entries(&self.specs[(*oi)?])
+                .iter()
+                .find(|specr| crate::match_krate(krate, &specr.spec))?;
+            relaxed.get_or_insert(specr);
+        }
+
+        relaxed
+    }
+}
+
 // If trees are being skipped, walk each one down to the specified depth and add
 // each dependency as a skipped crate at the specific version
 struct TreeSkipper {
@@ -115,11 +338,12 @@ impl TreeSkipper {
         krate_id: krates::NodeId,
         krates: &Krates,
     ) -> SkipRoot {
-        let (max_depth, reason) = ts.inner.map_or((std::usize::MAX, None), |inn| {
-            (inn.depth.unwrap_or(std::usize::MAX), inn.reason)
-        });
+        let (configured_depth, reason) =
+            ts.inner.map_or((None, None), |inn| (inn.depth, inn.reason));
+        let max_depth = configured_depth.unwrap_or(std::usize::MAX);
 
         let mut skip_crates = Vec::with_capacity(10);
+        let mut depths = Vec::with_capacity(10);
 
         let graph = krates.graph();
 
@@ -132,6 +356,7 @@ impl TreeSkipper {
             };
             if let Err(i) = skip_crates.binary_search(pkg_id) {
                 skip_crates.insert(i, pkg_id.clone());
+                depths.insert(i, depth);
 
                 if depth < max_depth {
                     for dep in krates.direct_dependencies(node_id) {
@@ -150,7 +375,9 @@ impl TreeSkipper {
                 use_instead: None,
                 file_id,
             },
+            max_depth: configured_depth,
             skip_crates,
+            depths,
             skip_hits,
         }
     }
@@ -172,6 +399,23 @@ impl TreeSkipper {
 
         skip
     }
+
+    /// Reports how many crates each root actually suppressed, and the deepest
+    /// of them, so that overly broad entries can be narrowed
+    fn report_usage(&self, severity: Severity, pack: &mut Pack) {
+        for root in &self.roots {
+            let depth = root.skip_hits.iter_ones().map(|i| root.depths[i]).max();
+
+            pack.push(diags::SkipTreeUsage {
+                skip_root_cfg: &root.specr,
+                suppressed: root.skip_hits.count_ones(),
+                total: root.skip_crates.len(),
+                depth,
+                max_depth: root.max_depth,
+                severity,
+            });
+        }
+    }
 }
 
 pub struct DupGraph {
@@ -187,33 +431,211 @@ impl fmt::Debug for DupGraph {
 
 pub type OutputGraph = dyn Fn(DupGraph) -> Result<(), Error> + Send + Sync;
 
+pub use graph::NewestVersions;
+pub use remediation::{
+    append_skips, banned_krates, remediation_script, unskipped_duplicates, BannedKrate,
+};
+pub use report::{member_findings, member_report, MemberFindings};
+
+/// Outputs a graph for each crate with multiple versions
+pub struct GraphOutput {
+    /// Called with each duplicate graph
+    pub output: Box<OutputGraph>,
+    /// If available, the newest versions in the registry index, used to
+    /// annotate duplicates that could be updated
+    pub newest: Option<NewestVersions>,
+}
+
+/// The inclusion graph for each version of a crate with multiple versions
+pub type DuplicateGraphs = Vec<(String, Vec<(semver::Version, diag::grapher::GraphNode)>)>;
+
+/// Builds the inclusion graphs for every crate with multiple versions in the
+/// graph, ordered by crate name and then version
+///
+/// The graphs of the versions selected by `highlight` are highlighted, the
+/// same as the edges leading to them in the dotgraphs emitted by [`check`]
+pub fn duplicate_graphs(
+    krates: &Krates,
+    highlight: cfg::GraphHighlight,
+) -> Result<DuplicateGraphs, diag::GraphError> {
+    let grapher = diag::InclusionGrapher::new(krates);
+    let mut dupes = Vec::new();
+
+    // The krates are ordered by id, so all versions of a crate are adjacent
+    let mut start = 0;
+    while start < krates.len() {
+        let name = &krates[start].name;
+        let end = (start + 1..krates.len())
+            .find(|i| krates[*i].name != *name)
+            .unwrap_or(krates.len());
+
+        if end - start > 1 {
+            let mut versions = (start..end)
+                .map(|i| {
+                    let krate = &krates[i];
+                    let graph = grapher.build_graph(
+                        &diag::GraphNode {
+                            kid: krate.id.clone(),
+                            feature: None,
+                        },
+                        0,
+                    )?;
+
+                    Ok((krate.version.clone(), graph))
+                })
+                .collect::<Result<Vec<_>, diag::GraphError>>()?;
+
+            versions.sort_by(|a, b| a.0.cmp(&b.0));
+
+            // The version with the fewest crates depending on it is
+            // presumably the easiest one to "fix"
+            if highlight.simplest() {
+                if let Some(simplest) = versions.iter_mut().min_by_key(|(_, g)| g.node_count()) {
+                    simplest.1.highlight();
+                }
+            }
+
+            if highlight.lowest_version() {
+                versions[0].1.highlight();
+            }
+
+            if highlight.highest_version() {
+                if let Some(highest) = versions.last_mut() {
+                    highest.1.highlight();
+                }
+            }
+
+            dupes.push((name.clone(), versions));
+        }
+
+        start = end;
+    }
+
+    Ok(dupes)
+}
+
 use crate::diag::{Check, Diag, Pack, Severity};
 
 pub fn check(
     ctx: crate::CheckCtx<'_, ValidConfig>,
-    output_graph: Option<Box<OutputGraph>>,
+    output_graph: Option<GraphOutput>,
     cargo_spans: diag::CargoSpans,
     sink: impl Into<diag::ErrorSink>,
+) {
+    check_with_findings(
+        ctx,
+        output_graph,
+        cargo_spans,
+        sink,
+        &mut remediation::Findings::default(),
+    );
+}
+
+/// Performs the bans check, also recording the crates that are banned or
+/// reported as duplicates, so that the remediation helpers share the exact
+/// same matching as the check itself
+pub(crate) fn check_with_findings<'k>(
+    ctx: crate::CheckCtx<'k, ValidConfig>,
+    output_graph: Option<GraphOutput>,
+    cargo_spans: diag::CargoSpans,
+    sink: impl Into<diag::ErrorSink>,
+    findings: &mut remediation::Findings<'k>,
 ) {
     let ValidConfig {
         file_id,
+        included_files: _,
         denied,
         denied_multiple_versions,
         allowed,
+        allow_exact,
+        exclude_workspace,
+        wrapper_notes,
+        removal_notes,
+        required,
         features,
         workspace_default_features,
         external_default_features,
+        deny_member_features,
+        deny_features_global,
+        deny_publishers,
+        crate_owners,
+        denied_publisher,
+        deny_direct,
+        denied_direct_dependency,
         skipped,
+        waived,
         multiple_versions,
+        multiple_versions_levels,
         multiple_versions_include_dev,
+        multiple_sources,
         highlight,
+        multiple_versions_policy,
+        roots,
         tree_skipped,
+        skip_tree_usage,
+        skip_sources,
+        overrides,
         wildcards,
         allow_wildcard_paths,
+        patches,
+        links,
+        duplicate_links,
+        build_and_normal,
+        typosquatting,
+        git_only,
+        replace,
+        requirement_drift,
+        workspace_inheritance,
+        prerelease,
+        prerelease_exceptions,
+        min_rust_version,
+        rust_version_too_new,
+        max_unique_dependencies,
+        max_total_crates,
+        max_glob_matches,
+        max_duplicates,
+        max_depth,
+        max_direct_deps,
+        max_crate_size,
+        crate_size_exceptions,
         build,
     } = ctx.cfg;
 
     let mut sink = sink.into();
+
+    // Skip entries can waive specific diagnostics for the crates they match,
+    // rather than skipping the crates entirely
+    if !waived.is_empty() {
+        let mut waivers = sink.waivers.as_deref().cloned().unwrap_or_default();
+        let mut codes = Vec::with_capacity(waived.len());
+        let waived = KrateMatchers::new(
+            waived
+                .into_iter()
+                .map(|waiver| {
+                    codes.push(waiver.entry.codes);
+                    (
+                        SpecAndReason {
+                            spec: waiver.entry.spec,
+                            reason: None,
+                            use_instead: None,
+                            file_id: waiver.file_id,
+                        },
+                        Default::default(),
+                        None,
+                    )
+                })
+                .collect(),
+        );
+
+        for krate in ctx.krates.krates() {
+            for rm in waived.matches(krate).into_iter().flatten() {
+                waivers.waive(krate.id.clone(), codes[rm.index].iter().cloned());
+            }
+        }
+
+        sink.waivers = Some(std::sync::Arc::new(waivers));
+    }
+
     let krate_spans = &ctx.krate_spans;
     let (mut tree_skipper, build_diags) = TreeSkipper::build(tree_skipped, ctx.krates, file_id);
 
@@ -221,8 +643,57 @@ pub fn check(
         sink.push(build_diags);
     }
 
+    let (member_overrides, override_diags) = MemberOverrides::build(overrides, ctx.krates, file_id);
+
+    if !override_diags.is_empty() {
+        sink.push(override_diags);
+    }
+
     use std::collections::BTreeMap;
 
+    // The exact same version of a crate from multiple sources, eg. crates.io
+    // and a git fork, is almost always a mistake, distinct from the crate
+    // simply being present with multiple versions
+    if multiple_sources != LintLevel::Allow {
+        let mut by_version = BTreeMap::<_, Vec<usize>>::new();
+        for (i, krate) in ctx.krates.krates().enumerate() {
+            by_version
+                .entry((&krate.name, &krate.version))
+                .or_default()
+                .push(i);
+        }
+
+        for indices in by_version.into_values().filter(|indices| indices.len() > 1) {
+            let mut diag: Diag = diags::MultipleSources {
+                krate: &ctx.krates[indices[0]],
+                sources: indices
+                    .iter()
+                    .map(|i| {
+                        let span = &krate_spans[*i];
+                        KrateCoord {
+                            file: krate_spans.file_id,
+                            span: (span.source..span.total.end).into(),
+                        }
+                    })
+                    .collect(),
+                severity: multiple_sources.into(),
+            }
+            .into();
+
+            diag.graph_nodes = indices
+                .into_iter()
+                .map(|i| crate::diag::GraphNode {
+                    kid: ctx.krates[i].id.clone(),
+                    feature: None,
+                })
+                .collect();
+
+            let mut pack = Pack::new(Check::Bans);
+            pack.push(diag);
+            sink.push(pack);
+        }
+    }
+
     struct BanWrappers {
         map: BTreeMap<usize, (usize, Vec<Spanned<String>>)>,
         hits: BitVec,
@@ -258,38 +729,133 @@ pub fn check(
         }
     }
 
-    let (denied_ids, mut ban_wrappers) = {
+    let (denied_ids, mut ban_wrappers, deny_filters) = {
         let mut bw = BTreeMap::new();
+        let mut filters = Vec::with_capacity(denied.len());
 
-        (
-            SpecsAndReasons(
-                denied
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, kb)| {
-                        let (reason, use_instead) = if let Some(ext) = kb.inner {
-                            if let Some(wrappers) = ext.wrappers.filter(|w| !w.is_empty()) {
-                                bw.insert(i, (0, wrappers));
-                            }
+        let denied = KrateMatchers::new(
+            denied
+                .into_iter()
+                .enumerate()
+                .map(|(i, cfg::FileEntry { file_id, entry: kb })| {
+                    let (reason, use_instead, tags) = if let Some(ext) = kb.inner {
+                        if let Some(wrappers) = ext.wrappers.filter(|w| !w.is_empty()) {
+                            bw.insert(i, (0, wrappers));
+                        }
 
-                            (ext.reason, ext.use_instead)
-                        } else {
-                            (None, None)
-                        };
+                        filters.push(DenyFilter {
+                            kinds: ext.kinds,
+                            default_features: ext.default_features,
+                            targets: ext.targets,
+                            exempt: ext.exempt,
+                        });
+                        (ext.reason, ext.use_instead, ext.tags)
+                    } else {
+                        filters.push(DenyFilter::default());
+                        (None, None, Default::default())
+                    };
 
+                    (
                         SpecAndReason {
                             spec: kb.spec,
                             reason,
                             use_instead,
                             file_id,
-                        }
-                    })
-                    .collect(),
-            ),
-            BanWrappers::new(bw),
-        )
+                        },
+                        tags,
+                        None,
+                    )
+                })
+                .collect(),
+        );
+
+        (denied, BanWrappers::new(bw), filters)
+    };
+
+    // Entries restricted to particular kinds of dependency only ban a crate
+    // if it is reached as one of them
+    let dep_kinds = if deny_filters.iter().any(|df| !df.kinds.is_empty()) {
+        dependency_kinds(ctx.krates)
+    } else {
+        Vec::new()
     };
 
+    // Likewise, entries restricted to particular targets only ban a crate if
+    // it would be built for one of them
+    let built_for: std::collections::HashMap<&str, Vec<bool>> = deny_filters
+        .iter()
+        .flat_map(|df| df.targets.iter().copied())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|triple| {
+            let target = krates::cfg_expr::targets::get_builtin_target_by_triple(triple)?;
+            Some((triple, built_for_target(ctx.krates, target)))
+        })
+        .collect();
+
+    // Workspace members and path dependencies are our own crates, even if they
+    // happen to share a name with a crate published to a registry, so by
+    // default they are never matched by `deny`, `allow`, or `skip` entries
+    let is_matchable =
+        |krate: &Krate| !exclude_workspace || !crate::is_first_party(krate, ctx.krates);
+
+    let applies_to = |index: usize, krate_index: usize| {
+        let df = &deny_filters[index];
+
+        is_matchable(&ctx.krates[krate_index])
+            && (df.kinds.is_empty()
+            || df
+                .kinds
+                .iter()
+                .any(|kind| dep_kinds[krate_index][*kind as usize]))
+            && (df.targets.is_empty()
+                || df.targets.iter().any(|triple| {
+                    built_for
+                        .get(triple)
+                        .map_or(false, |built| built[krate_index])
+                }))
+            // Entries can also be restricted to crates with, or without,
+            // their default features enabled, as those often pull in the parts
+            // of a crate that are actually problematic, eg. a bundled C library
+            && df.default_features.map_or(true, |default_features| {
+                ctx.krates
+                    .get_enabled_features(&ctx.krates[krate_index].id)
+                    .map_or(false, |features| features.contains("default"))
+                    == default_features
+            })
+            && !df
+                .exempt
+                .iter()
+                .any(|spec| crate::match_krate(&ctx.krates[krate_index], spec))
+    };
+
+    // In strict mode, every crate must be allowed by an entry that pins it to
+    // an exact version, the crates that aren't are reported together
+    let mut unapproved = Vec::new();
+
+    // If a glob deny entry matches more crates than the limit, the crates it
+    // bans are reported in a single diagnostic rather than one per crate
+    let mut glob_floods: BTreeMap<usize, Vec<&Krate>> = max_glob_matches
+        .as_ref()
+        .map(|limit| {
+            (0..denied_ids.globs.len())
+                .filter(|index| {
+                    denied_ids.globs[*index].is_some()
+                        && ctx
+                            .krates
+                            .krates()
+                            .enumerate()
+                            .filter(|(i, krate)| {
+                                denied_ids.is_match(*index, krate) && applies_to(*index, *i)
+                            })
+                            .count()
+                            > limit.limit.value
+                })
+                .map(|index| (index, Vec::new()))
+                .collect()
+        })
+        .unwrap_or_default();
+
     let (feature_ids, features): (Vec<_>, Vec<_>) = features
         .into_iter()
         .map(|cf| {
@@ -307,6 +873,12 @@ pub fn check(
 
     let feature_ids = SpecsAndReasons(feature_ids);
 
+    let replaced_by_patch = if patches.replace_originals {
+        patched_originals(ctx.krates)
+    } else {
+        std::collections::HashSet::new()
+    };
+
     // Keep track of all the crates we skip, and emit a warning if
     // we encounter a skip that didn't actually match any crate version
     // so that people can clean up their config files
@@ -328,160 +900,851 @@ pub fn check(
         Vec::new()
     };
 
-    // If we're not counting dev dependencies as duplicates, create a separate
-    // set of krates with dev dependencies filtered out
-    let should_add_dupe = move |kid| {
-        if multiple_versions_include_dev {
-            true
-        } else {
-            filtered_krates
-                .binary_search_by(|krate| krate.id.cmp(kid))
-                .is_ok()
+    // If roots are specified, only the crates reachable from them are
+    // considered when detecting duplicates
+    let rooted = if roots.is_empty() {
+        None
+    } else {
+        let mut rooted = std::collections::HashSet::new();
+        let mut pack = Pack::new(Check::Bans);
+
+        for root in &roots {
+            let member = ctx.krates.workspace_members().find_map(|n| match n {
+                krates::Node::Krate { id, krate, .. } if krate.name == root.value => {
+                    ctx.krates.nid_for_kid(id)
+                }
+                _ => None,
+            });
+
+            if let Some(nid) = member {
+                rooted.insert(nid);
+                rooted.extend(report::reachable(ctx.krates, nid));
+            } else {
+                pack.push(diags::UnmatchedRoot {
+                    root_cfg: CfgCoord {
+                        file: file_id,
+                        span: root.span,
+                    },
+                });
+            }
+        }
+
+        if !pack.is_empty() {
+            sink.push(pack);
+        }
+
+        Some(rooted)
+    };
+
+    // If we're not counting dev dependencies as duplicates, create a separate
+    // set of krates with dev dependencies filtered out
+    let krates = ctx.krates;
+    let should_add_dupe = move |kid| {
+        if let Some(rooted) = &rooted {
+            if !krates
+                .nid_for_kid(kid)
+                .map_or(false, |nid| rooted.contains(&nid))
+            {
+                return false;
+            }
+        }
+
+        if multiple_versions_include_dev {
+            true
+        } else {
+            filtered_krates
+                .binary_search_by(|krate| krate.id.cmp(kid))
+                .is_ok()
+        }
+    };
+
+    let dmv = SpecsAndReasons(
+        denied_multiple_versions
+            .into_iter()
+            .map(|dmv| SpecAndReason {
+                spec: dmv.entry,
+                reason: None,
+                use_instead: None,
+                file_id: dmv.file_id,
+            })
+            .collect(),
+    );
+
+    let allowed = KrateMatchers::new(
+        allowed
+            .into_iter()
+            .map(
+                |cfg::FileEntry {
+                     file_id,
+                     entry: all,
+                 }| {
+                    let (reason, tags, source) =
+                        all.inner.map_or((None, Default::default(), None), |ext| {
+                            (ext.reason, ext.tags, ext.source.map(|src| src.value))
+                        });
+
+                    (
+                        SpecAndReason {
+                            spec: all.spec,
+                            reason,
+                            use_instead: None,
+                            file_id,
+                        },
+                        tags,
+                        source,
+                    )
+                },
+            )
+            .collect(),
+    );
+
+    // The crates that each skip entry scoped to an ancestor does not apply to,
+    // as they can be reached without going through the ancestor
+    let mut skip_outside = Vec::with_capacity(skipped.len());
+    let skipped = KrateMatchers::new(
+        skipped
+            .into_iter()
+            .map(|skip| {
+                let (reason, source, under) = skip.entry.inner.map_or((None, None, None), |ext| {
+                    (ext.reason, ext.source, ext.under)
+                });
+
+                skip_outside.push(under.map(|under| reachable_outside(ctx.krates, &under.value)));
+
+                (
+                    SpecAndReason {
+                        spec: skip.entry.spec,
+                        reason,
+                        use_instead: None,
+                        file_id: skip.file_id,
+                    },
+                    Default::default(),
+                    source.map(|src| src.value),
+                )
+            })
+            .collect(),
+    );
+
+    let max_duplicates = max_duplicates.filter(|md| md.level != LintLevel::Allow);
+
+    // Reports the crate if it has multiple versions, returning true if it
+    // counts towards the duplicate budget
+    let report_duplicates = |multi_detector: &MultiDetector<'_>, sink: &mut diag::ErrorSink| {
+        if multi_detector.dupes.len() <= 1 {
+            return false;
+        }
+
+        // Versions that are semver compatible with each other will be unified
+        // by cargo once every requirement allows the same version, eg. after
+        // a `cargo update`, so they can optionally be ignored
+        if multiple_versions_policy == cfg::MultipleVersionsPolicy::SemverIncompatible {
+            let compat = |version: &semver::Version| match (version.major, version.minor) {
+                (0, 0) => (0, 0, version.patch),
+                (0, minor) => (0, minor, 0),
+                (major, _) => (major, 0, 0),
+            };
+
+            let first = compat(&ctx.krates[multi_detector.dupes[0]].version);
+            if multi_detector.dupes[1..]
+                .iter()
+                .all(|dup| compat(&ctx.krates[*dup].version) == first)
+            {
+                return false;
+            }
+        }
+
+        let is_denied = multi_detector.dupes.iter().any(|kindex| {
+            let krate = &ctx.krates[*kindex];
+            dmv.matches(krate).is_some()
+        });
+
+        // A level configured for the specific crate takes precedence over the
+        // global level
+        let crate_level = multiple_versions_levels
+            .iter()
+            .find(|mvl| {
+                multi_detector
+                    .dupes
+                    .iter()
+                    .any(|kindex| crate::match_krate(&ctx.krates[*kindex], &mvl.spec))
+            })
+            .map(|mvl| mvl.level);
+
+        let is_denied = is_denied || crate_level == Some(LintLevel::Deny);
+
+        let lint_level = if is_denied {
+            LintLevel::Deny
+        } else {
+            crate_level.unwrap_or(multiple_versions)
+        };
+
+        let severity = match lint_level {
+            LintLevel::Warn => Severity::Warning,
+            LintLevel::Deny => Severity::Error,
+            LintLevel::Allow => return false,
+        };
+
+        // With a budget, only exceeding the budget fails the check, unless
+        // the crate is explicitly denied from having multiple versions
+        let severity = if max_duplicates.is_some() && !is_denied {
+            Severity::Note
+        } else {
+            severity
+        };
+
+        let mut all_start = std::usize::MAX;
+        let mut all_end = 0;
+
+        struct Dupe {
+            /// Unique id, used for printing the actual diagnostic graphs
+            id: Kid,
+            /// Version, for deterministically ordering the duplicates
+            version: semver::Version,
+        }
+
+        let mut kids = smallvec::SmallVec::<[Dupe; 2]>::new();
+
+        for dup in multi_detector.dupes.iter().cloned() {
+            let span = &ctx.krate_spans[dup].total;
+
+            if span.start < all_start {
+                all_start = span.start;
+            }
+
+            if span.end > all_end {
+                all_end = span.end;
+            }
+
+            let krate = &ctx.krates[dup];
+
+            if let Err(i) = kids.binary_search_by(|other| match other.version.cmp(&krate.version) {
+                std::cmp::Ordering::Equal => other.id.cmp(&krate.id),
+                ord => ord,
+            }) {
+                kids.insert(
+                    i,
+                    Dupe {
+                        id: krate.id.clone(),
+                        version: krate.version.clone(),
+                    },
+                );
+            }
+        }
+
+        {
+            let mut diag: Diag = diags::Duplicates {
+                krate_name: multi_detector.name,
+                num_dupes: kids.len(),
+                krates_coord: KrateCoord {
+                    file: krate_spans.file_id,
+                    span: (all_start..all_end).into(),
+                },
+                severity,
+                updates: remediation::dedupe_updates(ctx.krates, &multi_detector.dupes),
+            }
+            .into();
+
+            diag.graph_nodes = kids
+                .into_iter()
+                .map(|dupe| crate::diag::GraphNode {
+                    kid: dupe.id,
+                    feature: None,
+                })
+                .collect();
+
+            let mut pack = Pack::new(Check::Bans);
+            pack.push(diag);
+
+            sink.push(pack);
+        }
+
+        if let Some(og) = &output_graph {
+            match graph::create_graph(
+                multi_detector.name,
+                highlight,
+                ctx.krates,
+                &multi_detector.dupes,
+                og.newest.as_ref(),
+            ) {
+                Ok(graph) => {
+                    if let Err(err) = (og.output)(DupGraph {
+                        duplicate: multi_detector.name.to_owned(),
+                        graph,
+                    }) {
+                        log::error!("{err}");
+                    }
+                }
+                Err(err) => {
+                    log::error!("unable to create graph for {}: {err}", multi_detector.name);
+                }
+            };
+        }
+
+        true
+    };
+
+    let mut num_duplicates = 0;
+
+    let max_unique_dependencies =
+        max_unique_dependencies.filter(|mud| mud.level != LintLevel::Allow);
+    let max_total_crates = max_total_crates
+        .filter(|mtc| mtc.level != LintLevel::Allow && ctx.krates.len() > mtc.limit.value);
+
+    // Only calculate the dominator tree if we actually need it
+    let dominators = (max_unique_dependencies.is_some()
+        || max_total_crates.is_some()
+        || (removal_notes && !denied_ids.specs.0.is_empty()))
+    .then(|| dominators::Dominators::new(ctx.krates));
+
+    if let Some((mtc, doms)) = max_total_crates.zip(dominators.as_ref()) {
+        // Point out the crates that would remove the most crates from the
+        // graph if they were removed
+        let mut heaviest: Vec<_> = (0..ctx.krates.len())
+            .filter(|i| {
+                doms.dominated(*i) > 0 && !is_workspace_member(ctx.krates, &ctx.krates[*i].id)
+            })
+            .map(|i| (i, doms.dominated(i)))
+            .collect();
+        heaviest.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut pack = Pack::new(Check::Bans);
+        pack.push(diags::TooManyCrates {
+            count: ctx.krates.len(),
+            limit_cfg: CfgCoord {
+                file: file_id,
+                span: mtc.limit.span,
+            },
+            limit: mtc.limit.value,
+            severity: mtc.level.into(),
+            heaviest: heaviest
+                .into_iter()
+                .take(3)
+                .map(|(i, count)| (&ctx.krates[i], count))
+                .collect(),
+        });
+        sink.push(pack);
+    }
+
+    if patches.unexpected != LintLevel::Allow || !patches.require.is_empty() {
+        let mut required_hit = BitVec::repeat(false, patches.require.len());
+
+        for (i, krate) in ctx.krates.krates().enumerate() {
+            if krate.source.as_ref().map_or(false, |src| src.is_registry()) {
+                continue;
+            }
+
+            let patched = patched_deps(ctx.krates, i);
+            if patched.is_empty() {
+                continue;
+            }
+
+            let mut is_required = false;
+            for (ri, spec) in patches.require.iter().enumerate() {
+                if crate::match_krate(krate, spec) {
+                    required_hit.set(ri, true);
+                    is_required = true;
+                }
+            }
+
+            if !is_required && patches.unexpected != LintLevel::Allow {
+                let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+                pack.push(diags::UnexpectedPatch {
+                    krate,
+                    patched,
+                    severity: patches.unexpected.into(),
+                    cargo_spans: &cargo_spans,
+                });
+                sink.push(pack);
+            }
+        }
+
+        let mut pack = Pack::new(Check::Bans);
+        for spec in required_hit
+            .into_iter()
+            .zip(&patches.require)
+            .filter_map(|(hit, spec)| (!hit).then_some(spec))
+        {
+            pack.push(diags::PatchNotFound { spec, file_id });
+        }
+
+        if !pack.is_empty() {
+            sink.push(pack);
+        }
+    }
+
+    if replace != LintLevel::Allow {
+        let manifest = ctx.krates.workspace_root().join("Cargo.toml");
+
+        for (i, entry) in replaced_krates(ctx.krates, &manifest) {
+            let krate = &ctx.krates[i];
+
+            let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+            pack.push(diags::Replaced {
+                krate,
+                entry: &entry,
+                manifest: &manifest,
+                severity: replace.into(),
+            });
+            sink.push(pack);
+        }
+    }
+
+    if duplicate_links != LintLevel::Allow {
+        // Cargo only prevents crates from declaring the same `links` value,
+        // crates that wrap the same library under a different name, or that
+        // don't declare it at all, can still end up in the same graph
+        for (library, wrappers) in native_libraries(ctx.krates) {
+            if wrappers.len() > 1 {
+                let mut pack = Pack::new(Check::Bans);
+                pack.push(diags::DuplicateLinks {
+                    library: &library,
+                    wrappers,
+                    severity: duplicate_links.into(),
+                });
+                sink.push(pack);
+            }
+        }
+    }
+
+    if build_and_normal != LintLevel::Allow {
+        // Build dependencies are compiled for the host, separately from normal
+        // dependencies, so a crate that is both is compiled twice, often with
+        // a different set of features
+        for (normal_path, build_path) in built_twice(ctx.krates) {
+            let krate = &ctx.krates[*normal_path.last().expect("path is never empty")];
+            let to_krates = |path: Vec<krates::NodeId>| -> Vec<_> {
+                path.into_iter().map(|nid| &ctx.krates[nid]).collect()
+            };
+
+            let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+            pack.push(diags::BuildAndNormal {
+                normal_path: to_krates(normal_path),
+                build_path: to_krates(build_path),
+                severity: build_and_normal.into(),
+            });
+            sink.push(pack);
+        }
+    }
+
+    if typosquatting != LintLevel::Allow {
+        const POPULAR_CRATES: &str = include_str!("bans/popular_crates.txt");
+
+        // Very short names are too close to too many legitimate crates to be
+        // useful, eg. `syn` and `sync`
+        let popular: Vec<_> = POPULAR_CRATES
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|name| (name, normalize_crate_name(name)))
+            .collect();
+
+        for krate in ctx.krates.krates() {
+            if is_workspace_member(ctx.krates, &krate.id) {
+                continue;
+            }
+
+            let name = normalize_crate_name(&krate.name);
+            if popular.iter().any(|(_, pop)| *pop == name) {
+                continue;
+            }
+
+            let Some((similar_to, _)) = popular.iter().find(|(_, pop)| {
+                pop.len() >= 4
+                    && pop.len().abs_diff(name.len()) <= 1
+                    && edit_distance(pop, &name) == 1
+            }) else {
+                continue;
+            };
+
+            let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+            pack.push(diags::PossibleTyposquat {
+                krate,
+                similar_to,
+                severity: typosquatting.into(),
+            });
+            sink.push(pack);
+        }
+    }
+
+    if git_only != LintLevel::Allow {
+        for (i, krate) in ctx.krates.krates().enumerate() {
+            if !krate.is_git_source() {
+                continue;
+            }
+
+            // A git crate that is patched in place of a registry dependency,
+            // or that is also resolved from a registry elsewhere in the graph,
+            // has been published, so there is a version to switch back to
+            if !patched_deps(ctx.krates, i).is_empty()
+                || ctx.krates.krates().any(|other| {
+                    other.name == krate.name
+                        && other.source.as_ref().map_or(false, |src| src.is_registry())
+                })
+            {
+                continue;
+            }
+
+            let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+            pack.push(diags::GitOnly {
+                krate,
+                severity: git_only.into(),
+            });
+            sink.push(pack);
+        }
+    }
+
+    if !deny_member_features.is_empty() {
+        // Only the features the members declare themselves are checked, the
+        // same features enabled by other crates are not the member's doing
+        for node in ctx.krates.workspace_members() {
+            let krates::Node::Krate { krate, .. } = node else {
+                continue;
+            };
+
+            let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+
+            for ban in &deny_member_features {
+                if ban.wrappers.iter().any(|w| w.value == krate.name) {
+                    continue;
+                }
+
+                let enabled = krate.deps.iter().any(|dep| {
+                    dep.name == ban.krate
+                        && (dep.features.contains(&ban.feature.value)
+                            || (ban.feature.value == "default" && dep.uses_default_features))
+                });
+
+                if enabled {
+                    pack.push(diags::DeniedMemberFeature {
+                        member: krate,
+                        ban,
+                        ban_cfg: CfgCoord {
+                            file: file_id,
+                            span: ban.feature.span,
+                        },
+                    });
+                }
+            }
+
+            if !pack.is_empty() {
+                sink.push(pack);
+            }
+        }
+    }
+
+    if !deny_features_global.is_empty() {
+        for (i, krate) in ctx.krates.krates().enumerate() {
+            let Some(enabled_features) = ctx.krates.get_enabled_features(&krate.id) else {
+                continue;
+            };
+
+            let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+
+            for denied in &deny_features_global {
+                let (name, feature) = match denied.value.split_once('/') {
+                    Some((name, feature)) => (Some(name), feature),
+                    None => (None, denied.value.as_str()),
+                };
+
+                if name.map_or(false, |name| name != krate.name)
+                    || !enabled_features.contains(feature)
+                {
+                    continue;
+                }
+
+                pack.push(diags::FeatureBannedGlobally {
+                    krate,
+                    feature,
+                    feature_cfg: CfgCoord {
+                        file: file_id,
+                        span: denied.span,
+                    },
+                    enablers: feature_enablers(ctx.krates, krates::NodeId::new(i), feature),
+                });
+            }
+
+            if !pack.is_empty() {
+                sink.push(pack);
+            }
+        }
+    }
+
+    if !deny_direct.is_empty() && denied_direct_dependency != LintLevel::Allow {
+        let globs: Vec<_> = deny_direct
+            .iter()
+            .map(|spec| {
+                // Invalid patterns have already been removed during validation
+                cfg::is_glob(&spec.name.value)
+                    .then(|| globset::Glob::new(&spec.name.value).ok())
+                    .flatten()
+                    .map(|glob| glob.compile_matcher())
+            })
+            .collect();
+
+        for node in ctx.krates.workspace_members() {
+            let krates::Node::Krate { id, krate, .. } = node else {
+                continue;
+            };
+            let Some(nid) = ctx.krates.nid_for_kid(id) else {
+                continue;
+            };
+
+            // A crate can depend on another through multiple edges, eg. as
+            // both a normal and build dependency, which is only reported once
+            let mut deps: Vec<_> = ctx
+                .krates
+                .direct_dependencies(nid)
+                .into_iter()
+                .map(|dep| dep.node_id)
+                .collect();
+            deps.sort();
+            deps.dedup();
+
+            let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+
+            for dep in deps {
+                let dep = &ctx.krates[dep];
+
+                let Some(spec) = deny_direct.iter().zip(&globs).find_map(|(spec, glob)| {
+                    let name_matches = match glob {
+                        Some(glob) => glob.is_match(&dep.name),
+                        None => dep.name == spec.name.value,
+                    };
+
+                    (name_matches && crate::match_req(&dep.version, spec.version_req.as_ref()))
+                        .then_some(spec)
+                }) else {
+                    continue;
+                };
+
+                pack.push(diags::DeniedDirectDependency {
+                    member: krate,
+                    dep,
+                    spec_cfg: CfgCoord {
+                        file: file_id,
+                        span: spec.name.span,
+                    },
+                    cargo_spans: &cargo_spans,
+                    severity: denied_direct_dependency.into(),
+                });
+            }
+
+            if !pack.is_empty() {
+                sink.push(pack);
+            }
         }
-    };
+    }
 
-    let dmv = SpecsAndReasons(
-        denied_multiple_versions
-            .into_iter()
-            .map(|spec| SpecAndReason {
-                spec,
-                reason: None,
-                use_instead: None,
-                file_id,
-            })
-            .collect(),
-    );
+    if !deny_publishers.is_empty() && denied_publisher != LintLevel::Allow {
+        for krate in ctx.krates.krates() {
+            if is_workspace_member(ctx.krates, &krate.id) {
+                continue;
+            }
 
-    let allowed = SpecsAndReasons(
-        allowed
-            .into_iter()
-            .map(|all| SpecAndReason {
-                spec: all.spec,
-                reason: all.inner,
-                use_instead: None,
-                file_id,
-            })
-            .collect(),
-    );
+            let Some(owners) = crate_owners.get(&krate.name) else {
+                continue;
+            };
 
-    let skipped = SpecsAndReasons(
-        skipped
-            .into_iter()
-            .map(|skip| SpecAndReason {
-                spec: skip.spec,
-                reason: skip.inner,
-                use_instead: None,
-                file_id,
-            })
-            .collect(),
-    );
+            let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
 
-    let report_duplicates = |multi_detector: &MultiDetector<'_>, sink: &mut diag::ErrorSink| {
-        if multi_detector.dupes.len() <= 1 {
-            return;
+            for publisher in deny_publishers
+                .iter()
+                .filter(|publisher| owners.contains(&publisher.value))
+            {
+                pack.push(diags::DeniedPublisher {
+                    krate,
+                    publisher: &publisher.value,
+                    publisher_cfg: CfgCoord {
+                        file: file_id,
+                        span: publisher.span,
+                    },
+                    severity: denied_publisher.into(),
+                });
+            }
+
+            if !pack.is_empty() {
+                sink.push(pack);
+            }
         }
+    }
 
-        let lint_level = if multi_detector.dupes.iter().any(|kindex| {
-            let krate = &ctx.krates[*kindex];
-            dmv.matches(krate).is_some()
-        }) {
-            LintLevel::Deny
-        } else {
-            multiple_versions
-        };
+    if requirement_drift != LintLevel::Allow {
+        // Differing requirements for the same dependency are a common root
+        // cause of duplicates, even when they currently resolve to one version
+        let mut declared =
+            std::collections::BTreeMap::<&str, Vec<(&Krate, &krates::cm::Dependency)>>::new();
 
-        let severity = match lint_level {
-            LintLevel::Warn => Severity::Warning,
-            LintLevel::Deny => Severity::Error,
-            LintLevel::Allow => return,
-        };
+        for node in ctx.krates.workspace_members() {
+            let krates::Node::Krate { krate, .. } = node else {
+                continue;
+            };
 
-        let mut all_start = std::usize::MAX;
-        let mut all_end = 0;
+            for dep in &krate.deps {
+                // Path dependencies don't have meaningful version requirements
+                if dep.source.is_none() {
+                    continue;
+                }
 
-        struct Dupe {
-            /// Unique id, used for printing the actual diagnostic graphs
-            id: Kid,
-            /// Version, for deterministically ordering the duplicates
-            version: semver::Version,
+                let members = declared.entry(dep.name.as_str()).or_default();
+                if !members
+                    .iter()
+                    .any(|(member, other)| member.id == krate.id && other.req == dep.req)
+                {
+                    members.push((krate, dep));
+                }
+            }
         }
 
-        let mut kids = smallvec::SmallVec::<[Dupe; 2]>::new();
+        for (name, members) in declared {
+            // A single member can declare the same dependency with different
+            // requirements, eg. in `[dependencies]` and `[dev-dependencies]`,
+            // which is only drift if the requirements differ across members
+            let drifted = members.iter().any(|(a, a_dep)| {
+                members
+                    .iter()
+                    .any(|(b, b_dep)| a.id != b.id && a_dep.req != b_dep.req)
+            });
 
-        for dup in multi_detector.dupes.iter().cloned() {
-            let span = &ctx.krate_spans[dup].total;
+            if drifted {
+                let num_reqs = members
+                    .iter()
+                    .map(|(_, dep)| &dep.req)
+                    .collect::<std::collections::HashSet<_>>()
+                    .len();
 
-            if span.start < all_start {
-                all_start = span.start;
+                let mut pack = Pack::new(Check::Bans);
+                pack.push(diags::RequirementDrift {
+                    name,
+                    declared: members,
+                    num_reqs,
+                    severity: requirement_drift.into(),
+                    cargo_spans: &cargo_spans,
+                });
+                sink.push(pack);
             }
+        }
+    }
 
-            if span.end > all_end {
-                all_end = span.end;
-            }
+    if workspace_inheritance != LintLevel::Allow {
+        let mut pack = Pack::new(Check::Bans);
 
-            let krate = &ctx.krates[dup];
+        for (member, dep) in not_inherited(ctx.krates) {
+            pack.push(diags::NotInherited {
+                member,
+                dep: &dep,
+                severity: workspace_inheritance.into(),
+                cargo_spans: &cargo_spans,
+            });
+        }
 
-            if let Err(i) = kids.binary_search_by(|other| match other.version.cmp(&krate.version) {
-                std::cmp::Ordering::Equal => other.id.cmp(&krate.id),
-                ord => ord,
-            }) {
-                kids.insert(
-                    i,
-                    Dupe {
-                        id: krate.id.clone(),
-                        version: krate.version.clone(),
-                    },
-                );
-            }
+        if !pack.is_empty() {
+            sink.push(pack);
         }
+    }
 
-        {
-            let mut diag: Diag = diags::Duplicates {
-                krate_name: multi_detector.name,
-                num_dupes: kids.len(),
-                krates_coord: KrateCoord {
-                    file: krate_spans.file_id,
-                    span: (all_start..all_end).into(),
+    if let Some(md) = max_depth.filter(|md| md.level != LintLevel::Allow) {
+        for path in too_deep(ctx.krates, md.limit.value) {
+            let krate = &ctx.krates[*path.last().unwrap()];
+
+            let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+            pack.push(diags::DependencyTooDeep {
+                path: path.into_iter().map(|nid| &ctx.krates[nid]).collect(),
+                limit_cfg: CfgCoord {
+                    file: file_id,
+                    span: md.limit.span,
                 },
-                severity,
-            }
-            .into();
+                limit: md.limit.value,
+                severity: md.level.into(),
+            });
+            sink.push(pack);
+        }
+    }
 
-            diag.graph_nodes = kids
+    if let Some(mdd) = max_direct_deps.filter(|mdd| mdd.level != LintLevel::Allow) {
+        for (i, krate) in ctx.krates.krates().enumerate() {
+            // A crate can depend on another through multiple edges, eg. as
+            // both a normal and build dependency, which only counts once
+            let mut deps: Vec<_> = ctx
+                .krates
+                .direct_dependencies(krates::NodeId::new(i))
                 .into_iter()
-                .map(|dupe| crate::diag::GraphNode {
-                    kid: dupe.id,
-                    feature: None,
-                })
+                .map(|dep| dep.node_id)
                 .collect();
+            deps.sort();
+            deps.dedup();
 
-            let mut pack = Pack::new(Check::Bans);
-            pack.push(diag);
+            if deps.len() > mdd.limit.value {
+                let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+                pack.push(diags::TooManyDirectDependencies {
+                    krate,
+                    count: deps.len(),
+                    limit_cfg: CfgCoord {
+                        file: file_id,
+                        span: mdd.limit.span,
+                    },
+                    limit: mdd.limit.value,
+                    severity: mdd.level.into(),
+                });
+                sink.push(pack);
+            }
+        }
+    }
 
-            sink.push(pack);
+    if let Some(mcs) = max_crate_size.filter(|mcs| mcs.level != LintLevel::Allow) {
+        // Path crates are part of the user's own source tree, so only crates
+        // that are downloaded, and thus cached and vendored, are sized
+        for krate in ctx.krates.krates().filter(|krate| {
+            krate.source.is_some()
+                && !crate_size_exceptions
+                    .iter()
+                    .any(|spec| crate::match_krate(krate, spec))
+        }) {
+            let Some(size) = krate.manifest_path.parent().map(unpacked_size) else {
+                continue;
+            };
+
+            if size > mcs.limit.value {
+                let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+                pack.push(diags::CrateTooLarge {
+                    krate,
+                    size,
+                    limit_cfg: CfgCoord {
+                        file: file_id,
+                        span: mcs.limit.span,
+                    },
+                    limit: mcs.limit.value,
+                    severity: mcs.level.into(),
+                });
+                sink.push(pack);
+            }
         }
+    }
 
-        if let Some(og) = &output_graph {
-            match graph::create_graph(
-                multi_detector.name,
-                highlight,
-                ctx.krates,
-                &multi_detector.dupes,
-            ) {
-                Ok(graph) => {
-                    if let Err(err) = og(DupGraph {
-                        duplicate: multi_detector.name.to_owned(),
-                        graph,
-                    }) {
-                        log::error!("{err}");
-                    }
-                }
-                Err(err) => {
-                    log::error!("unable to create graph for {}: {err}", multi_detector.name);
-                }
+    if let Some(mrv) = min_rust_version
+        .as_ref()
+        .filter(|_| rust_version_too_new != LintLevel::Allow)
+    {
+        for krate in ctx.krates.krates() {
+            let Some(rust_version) = &krate.rust_version else {
+                continue;
             };
+
+            if rust_version > &mrv.value {
+                let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+                pack.push(diags::RustVersionTooNew {
+                    krate,
+                    rust_version,
+                    msrv_cfg: CfgCoord {
+                        file: file_id,
+                        span: mrv.span,
+                    },
+                    msrv: &mrv.value,
+                    severity: rust_version_too_new.into(),
+                });
+                sink.push(pack);
+            }
         }
-    };
+    }
 
     enum Sink<'k> {
         Build(crossbeam::channel::Sender<(usize, &'k Krate, Pack)>),
@@ -516,15 +1779,24 @@ pub fn check(
 
             for (i, krate) in ctx.krates.krates().enumerate() {
                 let mut pack = Pack::with_kid(Check::Bans, krate.id.clone());
+                let matchable = is_matchable(krate);
+
+                // A crate isn't banned if every workspace member it is
+                // reachable from exempts it
+                let exempted = member_overrides
+                    .exempted(i, krate)
+                    .filter(|_| matchable && denied_ids.matches(krate).is_some());
+
+                if let Some(exempt_cfg) = exempted {
+                    pack.push(diags::ExplicitlyAllowed {
+                        krate,
+                        allow_cfg: exempt_cfg,
+                    });
+                }
 
                 // Check if the crate has been explicitly banned
-                if let Some(matches) = denied_ids.matches(krate) {
-                    for rm in matches {
-                        let ban_cfg = CfgCoord {
-                            file: file_id,
-                            span: rm.specr.spec.name.span,
-                        };
-
+                if let Some(matches) = denied_ids.matches(krate).filter(|_| exempted.is_none()) {
+                    for rm in matches.into_iter().filter(|rm| applies_to(rm.index, i)) {
                         // The crate is banned, but it might be allowed if it's
                         // wrapped by one or more particular crates
                         let is_allowed_by_wrapper = if ban_wrappers.has_wrappers(rm.index) {
@@ -540,9 +1812,9 @@ pub fn check(
                                     match ban_wrappers.check(rm.index, &src.krate.name) {
                                         Some(span) => (
                                             diags::BannedAllowedByWrapper {
-                                                ban_cfg: ban_cfg.clone(),
+                                                ban_cfg: rm.specr,
                                                 ban_exception_cfg: CfgCoord {
-                                                    file: file_id,
+                                                    file: rm.specr.file_id,
                                                     span,
                                                 },
                                                 banned_krate: krate,
@@ -562,7 +1834,9 @@ pub fn check(
                                         ),
                                     };
 
-                                pack.push(diag);
+                                if !is_allowed || wrapper_notes {
+                                    pack.push(diag);
+                                }
                                 all = all && is_allowed;
                             }
 
@@ -572,19 +1846,51 @@ pub fn check(
                         };
 
                         if !is_allowed_by_wrapper {
-                            pack.push(diags::ExplicitlyBanned {
-                                krate,
-                                ban_cfg: rm.specr,
-                            });
+                            findings.ban(krate, rm.specr);
+
+                            if let Some(flood) = glob_floods.get_mut(&rm.index) {
+                                flood.push(krate);
+                            } else {
+                                pack.push(diags::ExplicitlyBanned {
+                                    krate,
+                                    ban_cfg: rm.specr,
+                                    fixes: dominators.as_ref().filter(|_| removal_notes).map_or(
+                                        Vec::new(),
+                                        |doms| {
+                                            doms.dominating_edges(ctx.krates, i)
+                                                .into_iter()
+                                                .map(|(parent, dep)| {
+                                                    (&ctx.krates[parent], &ctx.krates[dep])
+                                                })
+                                                .collect()
+                                        },
+                                    ),
+                                    enabled_by: optional_enablers(ctx.krates, i),
+                                });
+                            }
                         }
                     }
                 }
 
-                if !allowed.0.is_empty() {
+                if let Some(ban_cfg) = member_overrides.denied(i, krate).filter(|_| matchable) {
+                    findings.ban(krate, ban_cfg);
+                    pack.push(diags::ExplicitlyBanned {
+                        krate,
+                        ban_cfg,
+                        fixes: Vec::new(),
+                        enabled_by: optional_enablers(ctx.krates, i),
+                    });
+                }
+
+                if matchable && (!allowed.specs.0.is_empty() || allow_exact) {
                     // Since only allowing specific crates is pretty draconian,
                     // also emit which allow filters actually passed each crate
                     match allowed.matches(krate) {
                         Some(matches) => {
+                            if allow_exact && !matches.iter().any(|rm| is_exact(&rm.specr.spec)) {
+                                unapproved.push(krate);
+                            }
+
                             for rm in matches {
                                 pack.push(diags::ExplicitlyAllowed {
                                     krate,
@@ -592,6 +1898,9 @@ pub fn check(
                                 });
                             }
                         }
+                        None if allow_exact => {
+                            unapproved.push(krate);
+                        }
                         None => {
                             pack.push(diags::NotAllowed { krate });
                         }
@@ -601,13 +1910,7 @@ pub fn check(
                 let enabled_features = ctx.krates.get_enabled_features(&krate.id).unwrap();
 
                 let default_lint_level = if enabled_features.contains("default") {
-                    if ctx.krates.workspace_members().any(|n| {
-                        if let krates::Node::Krate { id, .. } = n {
-                            id == &krate.id
-                        } else {
-                            false
-                        }
-                    }) {
+                    if is_workspace_member(ctx.krates, &krate.id) {
                         workspace_default_features.as_ref()
                     } else {
                         external_default_features.as_ref()
@@ -813,8 +2116,71 @@ pub fn check(
                     }
                 }
 
+                // Crates that link a native library introduce a system
+                // dependency, even if everything else is pure Rust
+                if links != LintLevel::Allow {
+                    if let Some(native) = &krate.links {
+                        pack.push(diags::LinksNativeLibrary {
+                            krate,
+                            krate_coord: krate_spans.get_coord(i),
+                            links: native,
+                            severity: links.into(),
+                        });
+                    }
+                }
+
+                // Pre-release versions make no stability guarantees, and are
+                // easily pulled in transitively without anyone noticing
+                if prerelease != LintLevel::Allow
+                    && !krate.version.pre.is_empty()
+                    && !prerelease_exceptions
+                        .iter()
+                        .any(|spec| crate::match_krate(krate, spec))
+                {
+                    pack.push(diags::Prerelease {
+                        krate,
+                        krate_coord: krate_spans.get_coord(i),
+                        severity: prerelease.into(),
+                    });
+                }
+
+                if let Some((mud, doms)) = max_unique_dependencies.zip(dominators.as_ref()) {
+                    let count = doms.dominated(i);
+
+                    if count > mud.limit.value && !is_workspace_member(ctx.krates, &krate.id) {
+                        pack.push(diags::TooManyUniqueDependencies {
+                            krate,
+                            count,
+                            limit_cfg: CfgCoord {
+                                file: file_id,
+                                span: mud.limit.span,
+                            },
+                            limit: mud.limit.value,
+                            severity: mud.level.into(),
+                            heaviest: doms
+                                .heaviest(i)
+                                .take(3)
+                                .map(|(di, count)| (&ctx.krates[di], count))
+                                .collect(),
+                        });
+                    }
+                }
+
                 if should_add_dupe(&krate.id) {
-                    if let Some(matches) = skipped.matches(krate) {
+                    let skip_matches =
+                        skipped
+                            .matches(krate)
+                            .filter(|_| matchable)
+                            .and_then(|mut matches| {
+                                matches.retain(|rm| {
+                                    skip_outside[rm.index].as_ref().map_or(true, |outside| {
+                                        !outside.contains(&krates::NodeId::new(i))
+                                    })
+                                });
+                                (!matches.is_empty()).then_some(matches)
+                            });
+
+                    if let Some(matches) = skip_matches {
                         for rm in matches {
                             pack.push(diags::Skipped {
                                 krate,
@@ -826,15 +2192,31 @@ pub fn check(
                             // their dependency graph changes over time
                             skip_hit.as_mut_bitslice().set(rm.index, true);
                         }
+                    } else if let Some(skip_cfg) =
+                        member_overrides.skipped(i, krate).filter(|_| matchable)
+                    {
+                        pack.push(diags::Skipped { krate, skip_cfg });
                     } else if !tree_skipper.matches(krate, &mut pack) {
-                        if multi_detector.name != krate.name {
-                            report_duplicates(&multi_detector, &mut sink);
+                        // Crates from skipped sources, eg. a vendored mirror,
+                        // or that have been replaced by a patch, don't count
+                        // towards duplicates, but are otherwise still checked
+                        if !skip_sources
+                            .iter()
+                            .any(|src| krate.matches_url(&src.value, false))
+                            && !replaced_by_patch.contains(&i)
+                        {
+                            if multi_detector.name != krate.name {
+                                if report_duplicates(&multi_detector, &mut sink) {
+                                    num_duplicates += 1;
+                                    findings.duplicate(ctx.krates, &multi_detector.dupes);
+                                }
 
-                            multi_detector.name = &krate.name;
-                            multi_detector.dupes.clear();
-                        }
+                                multi_detector.name = &krate.name;
+                                multi_detector.dupes.clear();
+                            }
 
-                        multi_detector.dupes.push(i);
+                            multi_detector.dupes.push(i);
+                        }
 
                         if wildcards != LintLevel::Allow && !krate.is_git_source() {
                             let severity = match wildcards {
@@ -877,8 +2259,9 @@ pub fn check(
                     }
                 }
 
-                if i == last {
-                    report_duplicates(&multi_detector, &mut sink);
+                if i == last && report_duplicates(&multi_detector, &mut sink) {
+                    num_duplicates += 1;
+                    findings.duplicate(ctx.krates, &multi_detector.dupes);
                 }
 
                 tx.push(i, krate, pack);
@@ -966,24 +2349,70 @@ pub fn check(
 
     let mut pack = Pack::new(Check::Bans);
 
+    for spec in required.iter().filter(|spec| {
+        !ctx.krates
+            .krates()
+            .any(|krate| crate::match_krate(krate, spec))
+    }) {
+        pack.push(diags::RequiredNotFound { spec, file_id });
+    }
+
+    if let Some(md) = &max_duplicates {
+        pack.push(diags::DuplicateBudget {
+            count: num_duplicates,
+            limit_cfg: CfgCoord {
+                file: file_id,
+                span: md.limit.span,
+            },
+            limit: md.limit.value,
+            severity: md.level.into(),
+        });
+    }
+
+    if skip_tree_usage != LintLevel::Allow {
+        tree_skipper.report_usage(skip_tree_usage.into(), &mut pack);
+    }
+
     for skip in skip_hit
         .into_iter()
-        .zip(skipped.0.into_iter())
+        .zip(skipped.specs.0.into_iter())
         .filter_map(|(hit, skip)| (!hit).then_some(skip))
     {
         pack.push(diags::UnmatchedSkip { skip_cfg: &skip });
     }
 
+    if !unapproved.is_empty() {
+        pack.push(diags::Unapproved { krates: unapproved });
+    }
+
+    if let Some(limit) = &max_glob_matches {
+        for (index, krates) in glob_floods.into_iter().filter(|(_, k)| !k.is_empty()) {
+            pack.push(diags::BannedByGlob {
+                ban_cfg: &denied_ids.specs.0[index],
+                krates,
+                limit_cfg: CfgCoord {
+                    file: file_id,
+                    span: limit.limit.span,
+                },
+                limit: limit.limit.value,
+                severity: limit.level.into(),
+            });
+        }
+    }
+
     for wrapper in ban_wrappers
         .hits
         .into_iter()
-        .zip(ban_wrappers.map.into_values().flat_map(|(_, w)| w))
+        .zip(ban_wrappers.map.into_iter().flat_map(|(i, (_, w))| {
+            let file = denied_ids.specs.0[i].file_id;
+            w.into_iter().map(move |w| (file, w))
+        }))
         .filter_map(|(hit, wrapper)| (!hit).then_some(wrapper))
     {
         pack.push(diags::UnusedWrapper {
             wrapper_cfg: CfgCoord {
-                file: file_id,
-                span: wrapper.span,
+                file: wrapper.0,
+                span: wrapper.1.span,
             },
         });
     }
@@ -1009,11 +2438,32 @@ pub fn check_build(
             || allow_build_scripts
                 .iter()
                 .any(|id| crate::match_krate(krate, id))
-    } else {
-        true
-    };
+    } else {
+        true
+    };
+
+    if let Some(allow_proc_macros) = &config.allow_proc_macros {
+        let is_proc_macro = krate
+            .targets
+            .iter()
+            .any(|t| t.kind.iter().any(|k| *k == "proc-macro"));
+
+        if is_proc_macro
+            && !allow_proc_macros
+                .iter()
+                .any(|id| crate::match_krate(krate, id))
+        {
+            pack.push(diags::ProcMacroNotAllowed {
+                krate,
+                severity: config.proc_macros.into(),
+            });
+        }
+    }
 
-    if build_script_allowed && config.executables == LintLevel::Allow {
+    if build_script_allowed
+        && config.executables == LintLevel::Allow
+        && config.deny_globs.patterns.is_empty()
+    {
         return None;
     }
 
@@ -1041,18 +2491,12 @@ pub fn check_build(
     }
 
     // Check if the krate is either a proc-macro, has a build-script, OR is a dependency
-    // of a crate that is/does
-    if !config.include_workspace
-        && krates.workspace_members().any(|n| {
-            if let krates::Node::Krate { id, .. } = n {
-                id == &krate.id
-            } else {
-                false
-            }
-        })
-        || (!config.include_dependencies && !executes_at_buildtime(krate))
-        || (config.include_dependencies
-            && !needs_checking(krates.nid_for_kid(&krate.id).unwrap(), krates))
+    // of a crate that is/does, unless every crate is being checked
+    if !config.include_workspace && is_workspace_member(krates, &krate.id)
+        || (!config.include_all
+            && ((!config.include_dependencies && !executes_at_buildtime(krate))
+                || (config.include_dependencies
+                    && !needs_checking(krates.nid_for_kid(&krate.id).unwrap(), krates))))
     {
         return None;
     }
@@ -1239,6 +2683,15 @@ pub fn check_build(
                     }
                 }
 
+                if let Some(globs) = config.deny_globs.matches(&candidate, &mut matches) {
+                    pack.push(diags::DeniedByGlob {
+                        path: diags::HomePath { path, root, home },
+                        globs,
+                        file_id,
+                    });
+                    continue;
+                }
+
                 // Check if the file matches a disallowed glob pattern
                 if let Some(globs) = config.script_extensions.matches(&candidate, &mut matches) {
                     pack.push(diags::DeniedByExtension {
@@ -1436,7 +2889,7 @@ fn check_is_executable(
 /// Validates the buffer matches the expected SHA-256 checksum
 fn validate_checksum(
     mut stream: impl std::io::Read,
-    expected: &cfg::Checksum,
+    expected: &crate::cfg::Checksum,
 ) -> anyhow::Result<()> {
     let digest = {
         let mut dc = ring::digest::Context::new(&ring::digest::SHA256);
@@ -1469,16 +2922,677 @@ fn validate_checksum(
 }
 
 #[inline]
-fn validate_file_checksum(path: &crate::Path, expected: &cfg::Checksum) -> anyhow::Result<()> {
+fn validate_file_checksum(
+    path: &crate::Path,
+    expected: &crate::cfg::Checksum,
+) -> anyhow::Result<()> {
     let file = std::fs::File::open(path)?;
     validate_checksum(std::io::BufReader::new(file), expected)?;
     Ok(())
 }
 
+fn is_member_name(krates: &Krates, name: &str) -> bool {
+    krates.workspace_members().any(|n| {
+        if let krates::Node::Krate { krate, .. } = n {
+            krate.name == name
+        } else {
+            false
+        }
+    })
+}
+
+/// Finds the crates whose shortest path from a workspace member is longer than
+/// `limit` edges, returning that path, starting at the workspace member
+///
+/// Only the crates that are the first to exceed the limit on their path are
+/// returned, as every crate that is even deeper has one of them as an ancestor
+fn too_deep(krates: &Krates, limit: usize) -> Vec<Vec<krates::NodeId>> {
+    use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+    // The parent of each crate on its shortest path from a workspace member
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    for node in krates.workspace_members() {
+        let krates::Node::Krate { id, .. } = node else {
+            continue;
+        };
+
+        if let Some(nid) = krates.nid_for_kid(id) {
+            if parents.insert(nid, None).is_none() {
+                queue.push_back((nid, 0));
+            }
+        }
+    }
+
+    let mut deep = Vec::new();
+
+    while let Some((nid, depth)) = queue.pop_front() {
+        if depth > limit {
+            let mut path = vec![nid];
+            let mut cur = nid;
+            while let Some(Some(parent)) = parents.get(&cur) {
+                path.push(*parent);
+                cur = *parent;
+            }
+
+            path.reverse();
+            deep.push(path);
+            continue;
+        }
+
+        for dep in krates.direct_dependencies(nid) {
+            if let Entry::Vacant(entry) = parents.entry(dep.node_id) {
+                entry.insert(Some(nid));
+                queue.push_back((dep.node_id, depth + 1));
+            }
+        }
+    }
+
+    deep.sort_by_key(|path| path.last().map(|nid| nid.index()));
+    deep
+}
+
+/// Gets the total size of the files in a crate's unpacked source
+///
+/// Crates from git sources are unpacked as part of the entire checkout, so the
+/// `.git` directory, and any nested packages, eg. the other crates in the same
+/// repository, are not counted towards the size of the crate
+fn unpacked_size(root: &crate::Path) -> u64 {
+    walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            !entry.file_type().is_dir()
+                || entry.depth() == 0
+                || (entry.file_name() != ".git" && !entry.path().join("Cargo.toml").exists())
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|md| md.len())
+        .sum()
+}
+
+/// Checks if the spec only matches a single, exact, version
+fn is_exact(spec: &PackageSpec) -> bool {
+    spec.version_req.as_ref().map_or(false, |req| {
+        matches!(req.comparators.as_slice(), [cmp] if cmp.op == semver::Op::Exact
+            && cmp.minor.is_some()
+            && cmp.patch.is_some())
+    })
+}
+
+/// Gets the kinds of dependency each crate is reached as from the workspace,
+/// indexed by the kind
+fn dependency_kinds(krates: &Krates) -> Vec<[bool; 3]> {
+    use cfg::EdgeKind;
+
+    let mut kinds = vec![[false; 3]; krates.len()];
+    let mut pending: Vec<_> = krates
+        .workspace_members()
+        .filter_map(|member| {
+            if let krates::Node::Krate { id, .. } = member {
+                krates.nid_for_kid(id).map(|nid| (nid, EdgeKind::Normal))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    while let Some((nid, kind)) = pending.pop() {
+        let seen = &mut kinds[nid.index()][kind as usize];
+        if *seen {
+            continue;
+        }
+        *seen = true;
+
+        for dep in krates.direct_dependencies(nid) {
+            // Once a crate is reached through a dev or build dependency,
+            // everything it depends on is as well
+            pending.push((dep.node_id, kind.max(edge_kind(krates, dep.edge_id))));
+        }
+    }
+
+    kinds
+}
+
+/// Describes each of the edges that directly enable the feature of a crate,
+/// either the dependency declaration of a parent crate, or another feature
+fn feature_enablers(krates: &Krates, nid: krates::NodeId, feature: &str) -> Vec<String> {
+    use krates::petgraph::{visit::EdgeRef, Direction};
+
+    let graph = krates.graph();
+    let Some(feature_nid) = graph.node_indices().find(|fnid| {
+        matches!(&graph[*fnid], krates::Node::Feature { krate_index, name } if *krate_index == nid && name == feature)
+    }) else {
+        return Vec::new();
+    };
+
+    graph
+        .edges_directed(feature_nid, Direction::Incoming)
+        .map(|edge| match &graph[edge.source()] {
+            krates::Node::Krate { krate, .. } => {
+                format!("enabled by the dependency declaration of '{krate}'")
+            }
+            krates::Node::Feature { krate_index, name } => {
+                format!(
+                    "enabled by the '{name}' feature of '{}'",
+                    krates[*krate_index]
+                )
+            }
+        })
+        .collect()
+}
+
+/// Gets the parents that depend on a crate via an optional dependency, along
+/// with each of the parent's features that lead to the dependency being enabled
+fn optional_enablers(krates: &Krates, i: usize) -> Vec<(&Krate, Vec<String>)> {
+    use krates::petgraph::{visit::EdgeRef, Direction};
+
+    let graph = krates.graph();
+    let nid = krates::NodeId::new(i);
+
+    let mut enablers: Vec<(&Krate, Vec<String>)> = Vec::new();
+    for edge in graph.edges_directed(nid, Direction::Incoming) {
+        let krates::Node::Feature { krate_index, .. } = &graph[edge.source()] else {
+            continue;
+        };
+
+        // Features of the crate itself aren't dependency edges
+        if *krate_index == nid {
+            continue;
+        }
+
+        // Walk up every feature of the parent that leads to the dependency
+        let mut features = Vec::new();
+        let mut visited = std::collections::HashSet::new();
+        let mut stack = vec![edge.source()];
+        while let Some(fnid) = stack.pop() {
+            if !visited.insert(fnid) {
+                continue;
+            }
+
+            let krates::Node::Feature { name, .. } = &graph[fnid] else {
+                continue;
+            };
+
+            if !name.starts_with("dep:") {
+                features.push(name.clone());
+            }
+
+            stack.extend(
+                graph
+                    .edges_directed(fnid, Direction::Incoming)
+                    .map(|e| e.source())
+                    .filter(|src| {
+                        matches!(&graph[*src], krates::Node::Feature { krate_index: ki, .. } if ki == krate_index)
+                    }),
+            );
+        }
+
+        if features.is_empty() {
+            continue;
+        }
+
+        features.sort();
+        features.dedup();
+
+        let parent = &krates[*krate_index];
+        if let Some(existing) = enablers.iter_mut().find(|(p, _)| p.id == parent.id) {
+            existing.1.extend(features);
+            existing.1.sort();
+            existing.1.dedup();
+        } else {
+            enablers.push((parent, features));
+        }
+    }
+
+    enablers.sort_by(|a, b| a.0.id.cmp(&b.0.id));
+    enablers
+}
+
+/// Normalizes a crate name for comparison, as `-` and `_` are interchangeable
+/// when referring to a crate
+fn normalize_crate_name(name: &str) -> String {
+    name.to_ascii_lowercase().replace('-', "_")
+}
+
+/// The optimal string alignment distance between two strings, ie. the number
+/// of insertions, deletions, substitutions, and transpositions of adjacent
+/// characters needed to turn one into the other
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<_> = a.chars().collect();
+    let b: Vec<_> = b.chars().collect();
+
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(dist[i - 2][j - 2] + 1);
+            }
+
+            dist[i][j] = d;
+        }
+    }
+
+    dist[a.len()][b.len()]
+}
+
+fn edge_kind(krates: &Krates, edge_id: krates::EdgeId) -> cfg::EdgeKind {
+    use cfg::EdgeKind;
+
+    match krates.graph()[edge_id] {
+        krates::Edge::Dep { kind, .. } | krates::Edge::DepFeature { kind, .. } => match kind {
+            krates::DepKind::Normal => EdgeKind::Normal,
+            krates::DepKind::Build => EdgeKind::Build,
+            krates::DepKind::Dev => EdgeKind::Dev,
+        },
+        krates::Edge::Feature => EdgeKind::Normal,
+    }
+}
+
+/// Finds the crates that are reachable as both normal and build dependencies,
+/// returning the shortest path from a workspace member to the crate as a
+/// normal dependency, and as a build dependency
+fn built_twice(krates: &Krates) -> Vec<(Vec<krates::NodeId>, Vec<krates::NodeId>)> {
+    use cfg::EdgeKind;
+
+    type Parent = Option<(krates::NodeId, EdgeKind)>;
+
+    // The node and kind that each node and kind was first reached from, the
+    // graph is walked breadth first so that the paths are the shortest ones
+    let mut parents = vec![[None::<Parent>; 3]; krates.len()];
+    let mut pending = std::collections::VecDeque::new();
+
+    for member in krates.workspace_members() {
+        if let krates::Node::Krate { id, .. } = member {
+            if let Some(nid) = krates.nid_for_kid(id) {
+                parents[nid.index()][EdgeKind::Normal as usize] = Some(None);
+                pending.push_back((nid, EdgeKind::Normal));
+            }
+        }
+    }
+
+    while let Some((nid, kind)) = pending.pop_front() {
+        for dep in krates.direct_dependencies(nid) {
+            let dep_kind = kind.max(edge_kind(krates, dep.edge_id));
+            let parent = &mut parents[dep.node_id.index()][dep_kind as usize];
+            if parent.is_none() {
+                *parent = Some(Some((nid, kind)));
+                pending.push_back((dep.node_id, dep_kind));
+            }
+        }
+    }
+
+    let path = |nid: krates::NodeId, kind: EdgeKind| {
+        let mut path = vec![nid];
+        let mut cur = (nid, kind);
+        while let Some(Some(parent)) = parents[cur.0.index()][cur.1 as usize] {
+            path.push(parent.0);
+            cur = parent;
+        }
+        path.reverse();
+        path
+    };
+
+    (0..krates.len())
+        .filter(|i| {
+            parents[*i][EdgeKind::Normal as usize].is_some()
+                && parents[*i][EdgeKind::Build as usize].is_some()
+        })
+        .map(|i| {
+            let nid = krates::NodeId::new(i);
+            (path(nid, EdgeKind::Normal), path(nid, EdgeKind::Build))
+        })
+        .collect()
+}
+
+/// Gets the crates that can be reached from a workspace member without going
+/// through a crate with the specified name
+fn reachable_outside(krates: &Krates, name: &str) -> std::collections::HashSet<krates::NodeId> {
+    let mut stack: Vec<_> = krates
+        .workspace_members()
+        .filter_map(|node| match node {
+            krates::Node::Krate { id, krate, .. } if krate.name != name => krates.nid_for_kid(id),
+            _ => None,
+        })
+        .collect();
+    let mut reached: std::collections::HashSet<_> = stack.iter().copied().collect();
+
+    while let Some(nid) = stack.pop() {
+        for dep in krates.direct_dependencies(nid) {
+            if krates[dep.node_id.index()].name != name && reached.insert(dep.node_id) {
+                stack.push(dep.node_id);
+            }
+        }
+    }
+
+    reached
+}
+
+/// Gets the crates that are built for the specified target, ie. that are
+/// reachable from the workspace through dependencies that are either not
+/// target specific, or whose target matches
+fn built_for_target(krates: &Krates, target: &krates::cfg_expr::targets::TargetInfo) -> Vec<bool> {
+    use krates::cfg_expr::{Expression, Predicate};
+
+    let matches = |cfg: &str| {
+        if cfg.starts_with("cfg(") {
+            Expression::parse(cfg).map_or(true, |expr| {
+                expr.eval(|pred| match pred {
+                    Predicate::Target(tp) => tp.matches(target),
+                    _ => false,
+                })
+            })
+        } else {
+            cfg == target.triple.as_str()
+        }
+    };
+
+    let mut built = vec![false; krates.len()];
+    let mut pending: Vec<_> = krates
+        .workspace_members()
+        .filter_map(|member| {
+            if let krates::Node::Krate { id, .. } = member {
+                krates.nid_for_kid(id)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    while let Some(nid) = pending.pop() {
+        if std::mem::replace(&mut built[nid.index()], true) {
+            continue;
+        }
+
+        for dep in krates.direct_dependencies(nid) {
+            let cfg = match &krates.graph()[dep.edge_id] {
+                krates::Edge::Dep { cfg, .. } | krates::Edge::DepFeature { cfg, .. } => {
+                    cfg.as_deref()
+                }
+                krates::Edge::Feature => None,
+            };
+
+            if cfg.map_or(true, matches) {
+                pending.push(dep.node_id);
+            }
+        }
+    }
+
+    built
+}
+
+/// Groups the crates that wrap a native library by the library they wrap
+///
+/// The library is the crate's `links` value if it has one, otherwise the name
+/// of a `*-sys` crate without the suffix, normalized so that eg. `libz-sys`
+/// and a crate with `links = "z"` are grouped together
+fn native_libraries(krates: &Krates) -> std::collections::BTreeMap<String, Vec<&Krate>> {
+    let mut libraries = std::collections::BTreeMap::<String, Vec<&Krate>>::new();
+
+    for krate in krates.krates() {
+        let Some(library) = krate
+            .links
+            .as_deref()
+            .or_else(|| krate.name.strip_suffix("-sys"))
+        else {
+            continue;
+        };
+
+        let library = library.to_lowercase().replace('_', "-");
+        let library = library
+            .strip_prefix("lib")
+            .filter(|lib| !lib.is_empty())
+            .unwrap_or(&library)
+            .to_owned();
+
+        libraries.entry(library).or_default().push(krate);
+    }
+
+    libraries
+}
+
+/// Gets the crates that were resolved through a `[replace]` entry in the
+/// specified workspace manifest, along with the key of the entry
+///
+/// The keys are package id specs, eg. `foo:0.1.0`, optionally prefixed with
+/// the url of the source, eg. `https://github.com/rust-lang/crates.io-index#foo:0.1.0`
+fn replaced_krates(krates: &Krates, manifest: &crate::Path) -> Vec<(usize, String)> {
+    let Some(contents) = read_manifest(manifest) else {
+        return Vec::new();
+    };
+
+    let root = match toml_span::parse(&contents) {
+        Ok(root) => root,
+        Err(err) => {
+            log::warn!("unable to parse manifest '{manifest}': {err}");
+            return Vec::new();
+        }
+    };
+
+    let Some(replace) = manifest_table(&root, &["replace"]) else {
+        return Vec::new();
+    };
+
+    let mut replaced = Vec::new();
+
+    for key in replace.keys() {
+        let spec = key
+            .name
+            .rsplit_once('#')
+            .map_or(&*key.name, |(_, spec)| spec);
+        let (name, version) = match spec.split_once([':', '@']) {
+            Some((name, version)) => (name, Some(version)),
+            None => (spec, None),
+        };
+
+        // The replacement is a git or path crate that takes the place of the
+        // registry crate matching the entry
+        replaced.extend(
+            krates
+                .krates()
+                .enumerate()
+                .filter(|(_, krate)| {
+                    krate.name == name
+                        && version.map_or(true, |v| krate.version.to_string() == v)
+                        && !krate.source.as_ref().map_or(false, |src| src.is_registry())
+                })
+                .map(|(i, _)| (i, key.name.to_string())),
+        );
+    }
+
+    replaced
+}
+
+/// Gets the dependencies that workspace members declare themselves, even
+/// though they are declared in `[workspace.dependencies]` and could be
+/// inherited instead
+fn not_inherited(krates: &Krates) -> Vec<(&Krate, String)> {
+    const DEP_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+    let root_manifest = krates.workspace_root().join("Cargo.toml");
+    let Some(contents) = read_manifest(&root_manifest) else {
+        return Vec::new();
+    };
+
+    let workspace_deps: Vec<_> = match toml_span::parse(&contents) {
+        Ok(root) => manifest_table(&root, &["workspace", "dependencies"])
+            .map(|deps| deps.keys().map(|key| key.name.to_string()).collect())
+            .unwrap_or_default(),
+        Err(err) => {
+            log::warn!("unable to parse manifest '{root_manifest}': {err}");
+            return Vec::new();
+        }
+    };
+
+    if workspace_deps.is_empty() {
+        return Vec::new();
+    }
+
+    let mut not_inherited = Vec::new();
+
+    for node in krates.workspace_members() {
+        let krates::Node::Krate { krate, .. } = node else {
+            continue;
+        };
+
+        let Some(contents) = read_manifest(&krate.manifest_path) else {
+            continue;
+        };
+
+        let member = match toml_span::parse(&contents) {
+            Ok(member) => member,
+            Err(err) => {
+                log::warn!("unable to parse manifest '{}': {err}", krate.manifest_path);
+                continue;
+            }
+        };
+
+        // Dependencies can also be declared for specific targets
+        let mut tables: Vec<_> = DEP_TABLES
+            .iter()
+            .filter_map(|name| manifest_table(&member, &[*name]))
+            .collect();
+        if let Some(targets) = manifest_table(&member, &["target"]) {
+            for target in targets.values() {
+                tables.extend(
+                    DEP_TABLES
+                        .iter()
+                        .filter_map(|name| manifest_table(target, &[*name])),
+                );
+            }
+        }
+
+        for (key, value) in tables.into_iter().flatten() {
+            if !workspace_deps.iter().any(|wd| *wd == key.name) {
+                continue;
+            }
+
+            let inherited = value
+                .as_table()
+                .and_then(|dep| dep.iter().find(|(key, _)| key.name == "workspace"))
+                .and_then(|(_, ws)| ws.as_bool())
+                .unwrap_or(false);
+
+            if !inherited {
+                not_inherited.push((krate, key.name.to_string()));
+            }
+        }
+    }
+
+    not_inherited
+}
+
+/// Reads the contents of a manifest, logging a warning if it can't be read
+fn read_manifest(manifest: &crate::Path) -> Option<String> {
+    std::fs::read_to_string(manifest)
+        .map_err(|err| {
+            log::warn!("unable to read manifest '{manifest}': {err}");
+        })
+        .ok()
+}
+
+/// Gets the table at the specified path of keys in a manifest
+fn manifest_table<'v, 'de>(
+    value: &'v toml_span::value::Value<'de>,
+    path: &[&str],
+) -> Option<&'v toml_span::value::Table<'de>> {
+    path.iter().try_fold(value.as_table()?, |table, name| {
+        table
+            .iter()
+            .find(|(key, _)| key.name == *name)
+            .and_then(|(_, value)| value.as_table())
+    })
+}
+
+/// Gets the registry dependencies on the crate at the specified index that
+/// resolved to it instead, along with the crate that declared each of them
+///
+/// For a git or path crate, these are the dependencies that were redirected to
+/// it via `[patch]`
+fn patched_deps(krates: &Krates, i: usize) -> Vec<(&Krate, &krates::cm::Dependency)> {
+    let krate = &krates[i];
+
+    krates
+        .direct_dependents(krates::NodeId::new(i))
+        .into_iter()
+        .flat_map(|dd| {
+            let dependent = dd.krate;
+
+            dependent
+                .deps
+                .iter()
+                .filter(|dep| {
+                    dep.name == krate.name
+                        && dep.source.as_ref().is_some_and(|src| {
+                            src.starts_with("registry+") || src.starts_with("sparse+")
+                        })
+                })
+                .map(move |dep| (dependent, dep))
+        })
+        .collect()
+}
+
+/// Gets the registry crates that have been replaced via `[patch]`
+///
+/// A crate is patched if one of its dependents declared a registry dependency
+/// on it, but it resolved to a git or path crate instead. The registry versions
+/// that also match the requirements of the patched dependencies are the ones
+/// the patch is replacing, even if they are still in the graph due to other
+/// crates that the patch does not apply to
+fn patched_originals(krates: &Krates) -> std::collections::HashSet<usize> {
+    let is_registry = |krate: &Krate| krate.source.as_ref().map_or(false, |src| src.is_registry());
+
+    let mut replaced = std::collections::HashSet::new();
+
+    for (i, krate) in krates.krates().enumerate() {
+        if is_registry(krate) {
+            continue;
+        }
+
+        let reqs: Vec<_> = patched_deps(krates, i)
+            .into_iter()
+            .map(|(_, dep)| &dep.req)
+            .collect();
+
+        if reqs.is_empty() {
+            continue;
+        }
+
+        replaced.extend(
+            krates
+                .krates()
+                .enumerate()
+                .filter(|(_, original)| {
+                    original.name == krate.name
+                        && is_registry(original)
+                        && reqs.iter().any(|req| req.matches(&original.version))
+                })
+                .map(|(j, _)| j),
+        );
+    }
+
+    replaced
+}
+
 /// Returns true if the dependency has a `path` or `git` source.
 ///
 /// TODO: Possibly what we actually care about, where this is used in the wildcard check, is
 /// “is not using any registry source”.
+#[inline]
 fn is_path_or_git_dependency(dep: &krates::cm::Dependency) -> bool {
     dep.path.is_some()
         || dep
@@ -1486,3 +3600,29 @@ fn is_path_or_git_dependency(dep: &krates::cm::Dependency) -> bool {
             .as_ref()
             .is_some_and(|url| url.starts_with("git+"))
 }
+
+#[cfg(test)]
+mod test {
+    use super::unpacked_size;
+
+    #[test]
+    fn unpacked_size_ignores_git_and_nested_packages() {
+        let root = tempfile::tempdir().unwrap();
+        let root = crate::Path::from_path(root.path()).unwrap();
+
+        let write = |path: &str, len: usize| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, vec![b'a'; len]).unwrap();
+        };
+
+        write("Cargo.toml", 10);
+        write("src/lib.rs", 20);
+        write(".git/objects/pack", 1000);
+        write("crates/sibling/Cargo.toml", 100);
+        write("crates/sibling/src/lib.rs", 100);
+        write("crates/assets/data.bin", 5);
+
+        assert_eq!(unpacked_size(root), 35);
+    }
+}
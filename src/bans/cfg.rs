@@ -1,6 +1,7 @@
 use crate::{
-    cfg::{PackageSpec, PackageSpecOrExtended, Reason, ValidationContext},
+    cfg::{Checksum, Expires, PackageSpec, PackageSpecOrExtended, Reason, ValidationContext},
     diag::{Diagnostic, FileId, Label},
+    sources::cfg::UrlSpan,
     LintLevel, Spanned,
 };
 use toml_span::{de_helpers::TableHelper, value::Value, DeserError, Deserialize};
@@ -18,6 +19,23 @@ pub struct CrateBanExtended {
     /// The crate to use instead of the banned crate, could be just the crate name
     /// or a URL
     pub use_instead: Option<Spanned<String>>,
+    /// Only bans crates tagged with one of these categories
+    pub categories: Vec<Spanned<String>>,
+    /// Only bans crates tagged with one of these keywords
+    pub keywords: Vec<Spanned<String>>,
+    /// The date after which the ban no longer takes effect
+    pub expires: Option<Expires>,
+    /// Only bans the crate when it is reached as one of these kinds of
+    /// dependency
+    pub kinds: Vec<EdgeKind>,
+    /// Only bans the crate when its default features are, or are not,
+    /// enabled
+    pub default_features: Option<bool>,
+    /// Only bans the crate when it is built for one of these targets, either
+    /// a target triple or a `cfg()` expression
+    pub targets: Vec<Spanned<String>>,
+    /// Crates that match the entry, but are approved and thus not banned
+    pub exempt: Vec<PackageSpec>,
 }
 
 impl<'de> Deserialize<'de> for CrateBanExtended {
@@ -28,6 +46,13 @@ impl<'de> Deserialize<'de> for CrateBanExtended {
         let deny_multiple_versions = th.optional("deny-multiple-versions");
         let reason = th.optional_s("reason");
         let use_instead = th.optional("use-instead");
+        let categories = th.optional("categories").unwrap_or_default();
+        let keywords = th.optional("keywords").unwrap_or_default();
+        let expires = th.optional("expires");
+        let kinds = th.optional("kinds").unwrap_or_default();
+        let default_features = th.optional("default-features");
+        let targets = th.optional("targets").unwrap_or_default();
+        let exempt = th.optional("exempt").unwrap_or_default();
         th.finalize(None)?;
 
         Ok(Self {
@@ -35,6 +60,60 @@ impl<'de> Deserialize<'de> for CrateBanExtended {
             deny_multiple_versions,
             reason: reason.map(Reason::from),
             use_instead,
+            categories,
+            keywords,
+            expires,
+            kinds,
+            default_features,
+            targets,
+            exempt,
+        })
+    }
+}
+
+/// The kind of dependency a crate is reached as from the workspace
+///
+/// A crate reached through a dev dependency is a dev dependency, even if the
+/// edges after it are normal or build dependencies, and likewise a crate
+/// reached through a build dependency is a build dependency
+#[cfg_attr(test, derive(Debug, serde::Serialize))]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, strum::VariantArray, strum::VariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum EdgeKind {
+    Normal,
+    Build,
+    Dev,
+}
+
+crate::enum_deser!(EdgeKind);
+
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct CrateAllowExtended {
+    /// The reason for allowing the crate
+    pub reason: Option<Reason>,
+    /// Only allows crates tagged with one of these categories
+    pub categories: Vec<Spanned<String>>,
+    /// Only allows crates tagged with one of these keywords
+    pub keywords: Vec<Spanned<String>>,
+    /// Only allows crates from this source
+    pub source: Option<Spanned<String>>,
+}
+
+impl<'de> Deserialize<'de> for CrateAllowExtended {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+
+        let reason = th.optional_s("reason");
+        let categories = th.optional("categories").unwrap_or_default();
+        let keywords = th.optional("keywords").unwrap_or_default();
+        let source = th.optional("source");
+        th.finalize(None)?;
+
+        Ok(Self {
+            reason: reason.map(Reason::from),
+            categories,
+            keywords,
+            source,
         })
     }
 }
@@ -84,6 +163,9 @@ pub enum GraphHighlight {
     SimplestPath,
     /// Highlights the path to the duplicate dependency with the lowest version
     LowestVersion,
+    /// Highlights the path to the duplicate dependency with the highest
+    /// version, which is usually the one the rest of the graph should move to
+    HighestVersion,
     /// Highlights with all of the other configs
     #[default]
     All,
@@ -101,89 +183,28 @@ impl GraphHighlight {
     pub(crate) fn lowest_version(self) -> bool {
         self == Self::LowestVersion || self == Self::All
     }
-}
-
-#[derive(Clone)]
-#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
-pub struct Checksum(pub [u8; 32]);
-
-#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
-pub enum ChecksumParseError {
-    /// The checksum string had an invalid length
-    InvalidLength(usize),
-    /// The checksum string contained a non-hex character
-    InvalidValue(char),
-}
-
-impl std::str::FromStr for Checksum {
-    type Err = ChecksumParseError;
 
-    fn from_str(data: &str) -> Result<Self, Self::Err> {
-        if data.len() != 64 {
-            return Err(ChecksumParseError::InvalidLength(data.len()));
-        }
-
-        let mut array = [0u8; 32];
-
-        for (ind, chunk) in data.as_bytes().chunks(2).enumerate() {
-            #[inline]
-            fn parse_hex(b: u8) -> Result<u8, ChecksumParseError> {
-                Ok(match b {
-                    b'A'..=b'F' => b - b'A' + 10,
-                    b'a'..=b'f' => b - b'a' + 10,
-                    b'0'..=b'9' => b - b'0',
-                    c => {
-                        return Err(ChecksumParseError::InvalidValue(c as char));
-                    }
-                })
-            }
-
-            let mut cur = parse_hex(chunk[0])?;
-            cur <<= 4;
-            cur |= parse_hex(chunk[1])?;
-
-            array[ind] = cur;
-        }
-
-        Ok(Self(array))
+    #[inline]
+    pub(crate) fn highest_version(self) -> bool {
+        self == Self::HighestVersion || self == Self::All
     }
 }
 
-impl<'de> Deserialize<'de> for Checksum {
-    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
-        let val = value.take_string(Some("a sha-256 hex encoded string"))?;
-
-        val.parse().map_err(|err| {
-            let err = match err {
-                ChecksumParseError::InvalidLength(len) => {
-                    toml_span::Error::from((toml_span::ErrorKind::Custom(format!("a sha-256 hex encoded string of length 64 but got a string of length '{len}'").into()), value.span))
-                }
-                ChecksumParseError::InvalidValue(c) => toml_span::Error::from((toml_span::ErrorKind::Unexpected(c), value.span)),
-            };
-            err.into()
-        })
-    }
+/// Which crates with multiple versions are reported as duplicates
+#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(PartialEq, Eq, Copy, Clone, Default, strum::VariantArray, strum::VariantNames)]
+#[strum(serialize_all = "kebab-case")]
+pub enum MultipleVersionsPolicy {
+    /// Any crate with more than one version is a duplicate
+    #[default]
+    All,
+    /// Only crates with versions that are semver incompatible with each other,
+    /// ie. a different major version, or a different minor version pre-1.0,
+    /// are duplicates, as compatible versions can be unified by cargo
+    SemverIncompatible,
 }
 
-#[cfg(test)]
-impl serde::Serialize for Checksum {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let mut hexs = [0; 64];
-
-        const CHARS: &[u8] = b"0123456789abcdef";
-
-        for (i, &byte) in self.0.iter().enumerate() {
-            let i = i * 2;
-            hexs[i] = CHARS[(byte >> 4) as usize];
-            hexs[i + 1] = CHARS[(byte & 0xf) as usize];
-        }
-
-        serializer.serialize_str(std::str::from_utf8(&hexs).unwrap())
-    }
-}
+crate::enum_deser!(MultipleVersionsPolicy);
 
 #[derive(Clone)]
 #[cfg_attr(test, derive(PartialEq, Eq, serde::Serialize))]
@@ -252,6 +273,11 @@ pub struct BuildConfig {
     /// List of crates that are allowed to have build scripts. If this is set,
     /// any crates with a build script that aren't listed here will be banned
     pub allow_build_scripts: Option<Vec<PackageSpec>>,
+    /// List of proc macro crates that are allowed. If this is set, any proc
+    /// macro crates that aren't listed here will be banned
+    pub allow_proc_macros: Option<Vec<PackageSpec>>,
+    /// The lint level for proc macro crates that aren't in `allow_proc_macros`
+    pub proc_macros: LintLevel,
     /// Lint level for when executables are detected within crates with build
     /// scripts or are proc macros, or are a dependency of either of them
     pub executables: LintLevel,
@@ -260,6 +286,8 @@ pub struct BuildConfig {
     /// List of script extensions that are considered to be executable. These
     /// are always in addition to the builtin ones.
     pub script_extensions: Option<Vec<Spanned<String>>>,
+    /// List of glob patterns for paths that are not allowed in crates
+    pub deny_globs: Option<Vec<Spanned<String>>>,
     /// The list of allowed executables, by crate
     pub bypass: Option<Vec<Bypass>>,
     /// If true, enables the built-in glob patterns
@@ -267,6 +295,9 @@ pub struct BuildConfig {
     /// If true, all dependencies of proc macro crates or crates with build
     /// scripts are also checked for executables/glob patterns
     pub include_dependencies: bool,
+    /// If true, every crate is checked for executables/glob patterns, not
+    /// just those that execute at build time
+    pub include_all: bool,
     /// If true, workspace crates are included
     pub include_workspace: bool,
     /// If true, archive files are counted as native executables
@@ -277,24 +308,32 @@ impl<'de> Deserialize<'de> for BuildConfig {
     fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
         let mut th = TableHelper::new(value)?;
         let allow_build_scripts = th.optional("allow-build-scripts");
+        let allow_proc_macros = th.optional("allow-proc-macros");
+        let proc_macros = th.optional("proc-macros").unwrap_or(LintLevel::Deny);
         let executables = th.optional("executables").unwrap_or(LintLevel::Deny);
         let interpreted = th.optional("interpreted").unwrap_or(LintLevel::Allow);
         let script_extensions = th.optional("script-extensions");
+        let deny_globs = th.optional("deny-globs");
         let bypass = th.optional("bypass");
         let enable_builtin_globs = th.optional("enable-builtin-globs").unwrap_or_default();
         let include_dependencies = th.optional("include-dependencies").unwrap_or_default();
+        let include_all = th.optional("include-all").unwrap_or_default();
         let include_workspace = th.optional("include-workspace").unwrap_or_default();
         let include_archives = th.optional("include-archives").unwrap_or_default();
         th.finalize(None)?;
 
         Ok(Self {
             allow_build_scripts,
+            allow_proc_macros,
+            proc_macros,
             executables,
             interpreted,
             script_extensions,
+            deny_globs,
             bypass,
             enable_builtin_globs,
             include_dependencies,
+            include_all,
             include_workspace,
             include_archives,
         })
@@ -307,6 +346,9 @@ pub struct TreeSkipExtended {
     pub depth: Option<usize>,
     /// Reason the tree is being skipped
     pub reason: Option<Reason>,
+    /// The date after which the entry no longer takes effect
+    #[cfg_attr(test, serde(skip))]
+    pub expires: Option<Expires>,
 }
 
 impl<'de> Deserialize<'de> for TreeSkipExtended {
@@ -319,26 +361,404 @@ impl<'de> Deserialize<'de> for TreeSkipExtended {
 
         let mut th = TableHelper::new(value)?;
         let depth = th.optional("depth");
+        let expires = th.optional("expires");
+        th.finalize(None)?;
+        Ok(Self {
+            depth,
+            reason,
+            expires,
+        })
+    }
+}
+
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct CrateSkipExtended {
+    /// Reason the crate is being skipped
+    pub reason: Option<Reason>,
+    /// The date after which the skip no longer takes effect
+    pub expires: Option<Expires>,
+    /// If specified, the crate is not skipped, rather only diagnostics with
+    /// these codes are suppressed for the crate
+    pub waive_codes: Vec<Spanned<String>>,
+    /// Only skips the crate if it comes from this source
+    pub source: Option<Spanned<String>>,
+    /// Only skips the crate if it is exclusively depended upon beneath the
+    /// crate with this name
+    pub under: Option<Spanned<String>>,
+}
+
+impl<'de> Deserialize<'de> for CrateSkipExtended {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let reason = th.optional_s("reason");
+        let expires = th.optional("expires");
+        let waive_codes = th.optional("waive-codes").unwrap_or_default();
+        let source = th.optional("source");
+        let under = th.optional("under");
+        th.finalize(None)?;
+
+        Ok(Self {
+            reason: reason.map(Reason::from),
+            expires,
+            waive_codes,
+            source,
+            under,
+        })
+    }
+}
+
+/// A limit on a count, and the lint level used when the count exceeds it
+#[derive(Copy, Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq, serde::Serialize))]
+pub struct Limit {
+    pub limit: Spanned<usize>,
+    pub level: LintLevel,
+}
+
+impl<'de> Deserialize<'de> for Limit {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        // Allow the limit to be specified by itself, eg. `= 50`, using the
+        // default lint level
+        if !value.has_key("limit") {
+            return Ok(Self {
+                limit: Spanned::deserialize(value)?,
+                level: LintLevel::Warn,
+            });
+        }
+
+        let mut th = TableHelper::new(value)?;
+        let limit = th.required("limit")?;
+        let level = th.optional("level").unwrap_or(LintLevel::Warn);
+        th.finalize(None)?;
+
+        Ok(Self { limit, level })
+    }
+}
+
+/// A size limit, and the lint level used when a size exceeds it
+#[derive(Copy, Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq, serde::Serialize))]
+pub struct SizeLimit {
+    /// The limit, in bytes
+    pub limit: Spanned<u64>,
+    pub level: LintLevel,
+}
+
+impl<'de> Deserialize<'de> for SizeLimit {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        fn parse_size(value: &mut Value<'_>) -> Result<Spanned<u64>, DeserError> {
+            use toml_span::value::ValueInner;
+
+            let span = value.span;
+            let size = match value.take() {
+                ValueInner::Integer(i) => u64::try_from(i).ok(),
+                ValueInner::String(s) => {
+                    let s = s.trim();
+                    let split = s
+                        .find(|c: char| !c.is_ascii_digit() && c != '.')
+                        .unwrap_or(s.len());
+                    let (num, unit) = s.split_at(split);
+
+                    let multiplier = match unit.trim().to_ascii_lowercase().as_str() {
+                        "" | "b" => Some(1.0),
+                        "kb" => Some(1e3),
+                        "mb" => Some(1e6),
+                        "gb" => Some(1e9),
+                        "kib" => Some(1024.0),
+                        "mib" => Some(1024.0 * 1024.0),
+                        "gib" => Some(1024.0 * 1024.0 * 1024.0),
+                        _ => None,
+                    };
+
+                    num.parse::<f64>()
+                        .ok()
+                        .zip(multiplier)
+                        .map(|(num, multiplier)| (num * multiplier) as u64)
+                }
+                other => {
+                    return Err(toml_span::de_helpers::expected(
+                        "an integer or a string",
+                        other,
+                        span,
+                    )
+                    .into())
+                }
+            };
+
+            size.map(|size| Spanned::with_span(size, span))
+                .ok_or_else(|| {
+                    toml_span::Error {
+                        kind: toml_span::ErrorKind::Custom(
+                            "expected a size in bytes, eg. 5000000 or \"5MB\"".into(),
+                        ),
+                        span,
+                        line_info: None,
+                    }
+                    .into()
+                })
+        }
+
+        // As with `Limit`, the size can be specified by itself
+        if !value.has_key("limit") {
+            return Ok(Self {
+                limit: parse_size(value)?,
+                level: LintLevel::Warn,
+            });
+        }
+
+        let mut th = TableHelper::new(value)?;
+        let mut limit = th.table.remove(&"limit".into()).expect("checked above");
+        let level = th.optional("level").unwrap_or(LintLevel::Warn);
+        th.finalize(None)?;
+
+        Ok(Self {
+            limit: parse_size(&mut limit)?,
+            level,
+        })
+    }
+}
+
+/// Policies for the crates that replace registry crates via `[patch]`
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct PatchesConfig {
+    /// Crates that must be patched, eg. with an internal fork
+    pub require: Vec<PackageSpec>,
+    /// How to handle patches for crates that are not in `require`
+    pub unexpected: LintLevel,
+    /// If true, crates redirected via `[patch]` are treated as replacing the
+    /// registry versions they patch, rather than as duplicates of them
+    pub replace_originals: bool,
+}
+
+impl Default for PatchesConfig {
+    fn default() -> Self {
+        Self {
+            require: Vec::new(),
+            unexpected: LintLevel::Allow,
+            replace_originals: false,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PatchesConfig {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let require = th.optional("require").unwrap_or_default();
+        let unexpected = th.optional("unexpected").unwrap_or(LintLevel::Allow);
+        let replace_originals = th.optional("replace-originals").unwrap_or_default();
         th.finalize(None)?;
-        Ok(Self { depth, reason })
+
+        Ok(Self {
+            require,
+            unexpected,
+            replace_originals,
+        })
     }
 }
 
 pub type CrateBan = PackageSpecOrExtended<CrateBanExtended>;
-pub type CrateAllow = PackageSpecOrExtended<Reason>;
-pub type CrateSkip = PackageSpecOrExtended<Reason>;
+pub type CrateAllow = PackageSpecOrExtended<CrateAllowExtended>;
+pub type CrateSkip = PackageSpecOrExtended<CrateSkipExtended>;
 pub type TreeSkip = PackageSpecOrExtended<TreeSkipExtended>;
 
+/// A feature that workspace members may not enable directly on a dependency,
+/// specified as `<crate>/<feature>`
+#[cfg_attr(test, derive(Debug, PartialEq, Eq, serde::Serialize))]
+pub struct MemberFeatureBan {
+    /// The name of the dependency
+    pub krate: String,
+    /// The feature, spanning the entire `<crate>/<feature>` in the config
+    pub feature: Spanned<String>,
+    /// The workspace members that are allowed to enable the feature, eg. a
+    /// crate that re-exports the dependency for the rest of the workspace
+    pub wrappers: Vec<Spanned<String>>,
+    /// The reason the feature is denied
+    pub reason: Option<Reason>,
+}
+
+impl<'de> Deserialize<'de> for MemberFeatureBan {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        use toml_span::value::ValueInner;
+
+        let (feature, wrappers, reason) = match value.take() {
+            ValueInner::String(s) => (
+                Spanned::with_span(s.into_owned(), value.span),
+                Vec::new(),
+                None,
+            ),
+            ValueInner::Table(tab) => {
+                let mut th = TableHelper::from((tab, value.span));
+                let feature: Spanned<String> = th.required("feature")?;
+                let wrappers = th.optional("wrappers").unwrap_or_default();
+                let reason = th.optional("reason");
+                th.finalize(None)?;
+
+                (feature, wrappers, reason)
+            }
+            other => {
+                return Err(
+                    toml_span::de_helpers::expected("a string or table", other, value.span).into(),
+                )
+            }
+        };
+
+        let Some((krate, feat)) = feature
+            .value
+            .split_once('/')
+            .filter(|(krate, feat)| !krate.is_empty() && !feat.is_empty())
+        else {
+            return Err(toml_span::Error {
+                kind: toml_span::ErrorKind::Custom(
+                    "expected a crate and feature, eg. \"tokio/full\"".into(),
+                ),
+                span: feature.span,
+                line_info: None,
+            }
+            .into());
+        };
+
+        Ok(Self {
+            krate: krate.to_owned(),
+            feature: Spanned::with_span(feat.to_owned(), feature.span),
+            wrappers,
+            reason,
+        })
+    }
+}
+
+/// The overrides for a single workspace member, which only apply to the crates
+/// reachable from that member
+#[cfg_attr(test, derive(Debug, PartialEq, Eq, serde::Serialize))]
+pub struct MemberOverride {
+    /// The name of the workspace member
+    pub member: Spanned<String>,
+    /// Crates that are banned, in addition to the workspace's `deny` entries
+    pub deny: Vec<PackageSpec>,
+    /// Crates that are exempt from the workspace's `deny` entries
+    pub exempt: Vec<PackageSpec>,
+    /// Crates that are skipped when checking for duplicates, in addition to
+    /// the workspace's `skip` entries
+    pub skip: Vec<PackageSpec>,
+}
+
+/// The `[bans.overrides]` table, keyed by workspace member
+struct MemberOverrides(Vec<MemberOverride>);
+
+/// The owners of each crate, keyed by crate name, as listed in a publishers
+/// file
+struct CrateOwners(std::collections::BTreeMap<String, Vec<String>>);
+
+impl<'de> Deserialize<'de> for CrateOwners {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        use toml_span::value::ValueInner;
+
+        let table = match value.take() {
+            ValueInner::Table(table) => table,
+            other => {
+                return Err(toml_span::de_helpers::expected("a table", other, value.span).into())
+            }
+        };
+
+        let mut owners = std::collections::BTreeMap::new();
+        for (key, mut value) in table {
+            owners.insert(
+                key.name.into_owned(),
+                Vec::<String>::deserialize(&mut value)?,
+            );
+        }
+
+        Ok(Self(owners))
+    }
+}
+
+impl<'de> Deserialize<'de> for MemberOverrides {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        use toml_span::value::ValueInner;
+
+        let table = match value.take() {
+            ValueInner::Table(table) => table,
+            other => {
+                return Err(toml_span::de_helpers::expected("a table", other, value.span).into())
+            }
+        };
+
+        let mut overrides = Vec::with_capacity(table.len());
+        for (key, mut value) in table {
+            let mut th = TableHelper::new(&mut value)?;
+            let deny = th.optional("deny").unwrap_or_default();
+            let exempt = th.optional("exempt").unwrap_or_default();
+            let skip = th.optional("skip").unwrap_or_default();
+            th.finalize(None)?;
+
+            overrides.push(MemberOverride {
+                member: Spanned::with_span(key.name.into_owned(), key.span),
+                deny,
+                exempt,
+                skip,
+            });
+        }
+
+        Ok(Self(overrides))
+    }
+}
+
+/// The lint level used for multiple versions of specific crates, rather than
+/// the global `multiple-versions` level
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct MultipleVersionsLevel {
+    pub spec: PackageSpec,
+    pub level: LintLevel,
+}
+
+impl<'de> Deserialize<'de> for MultipleVersionsLevel {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let spec = PackageSpec::deserialize(value)?;
+        let mut th = TableHelper::new(value)?;
+        let level = th.required("level")?;
+        th.finalize(None)?;
+
+        Ok(Self { spec, level })
+    }
+}
+
 pub struct Config {
     /// How to handle multiple versions of the same crate
     pub multiple_versions: LintLevel,
+    /// How to handle multiple versions of specific crates
+    pub multiple_versions_levels: Vec<MultipleVersionsLevel>,
     pub multiple_versions_include_dev: bool,
+    /// How to handle the same version of a crate being present from multiple
+    /// sources
+    pub multiple_sources: LintLevel,
     /// How the duplicate graphs are highlighted
     pub highlight: GraphHighlight,
+    /// Which crates with multiple versions are reported
+    pub multiple_versions_policy: MultipleVersionsPolicy,
+    /// If specified, only crates reachable from these workspace members are
+    /// considered when detecting duplicates
+    pub roots: Vec<Spanned<String>>,
     /// The crates that will cause us to emit failures
     pub deny: Vec<CrateBan>,
     /// If specified, means only the listed crates are allowed
     pub allow: Vec<CrateAllow>,
+    /// If true, every crate must be allowed by an `allow` entry with an exact
+    /// version requirement
+    pub allow_exact: bool,
+    /// If true, `deny`, `allow`, and `skip` entries only match crates from a
+    /// registry or git source, never workspace members or path dependencies
+    pub exclude_workspace: bool,
+    /// If true, a note is emitted each time a banned crate is allowed because it
+    /// is only used by one of the `wrappers` of the entry that banned it
+    pub wrapper_notes: bool,
+    /// If true, a banned crate notes each dependency edge that, if removed,
+    /// would remove it from the graph entirely
+    pub removal_notes: bool,
+    /// Crates that must be present in the graph, eg. a patched fork that
+    /// every workspace is expected to use
+    pub require: Vec<PackageSpec>,
+    /// Files, relative to the config, to load additional `deny`, `allow` and
+    /// `skip` entries from
+    pub include: Vec<Spanned<String>>,
     /// Allows specifying features that are or are not allowed on crates
     pub features: Vec<CrateFeatures>,
     /// The default lint level for default features for external, non-workspace
@@ -347,11 +767,38 @@ pub struct Config {
     /// The default lint level for default features for workspace crates, can be
     /// overridden in `features` on a crate by crate basis
     pub workspace_default_features: Option<Spanned<LintLevel>>,
+    /// Features that workspace members may not enable directly on their
+    /// dependencies
+    pub deny_member_features: Vec<MemberFeatureBan>,
+    /// Features that may not be enabled on any crate in the graph, either a
+    /// bare feature name, or `<crate>/<feature>` for a specific crate
+    pub deny_features_global: Vec<Spanned<String>>,
+    /// The crates.io users and teams whose crates are denied
+    pub deny_publishers: Vec<Spanned<String>>,
+    /// Path to a file with the owners of each crate, relative to the config
+    pub publishers_file: Option<Spanned<String>>,
+    /// The lint level for crates owned by one of the `deny_publishers`
+    pub denied_publisher: LintLevel,
+    /// Crates that may be transitive dependencies, but that workspace members
+    /// may not depend on directly
+    pub deny_direct: Vec<PackageSpec>,
+    /// The lint level for workspace members that depend on one of the
+    /// `deny_direct` crates directly
+    pub denied_direct_dependency: LintLevel,
     /// If specified, disregards the crate completely
     pub skip: Vec<CrateSkip>,
     /// If specified, disregards the crate's transitive dependencies
     /// down to a certain depth
     pub skip_tree: Vec<TreeSkip>,
+    /// The lint level used to report how many crates each `skip-tree` entry
+    /// suppressed, and how deep into its tree they were
+    pub skip_tree_usage: LintLevel,
+    /// If specified, disregards all crates from the matching sources when
+    /// checking for duplicates
+    pub skip_sources: Vec<Spanned<String>>,
+    /// Additional or relaxed entries that only apply to the crates reachable
+    /// from specific workspace members
+    pub overrides: Vec<MemberOverride>,
     /// How to handle wildcard dependencies
     pub wildcards: LintLevel,
     /// Wildcard dependencies defined using path attributes will be treated as
@@ -361,30 +808,133 @@ pub struct Config {
     /// crates.io does not allow packages to be published with path dependencies,
     /// thus this rule will not effect public packages.
     pub allow_wildcard_paths: bool,
+    /// Policies for the crates that replace registry crates via `[patch]`
+    pub patches: PatchesConfig,
+    /// How to handle crates that link a native library via the `links`
+    /// manifest key
+    pub links: LintLevel,
+    /// How to handle multiple crates in the graph that wrap the same native
+    /// library
+    pub duplicate_links: LintLevel,
+    /// How to handle crates that are both normal and build dependencies, and
+    /// are thus compiled twice
+    pub build_and_normal: LintLevel,
+    /// How to handle crates whose names are similar to, but not the same as,
+    /// a popular crate
+    pub typosquatting: LintLevel,
+    /// How to handle crates that are only sourced from a git repository, and
+    /// are not published to a registry
+    pub git_only: LintLevel,
+    /// How to handle crates that are resolved through a `[replace]` entry in
+    /// the workspace manifest
+    pub replace: LintLevel,
+    /// How to handle workspace members that declare the same dependency with
+    /// different version requirements
+    pub requirement_drift: LintLevel,
+    /// How to handle workspace members that declare a dependency themselves
+    /// rather than inheriting it from `[workspace.dependencies]`
+    pub workspace_inheritance: LintLevel,
+    /// The lint level for crates resolved to a pre-release version, eg.
+    /// `1.0.0-rc.1`
+    pub prerelease: LintLevel,
+    /// The crates that are allowed to be pre-release versions regardless of
+    /// the `prerelease` lint level
+    pub prerelease_exceptions: Vec<PackageSpec>,
+    /// The version of the toolchain used to build the workspace, crates whose
+    /// `rust-version` requires a newer compiler are banned
+    pub min_rust_version: Option<Spanned<String>>,
+    /// The lint level for crates whose `rust-version` is newer than
+    /// `min_rust_version`
+    pub rust_version_too_new: LintLevel,
+    /// The maximum number of crates that may only be reachable through any
+    /// single crate
+    pub max_unique_dependencies: Option<Limit>,
+    /// The maximum number of crates that may be in the graph
+    pub max_total_crates: Option<Limit>,
+    /// The maximum number of crates a single glob deny entry may match before
+    /// they are reported in a single diagnostic
+    pub max_glob_matches: Option<Limit>,
+    /// The maximum number of crates that may have multiple versions before
+    /// the duplicates cause the check to fail
+    pub max_duplicates: Option<Limit>,
+    /// The maximum number of edges any crate may be from the nearest
+    /// workspace member
+    pub max_depth: Option<Limit>,
+    /// The maximum number of direct dependencies any crate may have
+    pub max_direct_deps: Option<Limit>,
+    /// The maximum unpacked size of any registry or git crate
+    pub max_crate_size: Option<SizeLimit>,
+    /// Crates that are not subject to `max_crate_size`
+    pub crate_size_exceptions: Vec<PackageSpec>,
     /// Deprecated and moved into `build.allow_build_scripts`, will eventually
     /// be removed
     pub allow_build_scripts: Option<Spanned<Vec<PackageSpec>>>,
     /// Options for crates that run at build time
     pub build: Option<BuildConfig>,
+    /// How to report `deny`, `skip` and `skip-tree` entries whose `expires`
+    /// date has passed
+    pub expired: LintLevel,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             multiple_versions: LintLevel::Warn,
+            multiple_versions_levels: Vec::new(),
             multiple_versions_include_dev: false,
+            multiple_sources: LintLevel::Allow,
             highlight: GraphHighlight::All,
+            multiple_versions_policy: MultipleVersionsPolicy::All,
+            roots: Vec::new(),
             deny: Vec::new(),
             allow: Vec::new(),
+            allow_exact: false,
+            exclude_workspace: false,
+            wrapper_notes: true,
+            removal_notes: false,
+            require: Vec::new(),
+            include: Vec::new(),
             features: Vec::new(),
             external_default_features: None,
             workspace_default_features: None,
+            deny_member_features: Vec::new(),
+            deny_features_global: Vec::new(),
+            deny_publishers: Vec::new(),
+            publishers_file: None,
+            denied_publisher: LintLevel::Deny,
+            deny_direct: Vec::new(),
+            denied_direct_dependency: LintLevel::Deny,
             skip: Vec::new(),
             skip_tree: Vec::new(),
+            skip_tree_usage: LintLevel::Allow,
+            skip_sources: Vec::new(),
+            overrides: Vec::new(),
             wildcards: LintLevel::Allow,
             allow_wildcard_paths: false,
+            patches: PatchesConfig::default(),
+            links: LintLevel::Allow,
+            duplicate_links: LintLevel::Allow,
+            build_and_normal: LintLevel::Allow,
+            typosquatting: LintLevel::Allow,
+            git_only: LintLevel::Allow,
+            replace: LintLevel::Allow,
+            requirement_drift: LintLevel::Allow,
+            workspace_inheritance: LintLevel::Allow,
+            prerelease: LintLevel::Allow,
+            prerelease_exceptions: Vec::new(),
+            min_rust_version: None,
+            rust_version_too_new: LintLevel::Deny,
+            max_unique_dependencies: None,
+            max_total_crates: None,
+            max_glob_matches: None,
+            max_duplicates: None,
+            max_depth: None,
+            max_direct_deps: None,
+            max_crate_size: None,
+            crate_size_exceptions: Vec::new(),
             allow_build_scripts: None,
             build: None,
+            expired: LintLevel::Warn,
         }
     }
 }
@@ -394,56 +944,373 @@ impl<'de> Deserialize<'de> for Config {
         let mut th = TableHelper::new(value)?;
 
         let multiple_versions = th.optional("multiple-versions").unwrap_or(LintLevel::Warn);
+        let multiple_versions_levels = th.optional("multiple-versions-levels").unwrap_or_default();
         let multiple_versions_include_dev = th
             .optional("multiple-versions-include-dev")
             .unwrap_or_default();
+        let multiple_sources = th.optional("multiple-sources").unwrap_or(LintLevel::Allow);
         let highlight = th.optional("highlight").unwrap_or_default();
+        let multiple_versions_policy = th.optional("multiple-versions-policy").unwrap_or_default();
+        let roots = th.optional("roots").unwrap_or_default();
         let deny = th.optional("deny").unwrap_or_default();
         let allow = th.optional("allow").unwrap_or_default();
+        let allow_exact = th.optional("allow-exact").unwrap_or_default();
+        let exclude_workspace = th.optional("exclude-workspace").unwrap_or_default();
+        let wrapper_notes = th.optional("wrapper-notes").unwrap_or(true);
+        let removal_notes = th.optional("removal-notes").unwrap_or_default();
+        let require = th.optional("require").unwrap_or_default();
+        let include = th.optional("include").unwrap_or_default();
         let features = th.optional("features").unwrap_or_default();
         let external_default_features = th.optional("external-default-features");
         let workspace_default_features = th.optional("workspace-default-features");
+        let deny_member_features = th.optional("deny-member-features").unwrap_or_default();
+        let deny_features_global = th.optional("deny-features-global").unwrap_or_default();
+        let deny_publishers = th.optional("deny-publishers").unwrap_or_default();
+        let publishers_file = th.optional("publishers-file");
+        let denied_publisher = th.optional("denied-publisher").unwrap_or(LintLevel::Deny);
+        let deny_direct = th.optional("deny-direct").unwrap_or_default();
+        let denied_direct_dependency = th
+            .optional("denied-direct-dependency")
+            .unwrap_or(LintLevel::Deny);
         let skip = th.optional("skip").unwrap_or_default();
         let skip_tree = th.optional("skip-tree").unwrap_or_default();
+        let skip_tree_usage = th.optional("skip-tree-usage").unwrap_or(LintLevel::Allow);
+        let skip_sources = th.optional("skip-sources").unwrap_or_default();
+        let overrides = th
+            .optional::<MemberOverrides>("overrides")
+            .map_or_else(Vec::new, |mo| mo.0);
         let wildcards = th.optional("wildcards").unwrap_or(LintLevel::Allow);
         let allow_wildcard_paths = th.optional("allow-wildcard-paths").unwrap_or_default();
+        let patches = th.optional("patches").unwrap_or_default();
+        let links = th.optional("links").unwrap_or(LintLevel::Allow);
+        let duplicate_links = th.optional("duplicate-links").unwrap_or(LintLevel::Allow);
+        let build_and_normal = th.optional("build-and-normal").unwrap_or(LintLevel::Allow);
+        let typosquatting = th.optional("typosquatting").unwrap_or(LintLevel::Allow);
+        let git_only = th.optional("git-only").unwrap_or(LintLevel::Allow);
+        let replace = th.optional("replace").unwrap_or(LintLevel::Allow);
+        let requirement_drift = th.optional("requirement-drift").unwrap_or(LintLevel::Allow);
+        let workspace_inheritance = th
+            .optional("workspace-inheritance")
+            .unwrap_or(LintLevel::Allow);
+        let prerelease = th.optional("prerelease").unwrap_or(LintLevel::Allow);
+        let prerelease_exceptions = th.optional("prerelease-exceptions").unwrap_or_default();
+        let min_rust_version = th.optional("min-rust-version");
+        let rust_version_too_new = th
+            .optional("rust-version-too-new")
+            .unwrap_or(LintLevel::Deny);
+        let max_unique_dependencies = th.optional("max-unique-dependencies");
+        let max_total_crates = th.optional("max-total-crates");
+        let max_glob_matches = th.optional("max-glob-matches");
+        let max_duplicates = th.optional("max-duplicates");
+        let max_depth = th.optional("max-depth");
+        let max_direct_deps = th.optional("max-direct-deps");
+        let max_crate_size = th.optional("max-crate-size");
+        let crate_size_exceptions = th.optional("crate-size-exceptions").unwrap_or_default();
         let allow_build_scripts = th.optional("allow-build-scripts");
         let build = th.optional("build");
+        let expired = th.optional("expired").unwrap_or(LintLevel::Warn);
 
         th.finalize(None)?;
 
         Ok(Self {
             multiple_versions,
+            multiple_versions_levels,
             multiple_versions_include_dev,
+            multiple_sources,
             highlight,
+            multiple_versions_policy,
+            roots,
             deny,
             allow,
+            allow_exact,
+            exclude_workspace,
+            wrapper_notes,
+            removal_notes,
+            require,
+            include,
             features,
             external_default_features,
             workspace_default_features,
+            deny_member_features,
+            deny_features_global,
+            deny_publishers,
+            publishers_file,
+            denied_publisher,
+            deny_direct,
+            denied_direct_dependency,
             skip,
             skip_tree,
+            skip_tree_usage,
+            skip_sources,
+            overrides,
             wildcards,
             allow_wildcard_paths,
+            patches,
+            links,
+            duplicate_links,
+            build_and_normal,
+            typosquatting,
+            git_only,
+            replace,
+            requirement_drift,
+            workspace_inheritance,
+            prerelease,
+            prerelease_exceptions,
+            min_rust_version,
+            rust_version_too_new,
+            max_unique_dependencies,
+            max_total_crates,
+            max_glob_matches,
+            max_duplicates,
+            max_depth,
+            max_direct_deps,
+            max_crate_size,
+            crate_size_exceptions,
             allow_build_scripts,
             build,
+            expired,
         })
     }
 }
 
+/// The entries that can be specified in a file included via `include`
+struct Included {
+    include: Vec<Spanned<String>>,
+    deny: Vec<CrateBan>,
+    allow: Vec<CrateAllow>,
+    skip: Vec<CrateSkip>,
+}
+
+impl<'de> Deserialize<'de> for Included {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let include = th.optional("include").unwrap_or_default();
+        let deny = th.optional("deny").unwrap_or_default();
+        let allow = th.optional("allow").unwrap_or_default();
+        let skip = th.optional("skip").unwrap_or_default();
+        th.finalize(None)?;
+
+        Ok(Self {
+            include,
+            deny,
+            allow,
+            skip,
+        })
+    }
+}
+
+/// The entries from the config and all of its includes, along with the file
+/// each was declared in
+struct EntryLists {
+    deny: Vec<(FileId, CrateBan)>,
+    allow: Vec<(FileId, CrateAllow)>,
+    skip: Vec<(FileId, CrateSkip)>,
+    /// Every file that was included, in the order they were loaded
+    included: Vec<FileId>,
+}
+
+/// Recursively loads the entries from each included file, relative to the
+/// file that included it
+///
+/// The stack holds the files currently being included so that cycles can be
+/// reported rather than recursing forever
+fn load_includes(
+    ctx: &mut ValidationContext<'_>,
+    includes: Vec<Spanned<String>>,
+    lists: &mut EntryLists,
+    stack: &mut Vec<std::path::PathBuf>,
+) {
+    let parent = std::path::Path::new(ctx.files.name(ctx.cfg_id))
+        .parent()
+        .map(|p| p.to_owned())
+        .unwrap_or_default();
+
+    for include in includes {
+        let path = parent.join(&include.value);
+        let canonical = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+
+        if let Some(i) = stack.iter().position(|p| *p == canonical) {
+            let mut notes: Vec<_> = stack[i..]
+                .iter()
+                .map(|p| format!("'{}' includes", p.display()))
+                .collect();
+            notes.push(format!("'{}'", canonical.display()));
+
+            ctx.push(
+                Diagnostic::error()
+                    .with_message(format!("include cycle detected for '{}'", include.value))
+                    .with_labels(vec![Label::primary(ctx.cfg_id, include.span)])
+                    .with_notes(notes),
+            );
+            continue;
+        }
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(err) => {
+                ctx.push(
+                    Diagnostic::error()
+                        .with_message("failed to read included file")
+                        .with_labels(vec![Label::primary(ctx.cfg_id, include.span)])
+                        .with_notes(vec![
+                            format!("path = '{}'", path.display()),
+                            format!("error = {err:#}"),
+                        ]),
+                );
+                continue;
+            }
+        };
+
+        let file_id = ctx.files.add(&path, contents);
+        lists.included.push(file_id);
+
+        let included = toml_span::parse(ctx.files.source(file_id))
+            .map_err(DeserError::from)
+            .and_then(|mut value| Included::deserialize(&mut value));
+
+        let included = match included {
+            Ok(included) => included,
+            Err(err) => {
+                ctx.diagnostics
+                    .extend(err.errors.into_iter().map(|err| err.to_diagnostic(file_id)));
+                continue;
+            }
+        };
+
+        lists
+            .deny
+            .extend(included.deny.into_iter().map(|e| (file_id, e)));
+        lists
+            .allow
+            .extend(included.allow.into_iter().map(|e| (file_id, e)));
+        lists
+            .skip
+            .extend(included.skip.into_iter().map(|e| (file_id, e)));
+
+        if !included.include.is_empty() {
+            stack.push(canonical);
+            load_includes(&mut ctx.for_file(file_id), included.include, lists, stack);
+            stack.pop();
+        }
+    }
+}
+
+/// Loads the owners of each crate from the publishers file, relative to the
+/// config file
+fn load_publishers(
+    ctx: &mut ValidationContext<'_>,
+    publishers_file: &Spanned<String>,
+) -> std::collections::BTreeMap<String, Vec<String>> {
+    let path = std::path::Path::new(ctx.files.name(ctx.cfg_id))
+        .parent()
+        .map(|p| p.to_owned())
+        .unwrap_or_default()
+        .join(&publishers_file.value);
+
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(err) => {
+            ctx.push(
+                Diagnostic::error()
+                    .with_message("failed to read publishers file")
+                    .with_labels(vec![Label::primary(ctx.cfg_id, publishers_file.span)])
+                    .with_notes(vec![
+                        format!("path = '{}'", path.display()),
+                        format!("error = {err:#}"),
+                    ]),
+            );
+            return Default::default();
+        }
+    };
+
+    let file_id = ctx.files.add(&path, contents);
+
+    let owners = toml_span::parse(ctx.files.source(file_id))
+        .map_err(DeserError::from)
+        .and_then(|mut value| CrateOwners::deserialize(&mut value));
+
+    match owners {
+        Ok(owners) => owners.0,
+        Err(err) => {
+            ctx.diagnostics
+                .extend(err.errors.into_iter().map(|err| err.to_diagnostic(file_id)));
+            Default::default()
+        }
+    }
+}
+
 impl crate::cfg::UnvalidatedConfig for Config {
     type ValidCfg = ValidConfig;
 
     fn validate(self, mut ctx: ValidationContext<'_>) -> Self::ValidCfg {
         let cfg_id = ctx.cfg_id;
 
+        // Expired entries are treated as if they were absent, and are reported
+        // so that they are revisited rather than quietly becoming permanent
+        let today = time::OffsetDateTime::now_utc().date();
+        let expired_level = self.expired;
+        let is_expired =
+            |ctx: &mut ValidationContext<'_>, spec: &PackageSpec, kind: &str, expires: &Expires| {
+                if !expires.is_expired(today) {
+                    return false;
+                }
+
+                let diag = match expired_level {
+                    LintLevel::Allow => return true,
+                    LintLevel::Warn => Diagnostic::warning(),
+                    LintLevel::Deny => Diagnostic::error(),
+                };
+
+                ctx.push(
+                    diag.with_message(format!(
+                        "`{kind}` entry expired on {} and no longer takes effect",
+                        expires.0.value
+                    ))
+                    .with_labels(vec![
+                        Label::primary(ctx.cfg_id, expires.0.span).with_message("expiration date"),
+                        Label::secondary(ctx.cfg_id, spec.name.span),
+                    ])
+                    .with_notes(vec![
+                        "remove the entry, or extend its date if it is still needed".into(),
+                    ]),
+                );
+                true
+            };
+
+        // Entries from included files are validated the same as the ones
+        // declared directly in the config, but retain the file they are from
+        let mut lists = EntryLists {
+            deny: self.deny.into_iter().map(|e| (cfg_id, e)).collect(),
+            allow: self.allow.into_iter().map(|e| (cfg_id, e)).collect(),
+            skip: self.skip.into_iter().map(|e| (cfg_id, e)).collect(),
+            included: Vec::new(),
+        };
+
+        if !self.include.is_empty() {
+            let root = std::path::PathBuf::from(ctx.files.name(cfg_id));
+            let mut stack = vec![std::fs::canonicalize(&root).unwrap_or(root)];
+            load_includes(&mut ctx, self.include, &mut lists, &mut stack);
+        }
+
         let (denied_multiple_versions, denied) = {
             let mut dmulti = Vec::new();
             let mut denied = Vec::new();
-            for deny_spec in self.deny {
+            for (file_id, deny_spec) in lists.deny {
+                let mut ctx = ctx.for_file(file_id);
+                let cfg_id = file_id;
                 let spec = deny_spec.spec;
 
+                if !validate_glob(&mut ctx, &spec) {
+                    continue;
+                }
+
                 let inner = if let Some(extended) = deny_spec.inner {
+                    if let Some(expires) = &extended.expires {
+                        if is_expired(&mut ctx, &spec, "deny", expires) {
+                            continue;
+                        }
+                    }
+
                     let dmv = extended.deny_multiple_versions;
                     let wrappers = extended.wrappers;
 
@@ -466,7 +1333,10 @@ impl crate::cfg::UnvalidatedConfig for Config {
                     }
 
                     if dmv.map_or(false, |d| d.value) {
-                        dmulti.push(spec);
+                        dmulti.push(FileEntry {
+                            file_id,
+                            entry: spec,
+                        });
                         continue;
                     }
 
@@ -474,35 +1344,173 @@ impl crate::cfg::UnvalidatedConfig for Config {
                         wrappers: wrappers.map(|sv| sv.value),
                         reason: extended.reason,
                         use_instead: extended.use_instead,
+                        tags: CrateTags {
+                            categories: extended.categories,
+                            keywords: extended.keywords,
+                        },
+                        kinds: extended.kinds,
+                        default_features: extended.default_features,
+                        targets: extended
+                            .targets
+                            .iter()
+                            .flat_map(|target| resolve_targets(&mut ctx, target))
+                            .collect(),
+                        exempt: extended.exempt,
                     })
                 } else {
                     None
                 };
 
-                denied.push(ValidKrateBan { spec, inner });
+                denied.push(FileEntry {
+                    file_id,
+                    entry: ValidKrateBan { spec, inner },
+                });
             }
 
             (dmulti, denied)
         };
 
-        let allowed = self.allow;
-        let skipped = self.skip;
+        let allowed: Vec<_> = lists
+            .allow
+            .into_iter()
+            .filter_map(|(file_id, all)| {
+                let mut ctx = ctx.for_file(file_id);
+                if !validate_glob(&mut ctx, &all.spec) {
+                    return None;
+                }
+
+                let inner = if let Some(ext) = all.inner {
+                    // An entry with an invalid source is dropped rather than
+                    // allowing crates from any source
+                    let source = if let Some(src) = ext.source {
+                        Some(UrlSpan {
+                            value: crate::sources::cfg::parse_url(&mut ctx, &src)?,
+                            span: src.span,
+                        })
+                    } else {
+                        None
+                    };
+
+                    Some(KrateAllow {
+                        reason: ext.reason,
+                        tags: CrateTags {
+                            categories: ext.categories,
+                            keywords: ext.keywords,
+                        },
+                        source,
+                    })
+                } else {
+                    None
+                };
+
+                Some(FileEntry {
+                    file_id,
+                    entry: ValidKrateAllow {
+                        spec: all.spec,
+                        inner,
+                    },
+                })
+            })
+            .collect();
+        let mut waived = Vec::new();
+        let skipped: Vec<_> = lists
+            .skip
+            .into_iter()
+            .filter_map(|(file_id, skip)| {
+                if !validate_glob(&mut ctx.for_file(file_id), &skip.spec) {
+                    return None;
+                }
+
+                let mut inner = None;
+                if let Some(ext) = skip.inner {
+                    // An entry with an invalid source is dropped rather than
+                    // skipping crates from any source
+                    let source = if let Some(src) = ext.source {
+                        Some(UrlSpan {
+                            value: crate::sources::cfg::parse_url(
+                                &mut ctx.for_file(file_id),
+                                &src,
+                            )?,
+                            span: src.span,
+                        })
+                    } else {
+                        None
+                    };
+
+                    if let Some(expires) = &ext.expires {
+                        if is_expired(&mut ctx.for_file(file_id), &skip.spec, "skip", expires) {
+                            return None;
+                        }
+                    }
+
+                    // Entries that waive specific codes don't skip the crate
+                    if !ext.waive_codes.is_empty() {
+                        let codes = ext
+                            .waive_codes
+                            .into_iter()
+                            .filter_map(|code| {
+                                if code.value.parse::<super::Code>().is_ok() {
+                                    Some(code.value)
+                                } else {
+                                    ctx.push(
+                                        Diagnostic::error()
+                                            .with_message("unknown bans diagnostic code")
+                                            .with_labels(vec![Label::primary(file_id, code.span)]),
+                                    );
+                                    None
+                                }
+                            })
+                            .collect();
+
+                        waived.push(FileEntry {
+                            file_id,
+                            entry: WaivedCodes {
+                                spec: skip.spec,
+                                codes,
+                            },
+                        });
+                        return None;
+                    }
+
+                    inner = Some(KrateSkip {
+                        reason: ext.reason,
+                        source,
+                        under: ext.under,
+                    });
+                }
+
+                Some(FileEntry {
+                    file_id,
+                    entry: ValidKrateSkip {
+                        spec: skip.spec,
+                        inner,
+                    },
+                })
+            })
+            .collect();
 
         let dupe_crate_diag = |ctx: &mut ValidationContext<'_>,
-                               first: (&PackageSpec, &str),
-                               second: (&PackageSpec, &str)| {
-            let diag = Diagnostic::error()
+                               first: (FileId, &PackageSpec, &str),
+                               second: (FileId, &PackageSpec, &str)| {
+            let mut diag = Diagnostic::error()
                 .with_message(format!(
                     "a crate was specified in both `{}` and `{}`",
-                    second.1, first.1
+                    second.2, first.2
                 ))
                 .with_labels(vec![
-                    Label::secondary(cfg_id, first.0.name.span)
-                        .with_message(format!("marked as `{}`", first.1)),
-                    Label::secondary(cfg_id, second.0.name.span)
-                        .with_message(format!("marked as `{}`", second.1)),
+                    Label::secondary(first.0, first.1.name.span)
+                        .with_message(format!("marked as `{}`", first.2)),
+                    Label::secondary(second.0, second.1.name.span)
+                        .with_message(format!("marked as `{}`", second.2)),
                 ]);
 
+            if first.1 != second.1 {
+                diag = diag.with_notes(vec![format!(
+                    "versions matched by both '{}' and '{}' are ambiguous",
+                    first.1, second.1
+                )]);
+            }
+
             ctx.push(diag);
         };
 
@@ -522,18 +1530,31 @@ impl crate::cfg::UnvalidatedConfig for Config {
         };
 
         for d in &denied {
-            if let Some(dupe) = exact_match(&allowed, &d.spec) {
-                dupe_crate_diag(&mut ctx, (&d.spec, "deny"), (dupe, "allow"));
+            let (d_id, d) = (d.file_id, &d.entry);
+
+            // The same crate can be both denied and allowed as long as the
+            // versions they apply to are disjoint
+            if let Some(dupe) = allowed.iter().find_map(|all| {
+                all.entry
+                    .spec
+                    .overlaps(&d.spec)
+                    .then_some((all.file_id, &all.entry.spec))
+            }) {
+                dupe_crate_diag(&mut ctx, (d_id, &d.spec, "deny"), (dupe.0, dupe.1, "allow"));
             }
 
             if let Some(dupe) = exact_match(&skipped, &d.spec) {
-                dupe_crate_diag(&mut ctx, (&d.spec, "deny"), (dupe, "skip"));
+                dupe_crate_diag(&mut ctx, (d_id, &d.spec, "deny"), (dupe.0, dupe.1, "skip"));
             }
         }
 
         for all in &allowed {
-            if let Some(dupe) = exact_match(&skipped, &all.spec) {
-                dupe_crate_diag(&mut ctx, (&all.spec, "allow"), (dupe, "skip"));
+            if let Some(dupe) = exact_match(&skipped, &all.entry.spec) {
+                dupe_crate_diag(
+                    &mut ctx,
+                    (all.file_id, &all.entry.spec, "allow"),
+                    (dupe.0, dupe.1, "skip"),
+                );
             }
         }
 
@@ -621,6 +1642,35 @@ impl crate::cfg::UnvalidatedConfig for Config {
                 ValidGlobSet::default()
             });
 
+            let deny_globs = if let Some(deny_globs) = bc.deny_globs {
+                let mut gsb = GlobsetBuilder::new();
+
+                for dg in deny_globs {
+                    match globset::Glob::new(&dg.value) {
+                        Ok(glob) => {
+                            gsb.add(glob, GlobPattern::User(dg));
+                        }
+                        Err(err) => {
+                            ctx.diagnostics.push(
+                                Diagnostic::error()
+                                    .with_message(format!("invalid glob pattern: {err}"))
+                                    .with_labels(vec![Label::primary(ctx.cfg_id, dg.span)]),
+                            );
+                        }
+                    }
+                }
+
+                gsb.build().unwrap_or_else(|err| {
+                    ctx.diagnostics.push(
+                        Diagnostic::error()
+                            .with_message(format!("failed to build deny globs glob set: {err}")),
+                    );
+                    ValidGlobSet::default()
+                })
+            } else {
+                ValidGlobSet::default()
+            };
+
             let bypass = if let Some(aexes) = bc.bypass {
                 let mut aex = Vec::new();
 
@@ -690,10 +1740,14 @@ impl crate::cfg::UnvalidatedConfig for Config {
 
             Some(ValidBuildConfig {
                 allow_build_scripts: bc.allow_build_scripts,
+                allow_proc_macros: bc.allow_proc_macros,
+                proc_macros: bc.proc_macros,
                 executables: bc.executables,
                 script_extensions,
+                deny_globs,
                 bypass,
                 include_dependencies: bc.include_dependencies,
+                include_all: bc.include_all,
                 include_workspace: bc.include_workspace,
                 include_archives: bc.include_archives,
                 interpreted: bc.interpreted,
@@ -706,10 +1760,14 @@ impl crate::cfg::UnvalidatedConfig for Config {
                 ]));
             Some(ValidBuildConfig {
                 allow_build_scripts: Some(abs.value),
+                allow_proc_macros: None,
+                proc_macros: LintLevel::Deny,
                 executables: LintLevel::Allow,
                 script_extensions: ValidGlobSet::default(),
+                deny_globs: ValidGlobSet::default(),
                 bypass: Vec::new(),
                 include_dependencies: false,
+                include_all: false,
                 include_workspace: false,
                 include_archives: false,
                 interpreted: LintLevel::Warn,
@@ -718,21 +1776,124 @@ impl crate::cfg::UnvalidatedConfig for Config {
             None
         };
 
+        let tree_skipped = self
+            .skip_tree
+            .into_iter()
+            .filter(|ts| {
+                let Some(expires) = ts.inner.as_ref().and_then(|ext| ext.expires.as_ref()) else {
+                    return true;
+                };
+
+                !is_expired(&mut ctx, &ts.spec, "skip-tree", expires)
+            })
+            .collect();
+
+        let skip_sources = self
+            .skip_sources
+            .into_iter()
+            .filter_map(|ss| {
+                crate::sources::cfg::parse_url(&mut ctx, &ss).map(|url| UrlSpan {
+                    value: url,
+                    span: ss.span,
+                })
+            })
+            .collect();
+
+        let crate_owners = match &self.publishers_file {
+            Some(pf) => load_publishers(&mut ctx, pf),
+            None => {
+                if let Some(first) = self.deny_publishers.first() {
+                    ctx.push(
+                        Diagnostic::error()
+                            .with_message("`deny-publishers` requires a `publishers-file`")
+                            .with_labels(vec![Label::primary(ctx.cfg_id, first.span)])
+                            .with_notes(vec![
+                                "the file lists the owners of each crate, eg. `foo = [\"github:org:team\"]`".to_owned(),
+                            ]),
+                    );
+                }
+
+                Default::default()
+            }
+        };
+
+        let deny_direct = self
+            .deny_direct
+            .into_iter()
+            .filter(|spec| validate_glob(&mut ctx, spec))
+            .collect();
+
+        let min_rust_version = self.min_rust_version.and_then(|mrv| {
+            let Some(version) = parse_rust_version(&mrv.value) else {
+                ctx.push(
+                    Diagnostic::error()
+                        .with_message(format!("invalid rust version '{}'", mrv.value))
+                        .with_labels(vec![Label::primary(ctx.cfg_id, mrv.span)
+                            .with_message("expected a version, eg. '1.70' or '1.70.0'")]),
+                );
+                return None;
+            };
+
+            Some(Spanned::with_span(version, mrv.span))
+        });
+
         ValidConfig {
             file_id: ctx.cfg_id,
+            included_files: lists.included,
             multiple_versions: self.multiple_versions,
+            multiple_versions_levels: self.multiple_versions_levels,
             multiple_versions_include_dev: self.multiple_versions_include_dev,
+            multiple_sources: self.multiple_sources,
             highlight: self.highlight,
+            multiple_versions_policy: self.multiple_versions_policy,
+            roots: self.roots,
             denied,
             denied_multiple_versions,
             allowed,
+            allow_exact: self.allow_exact,
+            exclude_workspace: self.exclude_workspace,
+            wrapper_notes: self.wrapper_notes,
+            removal_notes: self.removal_notes,
+            required: self.require,
             features,
             external_default_features: self.external_default_features,
             workspace_default_features: self.workspace_default_features,
+            deny_member_features: self.deny_member_features,
+            deny_features_global: self.deny_features_global,
+            deny_publishers: self.deny_publishers,
+            crate_owners,
+            denied_publisher: self.denied_publisher,
+            deny_direct,
+            denied_direct_dependency: self.denied_direct_dependency,
             skipped,
+            waived,
             wildcards: self.wildcards,
             allow_wildcard_paths: self.allow_wildcard_paths,
-            tree_skipped: self.skip_tree,
+            patches: self.patches,
+            links: self.links,
+            duplicate_links: self.duplicate_links,
+            build_and_normal: self.build_and_normal,
+            typosquatting: self.typosquatting,
+            git_only: self.git_only,
+            replace: self.replace,
+            requirement_drift: self.requirement_drift,
+            workspace_inheritance: self.workspace_inheritance,
+            prerelease: self.prerelease,
+            prerelease_exceptions: self.prerelease_exceptions,
+            min_rust_version,
+            rust_version_too_new: self.rust_version_too_new,
+            max_unique_dependencies: self.max_unique_dependencies,
+            max_total_crates: self.max_total_crates,
+            max_glob_matches: self.max_glob_matches,
+            max_duplicates: self.max_duplicates,
+            max_depth: self.max_depth,
+            max_direct_deps: self.max_direct_deps,
+            max_crate_size: self.max_crate_size,
+            crate_size_exceptions: self.crate_size_exceptions,
+            tree_skipped,
+            skip_tree_usage: self.skip_tree_usage,
+            skip_sources,
+            overrides: self.overrides,
             build,
         }
     }
@@ -758,11 +1919,115 @@ fn load_builtin_globs(files: &mut crate::diag::Files, gsb: &mut GlobsetBuilder)
 
 #[inline]
 pub(crate) fn exact_match<'v, T>(
-    arr: &'v [PackageSpecOrExtended<T>],
+    arr: &'v [FileEntry<PackageSpecOrExtended<T>>],
     id: &'_ PackageSpec,
-) -> Option<&'v PackageSpec> {
+) -> Option<(FileId, &'v PackageSpec)> {
     arr.iter()
-        .find_map(|sid| (&sid.spec == id).then_some(&sid.spec))
+        .find_map(|sid| (&sid.entry.spec == id).then_some((sid.file_id, &sid.entry.spec)))
+}
+
+/// Parses a `rust-version`, which, unlike a full semver version, may omit the
+/// minor and patch components, eg. `1.70`
+pub(crate) fn parse_rust_version(rv: &str) -> Option<semver::Version> {
+    let mut parts = rv.trim().split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some(semver::Version::new(major, minor, patch))
+}
+
+/// Resolves a target triple, or a `cfg()` expression, to the triples of the
+/// builtin targets it applies to
+fn resolve_targets(ctx: &mut ValidationContext<'_>, target: &Spanned<String>) -> Vec<&'static str> {
+    use krates::cfg_expr::{targets, Expression, Predicate};
+
+    if !target.value.starts_with("cfg(") {
+        if let Some(ti) = targets::get_builtin_target_by_triple(&target.value) {
+            return vec![ti.triple.as_str()];
+        }
+
+        ctx.push(
+            Diagnostic::error()
+                .with_message(format!("unknown target '{}'", target.value))
+                .with_labels(vec![Label::primary(ctx.cfg_id, target.span)]),
+        );
+        return Vec::new();
+    }
+
+    let expr = match Expression::parse(&target.value) {
+        Ok(expr) => expr,
+        Err(err) => {
+            ctx.push(
+                Diagnostic::error()
+                    .with_message(format!("invalid cfg expression: {err}"))
+                    .with_labels(vec![Label::primary(ctx.cfg_id, target.span)]),
+            );
+            return Vec::new();
+        }
+    };
+
+    targets::ALL_BUILTINS
+        .iter()
+        .filter(|ti| {
+            expr.eval(|pred| match pred {
+                Predicate::Target(tp) => tp.matches(*ti),
+                _ => false,
+            })
+        })
+        .map(|ti| ti.triple.as_str())
+        .collect()
+}
+
+/// Crate names can't contain any of these characters, so a deny or allow
+/// entry whose name does is treated as a glob pattern
+#[inline]
+pub(crate) fn is_glob(name: &str) -> bool {
+    name.contains(['*', '?', '[', '{'])
+}
+
+/// Emits an error if the spec's name is an invalid glob pattern
+fn validate_glob(ctx: &mut ValidationContext<'_>, spec: &PackageSpec) -> bool {
+    if !is_glob(&spec.name.value) {
+        return true;
+    }
+
+    match globset::Glob::new(&spec.name.value) {
+        Ok(_) => true,
+        Err(err) => {
+            ctx.push(
+                Diagnostic::error()
+                    .with_message(format!("invalid glob pattern: {err}"))
+                    .with_labels(vec![Label::primary(ctx.cfg_id, spec.name.span)]),
+            );
+            false
+        }
+    }
+}
+
+/// Restricts an entry to crates that are tagged with at least one of the
+/// categories and/or keywords in their manifest. Crates without the metadata
+/// never match a non-empty filter
+#[derive(Default)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub(crate) struct CrateTags {
+    pub categories: Vec<Spanned<String>>,
+    pub keywords: Vec<Spanned<String>>,
+}
+
+impl CrateTags {
+    pub(crate) fn matches(&self, krate: &crate::Krate) -> bool {
+        #[inline]
+        fn any(filter: &[Spanned<String>], tags: &[String]) -> bool {
+            filter.is_empty() || filter.iter().any(|f| tags.contains(&f.value))
+        }
+
+        any(&self.categories, &krate.categories) && any(&self.keywords, &krate.keywords)
+    }
 }
 
 #[cfg_attr(test, derive(serde::Serialize))]
@@ -770,10 +2035,34 @@ pub(crate) struct KrateBan {
     pub wrappers: Option<Vec<Spanned<String>>>,
     pub reason: Option<Reason>,
     pub use_instead: Option<Spanned<String>>,
+    pub tags: CrateTags,
+    pub kinds: Vec<EdgeKind>,
+    pub default_features: Option<bool>,
+    /// The triples of the builtin targets the ban applies to
+    pub targets: Vec<&'static str>,
+    pub exempt: Vec<PackageSpec>,
 }
 
 pub(crate) type ValidKrateBan = PackageSpecOrExtended<KrateBan>;
 
+#[cfg_attr(test, derive(serde::Serialize))]
+pub(crate) struct KrateAllow {
+    pub reason: Option<Reason>,
+    pub tags: CrateTags,
+    pub source: Option<UrlSpan>,
+}
+
+pub(crate) type ValidKrateAllow = PackageSpecOrExtended<KrateAllow>;
+
+#[cfg_attr(test, derive(serde::Serialize))]
+pub(crate) struct KrateSkip {
+    pub reason: Option<Reason>,
+    pub source: Option<UrlSpan>,
+    pub under: Option<Spanned<String>>,
+}
+
+pub(crate) type ValidKrateSkip = PackageSpecOrExtended<KrateSkip>;
+
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct Features {
     pub allow: Spanned<Vec<Spanned<String>>>,
@@ -877,37 +2166,110 @@ pub struct ValidBypass {
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct ValidBuildConfig {
     pub allow_build_scripts: Option<Vec<PackageSpec>>,
+    pub allow_proc_macros: Option<Vec<PackageSpec>>,
+    pub proc_macros: LintLevel,
     pub executables: LintLevel,
     pub script_extensions: ValidGlobSet,
+    pub deny_globs: ValidGlobSet,
     pub bypass: Vec<ValidBypass>,
     pub include_dependencies: bool,
+    pub include_all: bool,
     pub include_workspace: bool,
     pub include_archives: bool,
     pub interpreted: LintLevel,
 }
 
 pub type ValidTreeSkip = PackageSpecOrExtended<TreeSkipExtended>;
-pub type SpecAndReason = PackageSpecOrExtended<Reason>;
+
+/// A config entry, along with the file it was declared in, as entries can be
+/// loaded from files included by the config
+#[cfg_attr(test, derive(serde::Serialize))]
+#[cfg_attr(test, serde(transparent))]
+pub(crate) struct FileEntry<T> {
+    #[cfg_attr(test, serde(skip))]
+    pub file_id: FileId,
+    pub entry: T,
+}
+
+/// The diagnostic codes suppressed for the crates matching a `skip` entry
+#[cfg_attr(test, derive(serde::Serialize))]
+pub(crate) struct WaivedCodes {
+    pub spec: PackageSpec,
+    pub codes: Vec<String>,
+}
 
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct ValidConfig {
     pub file_id: FileId,
+    /// The files included by the config, in the order they were loaded
+    #[cfg_attr(test, serde(skip))]
+    pub included_files: Vec<FileId>,
     pub multiple_versions: LintLevel,
+    pub multiple_versions_levels: Vec<MultipleVersionsLevel>,
     pub multiple_versions_include_dev: bool,
+    pub multiple_sources: LintLevel,
     pub highlight: GraphHighlight,
-    pub(crate) denied: Vec<ValidKrateBan>,
-    pub(crate) denied_multiple_versions: Vec<PackageSpec>,
-    pub(crate) allowed: Vec<SpecAndReason>,
+    pub multiple_versions_policy: MultipleVersionsPolicy,
+    pub roots: Vec<Spanned<String>>,
+    pub(crate) denied: Vec<FileEntry<ValidKrateBan>>,
+    pub(crate) denied_multiple_versions: Vec<FileEntry<PackageSpec>>,
+    pub(crate) allowed: Vec<FileEntry<ValidKrateAllow>>,
+    pub allow_exact: bool,
+    pub exclude_workspace: bool,
+    pub wrapper_notes: bool,
+    pub removal_notes: bool,
+    pub(crate) required: Vec<PackageSpec>,
     pub(crate) features: Vec<ValidKrateFeatures>,
     pub external_default_features: Option<Spanned<LintLevel>>,
     pub workspace_default_features: Option<Spanned<LintLevel>>,
-    pub(crate) skipped: Vec<SpecAndReason>,
+    pub deny_member_features: Vec<MemberFeatureBan>,
+    pub deny_features_global: Vec<Spanned<String>>,
+    pub deny_publishers: Vec<Spanned<String>>,
+    pub crate_owners: std::collections::BTreeMap<String, Vec<String>>,
+    pub denied_publisher: LintLevel,
+    pub(crate) deny_direct: Vec<PackageSpec>,
+    pub denied_direct_dependency: LintLevel,
+    pub(crate) skipped: Vec<FileEntry<ValidKrateSkip>>,
+    pub(crate) waived: Vec<FileEntry<WaivedCodes>>,
     pub(crate) tree_skipped: Vec<ValidTreeSkip>,
+    pub skip_tree_usage: LintLevel,
+    #[cfg_attr(test, serde(serialize_with = "serialize_urls"))]
+    pub(crate) skip_sources: Vec<UrlSpan>,
+    pub overrides: Vec<MemberOverride>,
     pub wildcards: LintLevel,
     pub allow_wildcard_paths: bool,
+    pub patches: PatchesConfig,
+    pub links: LintLevel,
+    pub duplicate_links: LintLevel,
+    pub build_and_normal: LintLevel,
+    pub typosquatting: LintLevel,
+    pub git_only: LintLevel,
+    pub replace: LintLevel,
+    pub requirement_drift: LintLevel,
+    pub workspace_inheritance: LintLevel,
+    pub prerelease: LintLevel,
+    pub prerelease_exceptions: Vec<PackageSpec>,
+    pub min_rust_version: Option<Spanned<semver::Version>>,
+    pub rust_version_too_new: LintLevel,
+    pub max_unique_dependencies: Option<Limit>,
+    pub max_total_crates: Option<Limit>,
+    pub max_glob_matches: Option<Limit>,
+    pub max_duplicates: Option<Limit>,
+    pub max_depth: Option<Limit>,
+    pub max_direct_deps: Option<Limit>,
+    pub max_crate_size: Option<SizeLimit>,
+    pub crate_size_exceptions: Vec<PackageSpec>,
     pub build: Option<ValidBuildConfig>,
 }
 
+#[cfg(test)]
+fn serialize_urls<S>(urls: &[UrlSpan], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_seq(urls.iter().map(|url| url.value.as_str()))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -935,4 +2297,231 @@ mod test {
 
         insta::assert_json_snapshot!(validated);
     }
+
+    /// Ensures a misspelled field is reported with a label pointing at the
+    /// offending key, rather than just failing to deserialize
+    #[test]
+    fn unknown_fields_are_spanned() {
+        const CFG: &str = "multiple-versions = 'deny'\nmultiple-versiosn = 'warn'\n";
+
+        let mut value = toml_span::parse(CFG).unwrap();
+        let Err(err) = Config::deserialize(&mut value) else {
+            panic!("expected the misspelled field to be rejected");
+        };
+
+        let mut files = crate::diag::Files::new();
+        let id = files.add("bans.toml", CFG.to_owned());
+
+        let start = CFG.find("multiple-versiosn").unwrap();
+        let key = start..start + "multiple-versiosn".len();
+
+        assert!(err
+            .errors
+            .into_iter()
+            .map(|err| err.to_diagnostic(id))
+            .any(|diag| diag.labels.iter().any(|label| label.range == key)));
+    }
+
+    #[test]
+    fn verifies_config_checksum() {
+        const CFG: &str = "[bans]\nmultiple-versions = 'deny'\n";
+
+        let mut files = crate::diag::Files::new();
+        let id = files.add("deny.toml", CFG.to_owned());
+
+        let expected = Checksum::calculate(CFG.as_bytes());
+        assert_eq!(
+            expected.to_string().parse::<Checksum>(),
+            Ok(expected.clone())
+        );
+
+        let mut diagnostics = Vec::new();
+        assert!(crate::cfg::verify_checksum(
+            &mut ValidationContext {
+                cfg_id: id,
+                files: &mut files,
+                diagnostics: &mut diagnostics,
+            },
+            &expected,
+        ));
+        assert!(diagnostics.is_empty());
+
+        let modified = files.add("deny.toml", CFG.replace("deny", "warn"));
+        assert!(!crate::cfg::verify_checksum(
+            &mut ValidationContext {
+                cfg_id: modified,
+                files: &mut files,
+                diagnostics: &mut diagnostics,
+            },
+            &expected,
+        ));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].code.as_deref(),
+            Some("config-checksum-mismatch")
+        );
+    }
+
+    #[test]
+    fn allow_deny_conflicts_are_version_aware() {
+        let conflicts = |cfg: &str| {
+            let cd = ConfigData::<Config>::load_str("bans.toml", cfg);
+
+            let mut conflicts = Vec::new();
+            let _: ValidConfig = cd.validate_with_diags(
+                |b| b,
+                |_files, diags| {
+                    conflicts.extend(diags.into_iter().filter(|d| {
+                        d.message == "a crate was specified in both `allow` and `deny`"
+                    }));
+                },
+            );
+            conflicts
+        };
+
+        let overlapping = conflicts("deny = ['foo:>=1']\nallow = ['foo:=1.5']\n");
+        assert_eq!(overlapping.len(), 1);
+        assert_eq!(
+            overlapping[0].notes,
+            ["versions matched by both 'foo = >=1' and 'foo = =1.5' are ambiguous"]
+        );
+
+        assert!(conflicts("deny = ['foo:<1']\nallow = ['foo:=1.5']\n").is_empty());
+    }
+
+    #[test]
+    fn loads_includes() {
+        let cd = ConfigData::<Config>::load("tests/cfg/includes/root.toml");
+        let root = cd.id;
+        let validated: ValidConfig = cd.validate(|b| b);
+
+        let denied: Vec<_> = validated
+            .denied
+            .iter()
+            .map(|d| d.entry.spec.name.value.as_str())
+            .collect();
+        assert_eq!(denied, ["openssl", "ring"]);
+
+        // Each entry retains the file it was declared in
+        let base = validated.denied[1].file_id;
+        assert_eq!(validated.denied[0].file_id, root);
+        assert_ne!(base, root);
+        assert_eq!(validated.allowed.len(), 1);
+        assert_eq!(validated.allowed[0].file_id, base);
+
+        // Includes are loaded transitively
+        assert_eq!(validated.skipped.len(), 1);
+        assert_eq!(validated.skipped[0].entry.spec.name.value, "windows-sys");
+        assert_ne!(validated.skipped[0].file_id, root);
+        assert_ne!(validated.skipped[0].file_id, base);
+
+        // The included files are recorded so they can be checksummed along
+        // with the config
+        assert_eq!(
+            validated.included_files,
+            [base, validated.skipped[0].file_id]
+        );
+    }
+
+    #[test]
+    fn detects_include_cycles() {
+        let cd = ConfigData::<Config>::load("tests/cfg/includes/cycle-a.toml");
+
+        let mut cycles = Vec::new();
+        let validated: ValidConfig = cd.validate_with_diags(
+            |b| b,
+            |_files, diags| {
+                cycles.extend(
+                    diags
+                        .into_iter()
+                        .filter(|d| d.message.starts_with("include cycle detected")),
+                );
+            },
+        );
+
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(
+            cycles[0].message,
+            "include cycle detected for 'cycle-a.toml'"
+        );
+
+        // The entries from each file are still only loaded once
+        assert_eq!(validated.denied.len(), 2);
+    }
+
+    #[test]
+    fn warns_on_expired_entries() {
+        let expired = |cfg: &str| {
+            let cd = ConfigData::<Config>::load_str("bans.toml", cfg);
+
+            let mut expired = Vec::new();
+            let _: ValidConfig = cd.validate_with_diags(
+                |b| b,
+                |_files, diags| {
+                    expired.extend(diags.into_iter().filter(|d| d.message.contains("expired")));
+                },
+            );
+            expired
+        };
+
+        let diags = expired(
+            "skip = [{ crate = 'foo', expires = '2000-01-01' }]\ndeny = [{ crate = 'bar', reason = 'old', expires = '2000-01-01' }]\n",
+        );
+        assert_eq!(diags.len(), 2);
+        assert!(diags
+            .iter()
+            .all(|d| d.severity == crate::diag::Severity::Warning));
+        assert_eq!(
+            diags[0].message,
+            "`deny` entry expired on 2000-01-01 and no longer takes effect"
+        );
+
+        assert!(expired("skip = [{ crate = 'foo', expires = '9999-12-31' }]\n").is_empty());
+
+        let denied = expired(
+            "expired = 'deny'\nskip = [{ crate = 'foo', reason = 'old', expires = '2000-01-01' }]\n",
+        );
+        assert_eq!(denied.len(), 1);
+        assert_eq!(denied[0].severity, crate::diag::Severity::Error);
+    }
+
+    #[test]
+    fn drops_expired_entries() {
+        let validate = |cfg: &str| {
+            let cd = ConfigData::<Config>::load_str("bans.toml", cfg);
+
+            let mut expired = Vec::new();
+            let validated: ValidConfig = cd.validate_with_diags(
+                |b| b,
+                |_files, diags| {
+                    expired.extend(diags.into_iter().filter(|d| d.message.contains("expired")));
+                },
+            );
+            (validated, expired)
+        };
+
+        let (validated, expired) = validate(
+            "deny = [{ crate = 'qux', expires = '2000-01-01' }]\nskip = [{ crate = 'foo', expires = '2000-01-01' }, { crate = 'bar', expires = '9999-12-31' }]\nskip-tree = [{ crate = 'baz', expires = '2000-01-01' }]\n",
+        );
+        assert!(validated.denied.is_empty());
+        assert_eq!(validated.skipped.len(), 1);
+        assert_eq!(validated.skipped[0].entry.spec.name.value, "bar");
+        assert!(validated.tree_skipped.is_empty());
+
+        assert_eq!(expired.len(), 3);
+        assert_eq!(
+            expired[1].message,
+            "`skip` entry expired on 2000-01-01 and no longer takes effect"
+        );
+        assert_eq!(
+            expired[2].message,
+            "`skip-tree` entry expired on 2000-01-01 and no longer takes effect"
+        );
+
+        // Expired entries are dropped even if they aren't reported
+        let (validated, expired) =
+            validate("expired = 'allow'\nskip = [{ crate = 'foo', expires = '2000-01-01' }]\n");
+        assert!(validated.skipped.is_empty());
+        assert!(expired.is_empty());
+    }
 }
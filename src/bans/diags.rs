@@ -33,6 +33,7 @@ pub enum Code {
     SkippedByRoot,
     UnmatchedSkipRoot,
     BuildScriptNotAllowed,
+    ProcMacroNotAllowed,
     ExactFeaturesMismatch,
     FeatureNotExplicitlyAllowed,
     FeatureBanned,
@@ -51,6 +52,36 @@ pub enum Code {
     UnmatchedPathBypass,
     UnmatchedGlob,
     UnusedWrapper,
+    TooManyUniqueDependencies,
+    MultipleSources,
+    TooManyCrates,
+    BannedByGlob,
+    LinksNativeLibrary,
+    DuplicateBudget,
+    DependencyTooDeep,
+    Prerelease,
+    RequiredNotFound,
+    UnexpectedPatch,
+    PatchNotFound,
+    Replaced,
+    RequirementDrift,
+    NotInherited,
+    SkipTreeUsage,
+    DuplicateLinks,
+    Unapproved,
+    CrateTooLarge,
+    TooManyDirectDependencies,
+    UnmatchedRoot,
+    RustVersionTooNew,
+    UnmatchedOverride,
+    BuildAndNormal,
+    DeniedMemberFeature,
+    FeatureBannedGlobally,
+    DeniedPublisher,
+    PossibleTyposquat,
+    DeniedByGlob,
+    GitOnly,
+    DeniedDirectDependency,
 }
 
 impl From<Code> for String {
@@ -82,11 +113,33 @@ impl SpecAndReason {
 
         v
     }
+
+    /// Gets a note with the reason for the entry, if one was provided, so
+    /// that it is part of the diagnostic even when the config isn't shown
+    pub(crate) fn reason_note(&self) -> Option<String> {
+        self.reason
+            .as_ref()
+            .map(|reason| format!("reason: {}", reason.0.value))
+    }
+
+    /// Gets a note with the suggested replacement for the crate, if one was
+    /// provided
+    pub(crate) fn use_instead_note(&self) -> Option<String> {
+        self.use_instead
+            .as_ref()
+            .map(|ui| format!("use '{}' instead", ui.value))
+    }
 }
 
 pub(crate) struct ExplicitlyBanned<'a> {
     pub(crate) krate: &'a Krate,
     pub(crate) ban_cfg: &'a SpecAndReason,
+    /// The dependency edges, any one of which could be removed to remove the
+    /// banned crate from the graph, nearest to the banned crate first
+    pub(crate) fixes: Vec<(&'a Krate, &'a Krate)>,
+    /// The parents that only depend on the banned crate due to an optional
+    /// dependency, along with the features that enabled it
+    pub(crate) enabled_by: Vec<(&'a Krate, Vec<String>)>,
 }
 
 impl<'a> From<ExplicitlyBanned<'a>> for Diag {
@@ -95,6 +148,62 @@ impl<'a> From<ExplicitlyBanned<'a>> for Diag {
             .with_message(format!("crate '{}' is explicitly banned", eb.krate))
             .with_code(Code::Banned)
             .with_labels(eb.ban_cfg.to_labels(Some("banned here")))
+            .with_notes(
+                eb.ban_cfg
+                    .reason_note()
+                    .into_iter()
+                    .chain(eb.ban_cfg.use_instead_note())
+                    .chain(eb.fixes.into_iter().map(|(parent, dep)| {
+                        format!(
+                            "remove dependency '{parent}' -> '{dep}' to eliminate '{}' entirely",
+                            eb.krate
+                        )
+                    }))
+                    .chain(eb.enabled_by.into_iter().map(|(parent, features)| {
+                        format!(
+                            "optional dependency '{parent}' -> '{}' is enabled by the {} feature(s) of '{parent}'",
+                            eb.krate,
+                            features
+                                .iter()
+                                .map(|f| format!("'{f}'"))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                        )
+                    }))
+                    .collect(),
+            )
+            .into()
+    }
+}
+
+pub(crate) struct BannedByGlob<'a> {
+    pub(crate) ban_cfg: &'a SpecAndReason,
+    pub(crate) krates: Vec<&'a Krate>,
+    pub(crate) limit_cfg: CfgCoord,
+    pub(crate) limit: usize,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<BannedByGlob<'a>> for Diag {
+    fn from(bbg: BannedByGlob<'a>) -> Self {
+        let mut labels = bbg.ban_cfg.to_labels(Some("banned here"));
+        labels.push(Label::secondary(bbg.limit_cfg.file, bbg.limit_cfg.span).with_message("limit"));
+
+        Diagnostic::new(bbg.severity)
+            .with_message(format!(
+                "{} crates are banned by '{}', exceeding the limit of {}",
+                bbg.krates.len(),
+                bbg.ban_cfg.spec.name.value,
+                bbg.limit,
+            ))
+            .with_code(Code::BannedByGlob)
+            .with_labels(labels)
+            .with_notes(
+                bbg.krates
+                    .into_iter()
+                    .map(|krate| format!("crate '{krate}' is explicitly banned"))
+                    .collect(),
+            )
             .into()
     }
 }
@@ -127,11 +236,293 @@ impl<'a> From<NotAllowed<'a>> for Diag {
     }
 }
 
+pub(crate) struct LinksNativeLibrary<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) krate_coord: KrateCoord,
+    pub(crate) links: &'a str,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<LinksNativeLibrary<'a>> for Diag {
+    fn from(lnl: LinksNativeLibrary<'a>) -> Self {
+        Diagnostic::new(lnl.severity)
+            .with_message(format!(
+                "crate '{}' links the native library '{}'",
+                lnl.krate, lnl.links
+            ))
+            .with_code(Code::LinksNativeLibrary)
+            .with_labels(vec![lnl
+                .krate_coord
+                .into_label()
+                .with_message("links a native library")])
+            .with_notes(vec![format!("declared via `links = \"{}\"`", lnl.links)])
+            .into()
+    }
+}
+
+pub(crate) struct Prerelease<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) krate_coord: KrateCoord,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<Prerelease<'a>> for Diag {
+    fn from(pr: Prerelease<'a>) -> Self {
+        Diagnostic::new(pr.severity)
+            .with_message(format!(
+                "crate '{}' is a pre-release version, which are not allowed",
+                pr.krate
+            ))
+            .with_code(Code::Prerelease)
+            .with_labels(vec![pr
+                .krate_coord
+                .into_label()
+                .with_message("pre-release version")])
+            .into()
+    }
+}
+
+pub(crate) struct Unapproved<'a> {
+    pub(crate) krates: Vec<&'a Krate>,
+}
+
+impl<'a> From<Unapproved<'a>> for Diag {
+    fn from(un: Unapproved<'a>) -> Self {
+        Diagnostic::new(Severity::Error)
+            .with_message(format!(
+                "{} crate{} not allowed with an exact version",
+                un.krates.len(),
+                if un.krates.len() == 1 { " is" } else { "s are" },
+            ))
+            .with_code(Code::Unapproved)
+            .with_notes(
+                un.krates
+                    .into_iter()
+                    .map(|krate| {
+                        format!("add '{}@{}' to the allow list", krate.name, krate.version)
+                    })
+                    .collect(),
+            )
+            .into()
+    }
+}
+
+pub(crate) struct DuplicateLinks<'a> {
+    pub(crate) library: &'a str,
+    pub(crate) wrappers: Vec<&'a Krate>,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<DuplicateLinks<'a>> for Diag {
+    fn from(dl: DuplicateLinks<'a>) -> Self {
+        Diagnostic::new(dl.severity)
+            .with_message(format!(
+                "native library '{}' is wrapped by {} crates",
+                dl.library,
+                dl.wrappers.len()
+            ))
+            .with_code(Code::DuplicateLinks)
+            .with_notes(
+                dl.wrappers
+                    .iter()
+                    .map(|krate| match &krate.links {
+                        Some(links) => format!("'{krate}' links '{links}'"),
+                        None => format!("'{krate}' is a -sys crate"),
+                    })
+                    .collect(),
+            )
+            .into()
+    }
+}
+
+pub(crate) struct BuildAndNormal<'a> {
+    /// The shortest path from a workspace member to the crate as a normal
+    /// dependency
+    pub(crate) normal_path: Vec<&'a Krate>,
+    /// The shortest path from a workspace member to the crate as a build
+    /// dependency
+    pub(crate) build_path: Vec<&'a Krate>,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<BuildAndNormal<'a>> for Diag {
+    fn from(ban: BuildAndNormal<'a>) -> Self {
+        let krate = ban.normal_path.last().expect("path is never empty");
+
+        // Display the paths inverted, from the crate to the workspace member,
+        // the same as the inclusion graphs
+        let inverse = |path: &[&Krate]| {
+            path.iter()
+                .rev()
+                .map(|krate| format!("'{krate}'"))
+                .collect::<Vec<_>>()
+                .join(" <- ")
+        };
+
+        Diagnostic::new(ban.severity)
+            .with_message(format!(
+                "crate '{krate}' is both a normal and a build dependency, and is compiled for each"
+            ))
+            .with_code(Code::BuildAndNormal)
+            .with_notes(vec![
+                format!("normal dependency: {}", inverse(&ban.normal_path)),
+                format!("build dependency: {}", inverse(&ban.build_path)),
+            ])
+            .into()
+    }
+}
+
+pub(crate) struct PossibleTyposquat<'a> {
+    pub(crate) krate: &'a Krate,
+    /// The popular crate the name is similar to
+    pub(crate) similar_to: &'a str,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<PossibleTyposquat<'a>> for Diag {
+    fn from(pt: PossibleTyposquat<'a>) -> Self {
+        Diagnostic::new(pt.severity)
+            .with_message(format!(
+                "crate '{}' has a name similar to the popular crate '{}'",
+                pt.krate, pt.similar_to
+            ))
+            .with_code(Code::PossibleTyposquat)
+            .with_notes(vec![format!(
+                "verify that '{}' is the intended crate and not a typosquat of '{}'",
+                pt.krate.name, pt.similar_to
+            )])
+            .into()
+    }
+}
+
+pub(crate) struct GitOnly<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<GitOnly<'a>> for Diag {
+    fn from(go: GitOnly<'a>) -> Self {
+        Diagnostic::new(go.severity)
+            .with_message(format!(
+                "crate '{}' is only available from a git repository",
+                go.krate
+            ))
+            .with_code(Code::GitOnly)
+            .with_notes(vec![
+                "unlike a registry, the repository can be rewritten or deleted, and versions can't be yanked"
+                    .to_owned(),
+            ])
+            .into()
+    }
+}
+
+pub(crate) struct Replaced<'a> {
+    pub(crate) krate: &'a Krate,
+    /// The key of the `[replace]` entry
+    pub(crate) entry: &'a str,
+    pub(crate) manifest: &'a crate::Path,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<Replaced<'a>> for Diag {
+    fn from(rep: Replaced<'a>) -> Self {
+        Diagnostic::new(rep.severity)
+            .with_message(format!(
+                "crate '{}' is resolved through a [replace] entry",
+                rep.krate
+            ))
+            .with_code(Code::Replaced)
+            .with_notes(vec![
+                format!("replaced by '{}' in '{}'", rep.entry, rep.manifest),
+                "[replace] is deprecated, use [patch] instead".to_owned(),
+            ])
+            .into()
+    }
+}
+
+pub(crate) struct RequirementDrift<'a> {
+    pub(crate) name: &'a str,
+    /// The workspace members that declare the dependency, and their
+    /// declaration of it
+    pub(crate) declared: Vec<(&'a Krate, &'a krates::cm::Dependency)>,
+    pub(crate) num_reqs: usize,
+    pub(crate) severity: Severity,
+    pub(crate) cargo_spans: &'a crate::diag::CargoSpans,
+}
+
+impl<'a> From<RequirementDrift<'a>> for Diag {
+    fn from(rd: RequirementDrift<'a>) -> Self {
+        let labels = rd
+            .declared
+            .iter()
+            .filter_map(|(member, dep)| {
+                let (file_id, map) = rd.cargo_spans.get(&member.id)?;
+                let span = map.get(&dep.name)?;
+                Some(Label::primary(*file_id, span.clone()).with_message(dep.req.to_string()))
+            })
+            .collect();
+
+        Diagnostic::new(rd.severity)
+            .with_message(format!(
+                "workspace members declare '{}' with {} different version requirements",
+                rd.name, rd.num_reqs,
+            ))
+            .with_code(Code::RequirementDrift)
+            .with_labels(labels)
+            .with_notes(
+                rd.declared
+                    .iter()
+                    .map(|(member, dep)| format!("'{}' requires '{}'", member.name, dep.req))
+                    .collect(),
+            )
+            .into()
+    }
+}
+
+pub(crate) struct NotInherited<'a> {
+    pub(crate) member: &'a Krate,
+    /// The name of the dependency in the member's manifest
+    pub(crate) dep: &'a str,
+    pub(crate) severity: Severity,
+    pub(crate) cargo_spans: &'a crate::diag::CargoSpans,
+}
+
+impl<'a> From<NotInherited<'a>> for Diag {
+    fn from(ni: NotInherited<'a>) -> Self {
+        let labels = ni
+            .cargo_spans
+            .get(&ni.member.id)
+            .and_then(|(file_id, map)| {
+                Some(
+                    Label::primary(*file_id, map.get(ni.dep)?.clone())
+                        .with_message("declared here"),
+                )
+            })
+            .into_iter()
+            .collect();
+
+        Diagnostic::new(ni.severity)
+            .with_message(format!(
+                "workspace member '{}' declares '{}' itself instead of inheriting it from the workspace",
+                ni.member.name, ni.dep,
+            ))
+            .with_code(Code::NotInherited)
+            .with_labels(labels)
+            .with_notes(vec![format!(
+                "use `{} = {{ workspace = true }}` in '{}'",
+                ni.dep, ni.member.manifest_path
+            )])
+            .into()
+    }
+}
+
 pub(crate) struct Duplicates<'a> {
     pub(crate) krate_name: &'a str,
     pub(crate) num_dupes: usize,
     pub(crate) krates_coord: KrateCoord,
     pub(crate) severity: Severity,
+    /// The `cargo update` commands that would remove some of the duplicates
+    pub(crate) updates: Vec<String>,
 }
 
 impl<'a> From<Duplicates<'a>> for Diag {
@@ -146,6 +537,37 @@ impl<'a> From<Duplicates<'a>> for Diag {
                 .krates_coord
                 .into_label()
                 .with_message("lock entries")])
+            .with_notes(
+                dup.updates
+                    .into_iter()
+                    .map(|update| format!("run `{update}` to remove a duplicate"))
+                    .collect(),
+            )
+            .into()
+    }
+}
+
+pub(crate) struct MultipleSources<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) sources: Vec<KrateCoord>,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<MultipleSources<'a>> for Diag {
+    fn from(ms: MultipleSources<'a>) -> Self {
+        Diagnostic::new(ms.severity)
+            .with_message(format!(
+                "crate '{}' is present from {} different sources",
+                ms.krate,
+                ms.sources.len(),
+            ))
+            .with_code(Code::MultipleSources)
+            .with_labels(
+                ms.sources
+                    .into_iter()
+                    .map(|src| src.into_label().with_message("source"))
+                    .collect(),
+            )
             .into()
     }
 }
@@ -164,6 +586,7 @@ impl<'a> From<Skipped<'a>> for Diag {
             ))
             .with_code(Code::Skipped)
             .with_labels(sk.skip_cfg.to_labels(Some("skipped here")))
+            .with_notes(sk.skip_cfg.reason_note().into_iter().collect())
             .into()
     }
 }
@@ -230,6 +653,85 @@ impl<'a> From<UnmatchedSkip<'a>> for Diag {
     }
 }
 
+pub(crate) struct RequiredNotFound<'a> {
+    pub(crate) spec: &'a crate::cfg::PackageSpec,
+    pub(crate) file_id: FileId,
+}
+
+impl<'a> From<RequiredNotFound<'a>> for Diag {
+    fn from(rnf: RequiredNotFound<'a>) -> Self {
+        Diagnostic::new(Severity::Error)
+            .with_message(format!(
+                "required crate '{}' was not found in the graph",
+                rnf.spec
+            ))
+            .with_code(Code::RequiredNotFound)
+            .with_labels(vec![
+                Label::primary(rnf.file_id, rnf.spec.name.span).with_message("required here")
+            ])
+            .into()
+    }
+}
+
+pub(crate) struct UnexpectedPatch<'a> {
+    pub(crate) krate: &'a Krate,
+    /// The registry dependencies that were redirected to the crate, and the
+    /// crates that declared them
+    pub(crate) patched: Vec<(&'a Krate, &'a krates::cm::Dependency)>,
+    pub(crate) severity: Severity,
+    pub(crate) cargo_spans: &'a crate::diag::CargoSpans,
+}
+
+impl<'a> From<UnexpectedPatch<'a>> for Diag {
+    fn from(up: UnexpectedPatch<'a>) -> Self {
+        let labels = up
+            .patched
+            .iter()
+            .filter_map(|(dependent, dep)| {
+                let (file_id, map) = up.cargo_spans.get(&dependent.id)?;
+                let span = map.get(&dep.name)?;
+                Some(Label::primary(*file_id, span.clone()).with_message("patched dependency"))
+            })
+            .collect();
+
+        let patch = up
+            .krate
+            .source
+            .as_ref()
+            .map_or_else(|| up.krate.manifest_path.to_string(), |src| src.to_string());
+
+        Diagnostic::new(up.severity)
+            .with_message(format!(
+                "crate '{}' replaces a registry crate via [patch], but is not a required patch",
+                up.krate
+            ))
+            .with_code(Code::UnexpectedPatch)
+            .with_labels(labels)
+            .with_notes(vec![format!("patched with '{patch}'")])
+            .into()
+    }
+}
+
+pub(crate) struct PatchNotFound<'a> {
+    pub(crate) spec: &'a crate::cfg::PackageSpec,
+    pub(crate) file_id: FileId,
+}
+
+impl<'a> From<PatchNotFound<'a>> for Diag {
+    fn from(pnf: PatchNotFound<'a>) -> Self {
+        Diagnostic::new(Severity::Error)
+            .with_message(format!(
+                "crate '{}' is required to be patched, but no patch for it was found",
+                pnf.spec
+            ))
+            .with_code(Code::PatchNotFound)
+            .with_labels(vec![
+                Label::primary(pnf.file_id, pnf.spec.name.span).with_message("required patch")
+            ])
+            .into()
+    }
+}
+
 pub(crate) struct UnusedWrapper {
     pub(crate) wrapper_cfg: CfgCoord,
 }
@@ -248,7 +750,7 @@ impl From<UnusedWrapper> for Diag {
 }
 
 pub(crate) struct BannedAllowedByWrapper<'a> {
-    pub(crate) ban_cfg: CfgCoord,
+    pub(crate) ban_cfg: &'a SpecAndReason,
     pub(crate) banned_krate: &'a Krate,
     pub(crate) ban_exception_cfg: CfgCoord,
     pub(crate) wrapper_krate: &'a Krate,
@@ -256,18 +758,20 @@ pub(crate) struct BannedAllowedByWrapper<'a> {
 
 impl<'a> From<BannedAllowedByWrapper<'a>> for Diag {
     fn from(baw: BannedAllowedByWrapper<'a>) -> Self {
+        let mut labels = baw.ban_cfg.to_labels(Some("banned here"));
+        labels.push(
+            baw.ban_exception_cfg
+                .into_label()
+                .with_message("allowed wrapper"),
+        );
+
         Diagnostic::new(Severity::Note)
             .with_message(format!(
                 "banned crate '{}' allowed by wrapper '{}'",
                 baw.banned_krate, baw.wrapper_krate
             ))
             .with_code(Code::AllowedByWrapper)
-            .with_labels(vec![
-                baw.ban_cfg.into_label().with_message("banned here"),
-                baw.ban_exception_cfg
-                    .into_label()
-                    .with_message("allowed wrapper"),
-            ])
+            .with_labels(labels)
             .into()
     }
 }
@@ -323,6 +827,56 @@ impl From<UnmatchedSkipRoot> for Diag {
     }
 }
 
+pub(crate) struct UnmatchedOverride {
+    pub(crate) member_cfg: CfgCoord,
+}
+
+impl From<UnmatchedOverride> for Diag {
+    fn from(uo: UnmatchedOverride) -> Self {
+        Diagnostic::new(Severity::Warning)
+            .with_message("overrides were specified for a crate that is not a workspace member")
+            .with_code(Code::UnmatchedOverride)
+            .with_labels(vec![uo
+                .member_cfg
+                .into_label()
+                .with_message("no workspace member has this name")])
+            .into()
+    }
+}
+
+pub(crate) struct SkipTreeUsage<'a> {
+    pub(crate) skip_root_cfg: &'a SpecAndReason,
+    pub(crate) suppressed: usize,
+    pub(crate) total: usize,
+    /// The deepest suppressed crate, if any were suppressed
+    pub(crate) depth: Option<usize>,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<SkipTreeUsage<'a>> for Diag {
+    fn from(stu: SkipTreeUsage<'a>) -> Self {
+        let configured = stu
+            .max_depth
+            .map_or_else(|| "unlimited".to_owned(), |md| md.to_string());
+
+        let mut notes = vec![format!("configured depth: {configured}")];
+        if let Some(depth) = stu.depth {
+            notes.push(format!("deepest suppressed crate: {depth}"));
+        }
+
+        Diagnostic::new(stu.severity)
+            .with_message(format!(
+                "skip tree root '{}' suppressed {} of the {} crates in its tree",
+                stu.skip_root_cfg.spec, stu.suppressed, stu.total
+            ))
+            .with_code(Code::SkipTreeUsage)
+            .with_labels(stu.skip_root_cfg.to_labels(Some("skip tree root")))
+            .with_notes(notes)
+            .into()
+    }
+}
+
 pub(crate) struct BuildScriptNotAllowed<'a> {
     pub(crate) krate: &'a Krate,
 }
@@ -339,6 +893,23 @@ impl<'a> From<BuildScriptNotAllowed<'a>> for Diag {
     }
 }
 
+pub(crate) struct ProcMacroNotAllowed<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<ProcMacroNotAllowed<'a>> for Diag {
+    fn from(pm: ProcMacroNotAllowed<'a>) -> Self {
+        Diagnostic::new(pm.severity)
+            .with_message(format!(
+                "crate '{}' is a proc macro but is not allowed to be one",
+                pm.krate
+            ))
+            .with_code(Code::ProcMacroNotAllowed)
+            .into()
+    }
+}
+
 pub(crate) struct ExactFeaturesMismatch<'a> {
     pub(crate) missing_allowed: Vec<CfgCoord>,
     pub(crate) not_allowed: &'a [&'a str],
@@ -460,6 +1031,105 @@ impl From<FeatureBanned<'_>> for Diag {
     }
 }
 
+pub(crate) struct FeatureBannedGlobally<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) feature: &'a str,
+    pub(crate) feature_cfg: CfgCoord,
+    /// Descriptions of what directly enables the feature
+    pub(crate) enablers: Vec<String>,
+}
+
+impl From<FeatureBannedGlobally<'_>> for Diag {
+    fn from(fbg: FeatureBannedGlobally<'_>) -> Diag {
+        let diag = Diagnostic::new(Severity::Error)
+            .with_message(format!(
+                "feature '{}' for crate '{}' is denied for every crate",
+                fbg.feature, fbg.krate,
+            ))
+            .with_code(Code::FeatureBannedGlobally)
+            .with_labels(vec![fbg
+                .feature_cfg
+                .into_label()
+                .with_message("feature denied here")])
+            .with_notes(fbg.enablers);
+
+        Diag {
+            diag,
+            graph_nodes: std::iter::once(GraphNode {
+                kid: fbg.krate.id.clone(),
+                feature: Some(fbg.feature.to_owned()),
+            })
+            .collect(),
+            extra: None,
+            with_features: true,
+        }
+    }
+}
+
+pub(crate) struct DeniedPublisher<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) publisher: &'a str,
+    pub(crate) publisher_cfg: CfgCoord,
+    pub(crate) severity: Severity,
+}
+
+impl From<DeniedPublisher<'_>> for Diag {
+    fn from(dp: DeniedPublisher<'_>) -> Diag {
+        Diagnostic::new(dp.severity)
+            .with_message(format!(
+                "crate '{}' is owned by denied publisher '{}'",
+                dp.krate, dp.publisher,
+            ))
+            .with_code(Code::DeniedPublisher)
+            .with_labels(vec![dp
+                .publisher_cfg
+                .into_label()
+                .with_message("publisher denied here")])
+            .into()
+    }
+}
+
+pub(crate) struct DeniedDirectDependency<'a> {
+    pub(crate) member: &'a Krate,
+    pub(crate) dep: &'a Krate,
+    pub(crate) spec_cfg: CfgCoord,
+    pub(crate) cargo_spans: &'a crate::diag::CargoSpans,
+    pub(crate) severity: Severity,
+}
+
+impl From<DeniedDirectDependency<'_>> for Diag {
+    fn from(ddd: DeniedDirectDependency<'_>) -> Diag {
+        let labels = ddd
+            .cargo_spans
+            .get(&ddd.member.id)
+            .and_then(|(file_id, map)| {
+                Some(
+                    Label::primary(*file_id, map.get(&ddd.dep.name)?.clone())
+                        .with_message("declared here"),
+                )
+            })
+            .into_iter()
+            .chain(std::iter::once(
+                Label::secondary(ddd.spec_cfg.file, ddd.spec_cfg.span)
+                    .with_message("denied as a direct dependency here"),
+            ))
+            .collect();
+
+        Diagnostic::new(ddd.severity)
+            .with_message(format!(
+                "workspace member '{}' depends on '{}' directly, which is only allowed as a transitive dependency",
+                ddd.member.name, ddd.dep,
+            ))
+            .with_code(Code::DeniedDirectDependency)
+            .with_labels(labels)
+            .with_notes(vec![format!(
+                "declared in '{}'",
+                ddd.member.manifest_path
+            )])
+            .into()
+    }
+}
+
 pub(crate) struct UnknownFeature<'a> {
     pub(crate) krate: &'a Krate,
     pub(crate) feature: &'a Spanned<String>,
@@ -612,7 +1282,7 @@ impl From<GlobAllowance<'_>> for Diag {
 
 pub(crate) struct ChecksumMatch<'a> {
     pub(crate) path: HomePath<'a>,
-    pub(crate) checksum: &'a Spanned<super::cfg::Checksum>,
+    pub(crate) checksum: &'a Spanned<crate::cfg::Checksum>,
     pub(crate) severity: Option<Severity>,
     pub(crate) file_id: FileId,
 }
@@ -639,7 +1309,7 @@ impl From<ChecksumMatch<'_>> for Diag {
 
 pub(crate) struct ChecksumMismatch<'a> {
     pub(crate) path: HomePath<'a>,
-    pub(crate) checksum: &'a Spanned<super::cfg::Checksum>,
+    pub(crate) checksum: &'a Spanned<crate::cfg::Checksum>,
     pub(crate) severity: Option<Severity>,
     pub(crate) error: String,
     pub(crate) file_id: FileId,
@@ -696,6 +1366,29 @@ impl From<DeniedByExtension<'_>> for Diag {
     }
 }
 
+pub(crate) struct DeniedByGlob<'a> {
+    pub(crate) path: HomePath<'a>,
+    pub(crate) globs: Vec<&'a cfg::GlobPattern>,
+    pub(crate) file_id: FileId,
+}
+
+impl From<DeniedByGlob<'_>> for Diag {
+    fn from(dg: DeniedByGlob<'_>) -> Diag {
+        let diag = Diagnostic::new(Severity::Error)
+            .with_message("path disallowed by glob pattern")
+            .with_notes(vec![format!("path = '{}'", dg.path)])
+            .with_code(Code::DeniedByGlob)
+            .with_labels(globs_to_labels(dg.file_id, dg.globs));
+
+        Diag {
+            diag,
+            graph_nodes: Default::default(),
+            extra: None,
+            with_features: false,
+        }
+    }
+}
+
 pub(crate) struct DetectedExecutable<'a> {
     pub(crate) path: HomePath<'a>,
     pub(crate) interpreted: crate::LintLevel,
@@ -849,3 +1542,249 @@ impl<'a> From<UnmatchedGlob<'a>> for Diag {
             .into()
     }
 }
+
+pub(crate) struct TooManyUniqueDependencies<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) count: usize,
+    pub(crate) limit_cfg: CfgCoord,
+    pub(crate) limit: usize,
+    pub(crate) severity: Severity,
+    /// The crates accounting for the most unique dependencies, and how many
+    /// they account for
+    pub(crate) heaviest: Vec<(&'a Krate, usize)>,
+}
+
+impl<'a> From<TooManyUniqueDependencies<'a>> for Diag {
+    fn from(tmu: TooManyUniqueDependencies<'a>) -> Self {
+        Diagnostic::new(tmu.severity)
+            .with_message(format!(
+                "crate '{}' pulls in {} {} only reachable through it, exceeding the limit of {}",
+                tmu.krate,
+                tmu.count,
+                if tmu.count == 1 {
+                    "crate that is"
+                } else {
+                    "crates that are"
+                },
+                tmu.limit,
+            ))
+            .with_code(Code::TooManyUniqueDependencies)
+            .with_labels(vec![tmu.limit_cfg.into_label().with_message("limit")])
+            .with_notes(
+                tmu.heaviest
+                    .into_iter()
+                    .map(|(krate, count)| format!("'{krate}' accounts for {count}"))
+                    .collect(),
+            )
+            .into()
+    }
+}
+
+pub(crate) struct TooManyCrates<'a> {
+    pub(crate) count: usize,
+    pub(crate) limit_cfg: CfgCoord,
+    pub(crate) limit: usize,
+    pub(crate) severity: Severity,
+    /// The crates that are the only path to the most other crates, and how
+    /// many crates that is
+    pub(crate) heaviest: Vec<(&'a Krate, usize)>,
+}
+
+impl<'a> From<TooManyCrates<'a>> for Diag {
+    fn from(tmc: TooManyCrates<'a>) -> Self {
+        Diagnostic::new(tmc.severity)
+            .with_message(format!(
+                "found {} crates in the graph, exceeding the limit of {}",
+                tmc.count, tmc.limit,
+            ))
+            .with_code(Code::TooManyCrates)
+            .with_labels(vec![tmc.limit_cfg.into_label().with_message("limit")])
+            .with_notes(
+                tmc.heaviest
+                    .into_iter()
+                    .map(|(krate, count)| {
+                        format!(
+                            "'{krate}' pulls in {count} {} only reachable through it",
+                            if count == 1 {
+                                "crate that is"
+                            } else {
+                                "crates that are"
+                            }
+                        )
+                    })
+                    .collect(),
+            )
+            .into()
+    }
+}
+
+pub(crate) struct DuplicateBudget {
+    pub(crate) count: usize,
+    pub(crate) limit_cfg: CfgCoord,
+    pub(crate) limit: usize,
+    pub(crate) severity: Severity,
+}
+
+impl From<DuplicateBudget> for Diag {
+    fn from(db: DuplicateBudget) -> Self {
+        let (severity, msg) = if db.count > db.limit {
+            (db.severity, "exceeding")
+        } else {
+            (Severity::Note, "within")
+        };
+
+        Diagnostic::new(severity)
+            .with_message(format!(
+                "found {} {} with multiple versions, {msg} the budget of {}",
+                db.count,
+                if db.count == 1 { "crate" } else { "crates" },
+                db.limit,
+            ))
+            .with_code(Code::DuplicateBudget)
+            .with_labels(vec![db.limit_cfg.into_label().with_message("budget")])
+            .into()
+    }
+}
+
+pub(crate) struct TooManyDirectDependencies<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) count: usize,
+    pub(crate) limit_cfg: CfgCoord,
+    pub(crate) limit: usize,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<TooManyDirectDependencies<'a>> for Diag {
+    fn from(tmd: TooManyDirectDependencies<'a>) -> Self {
+        Diagnostic::new(tmd.severity)
+            .with_message(format!(
+                "crate '{}' has {} direct dependencies, exceeding the limit of {}",
+                tmd.krate, tmd.count, tmd.limit,
+            ))
+            .with_code(Code::TooManyDirectDependencies)
+            .with_labels(vec![tmd.limit_cfg.into_label().with_message("limit")])
+            .into()
+    }
+}
+
+pub(crate) struct CrateTooLarge<'a> {
+    pub(crate) krate: &'a Krate,
+    /// The unpacked size of the crate, in bytes
+    pub(crate) size: u64,
+    pub(crate) limit_cfg: CfgCoord,
+    pub(crate) limit: u64,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<CrateTooLarge<'a>> for Diag {
+    fn from(ctl: CrateTooLarge<'a>) -> Self {
+        Diagnostic::new(ctl.severity)
+            .with_message(format!(
+                "crate '{}' is {} bytes unpacked, exceeding the limit of {} bytes",
+                ctl.krate, ctl.size, ctl.limit,
+            ))
+            .with_code(Code::CrateTooLarge)
+            .with_labels(vec![ctl.limit_cfg.into_label().with_message("limit")])
+            .into()
+    }
+}
+
+pub(crate) struct RustVersionTooNew<'a> {
+    pub(crate) krate: &'a Krate,
+    /// The `rust-version` declared by the crate
+    pub(crate) rust_version: &'a semver::Version,
+    pub(crate) msrv_cfg: CfgCoord,
+    pub(crate) msrv: &'a semver::Version,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<RustVersionTooNew<'a>> for Diag {
+    fn from(rvtn: RustVersionTooNew<'a>) -> Self {
+        Diagnostic::new(rvtn.severity)
+            .with_message(format!(
+                "crate '{}' requires rust {}, which is newer than {}",
+                rvtn.krate, rvtn.rust_version, rvtn.msrv,
+            ))
+            .with_code(Code::RustVersionTooNew)
+            .with_labels(vec![rvtn
+                .msrv_cfg
+                .into_label()
+                .with_message("minimum rust version")])
+            .into()
+    }
+}
+
+pub(crate) struct DependencyTooDeep<'a> {
+    /// The shortest path from a workspace member to the crate
+    pub(crate) path: Vec<&'a Krate>,
+    pub(crate) limit_cfg: CfgCoord,
+    pub(crate) limit: usize,
+    pub(crate) severity: Severity,
+}
+
+impl<'a> From<DependencyTooDeep<'a>> for Diag {
+    fn from(dtd: DependencyTooDeep<'a>) -> Self {
+        let krate = dtd.path.last().expect("path is never empty");
+        let path: Vec<_> = dtd.path.iter().map(|krate| format!("'{krate}'")).collect();
+
+        Diagnostic::new(dtd.severity)
+            .with_message(format!(
+                "crate '{krate}' is {} {} from the nearest workspace member, exceeding the limit of {}",
+                path.len() - 1,
+                if path.len() == 2 { "edge" } else { "edges" },
+                dtd.limit,
+            ))
+            .with_code(Code::DependencyTooDeep)
+            .with_labels(vec![dtd.limit_cfg.into_label().with_message("limit")])
+            .with_notes(vec![format!("shortest path: {}", path.join(" -> "))])
+            .into()
+    }
+}
+
+pub(crate) struct UnmatchedRoot {
+    pub(crate) root_cfg: CfgCoord,
+}
+
+impl From<UnmatchedRoot> for Diag {
+    fn from(ur: UnmatchedRoot) -> Self {
+        Diagnostic::new(Severity::Warning)
+            .with_message("root was not found in the workspace")
+            .with_code(Code::UnmatchedRoot)
+            .with_labels(vec![ur
+                .root_cfg
+                .into_label()
+                .with_message("no workspace member has this name")])
+            .into()
+    }
+}
+
+pub(crate) struct DeniedMemberFeature<'a> {
+    pub(crate) member: &'a Krate,
+    pub(crate) ban: &'a crate::bans::cfg::MemberFeatureBan,
+    pub(crate) ban_cfg: CfgCoord,
+}
+
+impl<'a> From<DeniedMemberFeature<'a>> for Diag {
+    fn from(dmf: DeniedMemberFeature<'a>) -> Self {
+        Diagnostic::new(Severity::Error)
+            .with_message(format!(
+                "workspace member '{}' enables denied feature '{}/{}'",
+                dmf.member.name, dmf.ban.krate, dmf.ban.feature.value,
+            ))
+            .with_code(Code::DeniedMemberFeature)
+            .with_labels(vec![dmf.ban_cfg.into_label().with_message("denied here")])
+            .with_notes(
+                dmf.ban
+                    .reason
+                    .as_ref()
+                    .map(|reason| format!("reason: {}", reason.0.value))
+                    .into_iter()
+                    .chain(std::iter::once(format!(
+                        "declared in '{}'",
+                        dmf.member.manifest_path
+                    )))
+                    .collect(),
+            )
+            .into()
+    }
+}
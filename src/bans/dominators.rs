@@ -0,0 +1,128 @@
+use crate::Krates;
+use krates::petgraph as pg;
+
+/// The dominator tree of the crate graph.
+///
+/// A crate dominates another crate if every path from the workspace to the
+/// other crate goes through it, ie. the other crate would no longer be in the
+/// graph if the dominating crate was removed.
+pub(crate) struct Dominators {
+    /// The crates immediately dominated by each crate, sorted by the number of
+    /// crates they dominate in turn, descending
+    children: Vec<Vec<usize>>,
+    /// The number of crates dominated by each crate, not including itself
+    dominated: Vec<usize>,
+    /// The immediate dominator of each crate, `None` if the crate is only
+    /// dominated by the workspace itself
+    idoms: Vec<Option<usize>>,
+}
+
+impl Dominators {
+    pub(crate) fn new(krates: &Krates) -> Self {
+        type Id = pg::graph::NodeIndex<u32>;
+
+        let count = krates.len();
+
+        // A workspace can have multiple members, so we add a virtual root
+        // that depends on all of them
+        let mut graph = pg::Graph::<(), ()>::with_capacity(count + 1, count * 2);
+        for _ in 0..=count {
+            graph.add_node(());
+        }
+        let root = Id::new(count);
+
+        for member in krates.workspace_members() {
+            if let krates::Node::Krate { id, .. } = member {
+                if let Some(nid) = krates.nid_for_kid(id) {
+                    graph.add_edge(root, Id::new(nid.index()), ());
+                }
+            }
+        }
+
+        for i in 0..count {
+            for dep in krates.direct_dependencies(krates::NodeId::new(i)) {
+                graph.add_edge(Id::new(i), Id::new(dep.node_id.index()), ());
+            }
+        }
+
+        let doms = pg::algo::dominators::simple_fast(&graph, root);
+
+        let mut children = vec![Vec::new(); count];
+        let mut idoms = vec![None; count];
+        let mut roots = Vec::new();
+        for i in 0..count {
+            match doms.immediate_dominator(Id::new(i)) {
+                Some(idom) if idom != root => {
+                    children[idom.index()].push(i);
+                    idoms[i] = Some(idom.index());
+                }
+                _ => roots.push(i),
+            }
+        }
+
+        // Walk the tree depth first so that every crate is visited before its
+        // dominator, letting us accumulate the counts bottom up
+        let mut order = Vec::with_capacity(count);
+        let mut stack = roots;
+        while let Some(i) = stack.pop() {
+            order.push(i);
+            stack.extend(children[i].iter().copied());
+        }
+
+        let mut dominated = vec![0; count];
+        for &i in order.iter().rev() {
+            dominated[i] = children[i].iter().map(|c| dominated[*c] + 1).sum();
+        }
+
+        for kids in &mut children {
+            kids.sort_by(|a, b| dominated[*b].cmp(&dominated[*a]).then(a.cmp(b)));
+        }
+
+        Self {
+            children,
+            dominated,
+            idoms,
+        }
+    }
+
+    /// The number of crates that are only reachable through the specified
+    /// crate
+    #[inline]
+    pub(crate) fn dominated(&self, index: usize) -> usize {
+        self.dominated[index]
+    }
+
+    /// The crates immediately dominated by the specified crate, heaviest first,
+    /// along with the number of crates each of them accounts for, including
+    /// itself
+    #[inline]
+    pub(crate) fn heaviest(&self, index: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.children[index]
+            .iter()
+            .map(|c| (*c, self.dominated[*c] + 1))
+    }
+
+    /// The dependency edges that every path from the workspace to the
+    /// specified crate goes through, ie. removing any single one of them would
+    /// remove the crate from the graph entirely, nearest to the crate first
+    pub(crate) fn dominating_edges(&self, krates: &Krates, index: usize) -> Vec<(usize, usize)> {
+        std::iter::successors(Some(index), |i| self.idoms[*i])
+            .filter_map(|dom| {
+                // Crates can depend on the same crate multiple times, eg. as
+                // both a normal and a build dependency
+                let mut dependents: Vec<_> = krates
+                    .direct_dependents(krates::NodeId::new(dom))
+                    .into_iter()
+                    .map(|dd| dd.node_id.index())
+                    .collect();
+                dependents.sort_unstable();
+                dependents.dedup();
+
+                // Workspace members are always in the graph, regardless of
+                // whether other crates depend on them
+                (dependents.len() == 1 && !crate::is_workspace_member(krates, &krates[dom].id))
+                    .then(|| (dependents[0], dom))
+            })
+            .collect()
+    }
+}
@@ -1,5 +1,5 @@
 use super::cfg::GraphHighlight;
-use crate::{DepKind, Kid, Krate};
+use crate::{DepKind, Kid, Krate, Source};
 use anyhow::{Context, Error};
 use krates::petgraph as pg;
 use semver::Version;
@@ -78,6 +78,55 @@ struct EdgeAttributes<'a> {
 
 const INDENT: &str = "    ";
 
+/// The newest version available in a registry index for each crate, used to
+/// annotate duplicate versions that have a newer release they could be
+/// unified on
+#[derive(Default)]
+pub struct NewestVersions(BTreeMap<(String, Source), Version>);
+
+impl NewestVersions {
+    /// Sets the newest available version for the crate's name and source
+    pub fn insert(&mut self, krate: &Krate, newest: Version) {
+        if let Some(src) = &krate.source {
+            self.0.insert((krate.name.clone(), src.clone()), newest);
+        }
+    }
+
+    /// Gets the newest available version of the crate, if it is newer than
+    /// the crate's own version
+    fn newer_than(&self, krate: &Krate) -> Option<&Version> {
+        let src = krate.source.as_ref()?;
+        self.0
+            .get(&(krate.name.clone(), src.clone()))
+            .filter(|newest| **newest > krate.version)
+    }
+}
+
+impl<'k> From<&crate::advisories::Indices<'k>> for NewestVersions {
+    fn from(indices: &crate::advisories::Indices<'k>) -> Self {
+        Self(
+            indices
+                .cache
+                .iter()
+                .filter_map(|((name, src), entry)| {
+                    let crate::advisories::Entry::Map(versions) = entry else {
+                        return None;
+                    };
+                    // Yanked and prerelease versions aren't something crates
+                    // could reasonably be unified on
+                    let newest = versions
+                        .iter()
+                        .filter(|(version, yanked)| !yanked && version.pre.is_empty())
+                        .map(|(version, _)| version)
+                        .max()?;
+
+                    Some((((*name).to_owned(), (*src).clone()), newest.clone()))
+                })
+                .collect(),
+        )
+    }
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
 struct DupNode<'k> {
     kid: &'k Kid,
@@ -89,6 +138,7 @@ pub(crate) fn create_graph(
     highlight: GraphHighlight,
     krates: &crate::Krates,
     dup_ids: &[usize],
+    newest: Option<&NewestVersions>,
 ) -> Result<String, Error> {
     use pg::visit::{EdgeRef, NodeRef};
 
@@ -262,6 +312,12 @@ pub(crate) fn create_graph(
     // The krates are ordered lexicographically by id, so the first duplicate
     // is the one with the lowest version (or at least the lowest source...)
     let lowest = &edge_sets[0];
+    let highest = dup_ids
+        .iter()
+        .enumerate()
+        .max_by(|a, b| krates[*a.1].version.cmp(&krates[*b.1].version))
+        .map(|(i, _)| &edge_sets[i])
+        .context("expected highest version")?;
 
     print_graph(
         &graph,
@@ -283,11 +339,21 @@ pub(crate) fn create_graph(
 
                 if dupe_nodes.contains_key(name) {
                     // Add the source only if it is not crates.io
-                    let label = if source != "registry+https://github.com/rust-lang/crates.io-index"
+                    let label: Cow<'_, str> =
+                        if source != "registry+https://github.com/rust-lang/crates.io-index" {
+                            format!("{version} {source}").into()
+                        } else {
+                            version.into()
+                        };
+
+                    // Point out when a newer release is available, as it
+                    // might be possible to unify the duplicates on it
+                    let label = match newest
+                        .zip(krates.nid_for_kid(kid))
+                        .and_then(|(newest, nid)| newest.newer_than(&krates[nid]))
                     {
-                        format!("{version} {source}").into()
-                    } else {
-                        version.into()
+                        Some(newer) => format!("{label} (newest {newer})").into(),
+                        None => label,
                     };
 
                     NodeAttributes {
@@ -308,7 +374,7 @@ pub(crate) fn create_graph(
             }
         },
         |edge| {
-            // Color edges if they are part of the lowest or smollest path,
+            // Color edges if they are part of the lowest, highest or smollest path,
             // based on the graph highlighting configuration
             let label = match edge.weight() {
                 DepKind::Normal => None,
@@ -325,6 +391,11 @@ pub(crate) fn create_graph(
                     color: Some("blue"),
                     label,
                 }
+            } else if highlight.highest_version() && highest.contains(&edge.id()) {
+                EdgeAttributes {
+                    color: Some("green"),
+                    label,
+                }
             } else {
                 EdgeAttributes { color: None, label }
             }
@@ -0,0 +1,302 @@
+use super::cfg::ValidConfig;
+use crate::{Krate, Krates};
+use std::fmt::Write;
+
+/// A crate that matched a `deny` entry, along with the details of the entry
+pub struct BannedKrate<'k> {
+    pub krate: &'k Krate,
+    pub reason: Option<String>,
+    pub use_instead: Option<String>,
+}
+
+/// The banned and duplicate crates found by the bans check
+#[derive(Default)]
+pub(crate) struct Findings<'k> {
+    pub(crate) banned: Vec<BannedKrate<'k>>,
+    /// The versions of each crate reported as having duplicates, lowest first
+    pub(crate) duplicates: Vec<Vec<&'k Krate>>,
+}
+
+impl<'k> Findings<'k> {
+    /// Records a crate that was banned by the specified entry, a crate
+    /// matched by more than one entry is only recorded for the first
+    pub(crate) fn ban(&mut self, krate: &'k Krate, ban_cfg: &super::SpecAndReason) {
+        if self
+            .banned
+            .last()
+            .map_or(false, |bk| bk.krate.id == krate.id)
+        {
+            return;
+        }
+
+        self.banned.push(BannedKrate {
+            krate,
+            reason: ban_cfg.reason.as_ref().map(|r| r.0.value.clone()),
+            use_instead: ban_cfg.use_instead.as_ref().map(|ui| ui.value.clone()),
+        });
+    }
+
+    /// Records the versions of a crate that were reported as duplicates
+    pub(crate) fn duplicate(&mut self, krates: &'k Krates, dupes: &[usize]) {
+        let mut versions: Vec<_> = dupes.iter().map(|di| &krates[*di]).collect();
+        versions.sort_by(|a, b| a.version.cmp(&b.version).then_with(|| a.id.cmp(&b.id)));
+        self.duplicates.push(versions);
+    }
+}
+
+/// Runs the bans check against the graph, gathering the crates it bans or
+/// reports as duplicates, rather than the diagnostics themselves
+pub(crate) fn findings(krates: &Krates, cfg: ValidConfig) -> Findings<'_> {
+    let mut files = crate::diag::Files::new();
+    let (spans, contents, _) = crate::diag::KrateSpans::synthesize(krates);
+    let spans_id = files.add("Cargo.lock", contents);
+    let krate_spans = crate::diag::KrateSpans::with_spans(spans, spans_id);
+
+    let ctx = crate::CheckCtx {
+        cfg,
+        krates,
+        krate_spans: &krate_spans,
+        serialize_extra: false,
+        colorize: false,
+        log_level: log::LevelFilter::Off,
+    };
+
+    // The diagnostics are not needed, but the receiver is kept alive until the
+    // check is finished as the sink fails if it is disconnected
+    let (tx, _rx) = crossbeam::channel::unbounded();
+    let mut findings = Findings::default();
+    super::check_with_findings(ctx, None, crate::diag::CargoSpans::new(), tx, &mut findings);
+
+    findings
+}
+
+/// Gets the crates in the graph that are banned by the config
+///
+/// This uses the same matching as the bans check, so crates that are exempted
+/// or allowed by their `wrappers`, or that aren't matched due to the `kinds`,
+/// `targets` or `default-features` of an entry, are not included
+pub fn banned_krates(krates: &Krates, cfg: ValidConfig) -> Vec<BannedKrate<'_>> {
+    findings(krates, cfg).banned
+}
+
+/// Generates a draft shell script with the steps a user can take to start
+/// removing or replacing each of the banned crates
+///
+/// Each banned crate is described in comments, along with the workspace
+/// members that are responsible for it being in the graph, followed by
+/// `cargo tree` invocations that show how each member depends on it
+pub fn remediation_script(krates: &Krates, banned: &[BannedKrate<'_>]) -> String {
+    // Gather every crate reachable from each workspace member
+    let members: Vec<_> = krates
+        .workspace_members()
+        .filter_map(|node| {
+            let krates::Node::Krate { id, krate, .. } = node else {
+                return None;
+            };
+            let nid = krates.nid_for_kid(id)?;
+            Some((nid, krate, super::report::reachable(krates, nid)))
+        })
+        .collect();
+
+    let mut script = String::from("#!/usr/bin/env sh\n");
+    let _ = writeln!(
+        script,
+        "# Steps to start fixing {} banned crate{}",
+        banned.len(),
+        if banned.len() == 1 { "" } else { "s" }
+    );
+
+    for bk in banned {
+        let krate = bk.krate;
+        let Some(nid) = krates.nid_for_kid(&krate.id) else {
+            continue;
+        };
+
+        let _ = writeln!(script, "\n# crate '{krate}' is banned");
+        if let Some(reason) = &bk.reason {
+            let _ = writeln!(script, "# reason: {reason}");
+        }
+        if let Some(ui) = &bk.use_instead {
+            let _ = writeln!(script, "# consider using '{ui}' instead");
+        }
+
+        for (member_nid, member, reachable) in &members {
+            if !reachable.contains(&nid) {
+                continue;
+            }
+
+            let is_direct = krates
+                .direct_dependencies(*member_nid)
+                .iter()
+                .any(|dep| dep.node_id == nid);
+
+            if is_direct {
+                let _ = writeln!(
+                    script,
+                    "# it is a direct dependency of workspace member '{}', consider removing or replacing it in '{}'",
+                    member.name, member.manifest_path
+                );
+            } else {
+                let via: Vec<_> = krates
+                    .direct_dependents(nid)
+                    .iter()
+                    .filter(|dd| dd.node_id == *member_nid || reachable.contains(&dd.node_id))
+                    .map(|dd| format!("'{}'", dd.krate))
+                    .collect();
+
+                let _ = writeln!(
+                    script,
+                    "# it is a transitive dependency of workspace member '{}', via {}",
+                    member.name,
+                    via.join(", ")
+                );
+            }
+
+            let _ = writeln!(
+                script,
+                "cargo tree -p {} -i {}@{}",
+                member.name, krate.name, krate.version
+            );
+        }
+    }
+
+    script
+}
+
+/// Gets the `cargo update` commands that would remove some of the duplicate
+/// versions of a crate
+///
+/// A version can be removed if every crate that depends on it also accepts a
+/// newer version that is already in the graph, eg. a `>=0.5, <0.7` requirement
+/// that was resolved to `0.5.1` when `0.6.4` is also present.
+pub(crate) fn dedupe_updates(krates: &Krates, dupes: &[usize]) -> Vec<String> {
+    let mut updates = Vec::new();
+
+    for &di in dupes {
+        let krate = &krates[di];
+
+        // Only crates from a registry can be updated to a precise version
+        if !krate.source.as_ref().map_or(false, |src| src.is_registry()) {
+            continue;
+        }
+
+        let reqs: Vec<_> = krates
+            .direct_dependents(krates::NodeId::new(di))
+            .into_iter()
+            .filter_map(|dd| {
+                dd.krate
+                    .deps
+                    .iter()
+                    .find(|dep| dep.name == krate.name && dep.req.matches(&krate.version))
+                    .map(|dep| &dep.req)
+            })
+            .collect();
+
+        if reqs.is_empty() {
+            continue;
+        }
+
+        let newer = dupes
+            .iter()
+            .map(|ni| &krates[*ni])
+            .filter(|nk| {
+                nk.version > krate.version
+                    && nk.source == krate.source
+                    && reqs.iter().all(|req| req.matches(&nk.version))
+            })
+            .max_by(|a, b| a.version.cmp(&b.version));
+
+        if let Some(newer) = newer {
+            updates.push(format!(
+                "cargo update -p {}@{} --precise {}",
+                krate.name, krate.version, newer.version
+            ));
+        }
+    }
+
+    updates
+}
+
+/// Gets the crates that need a `skip` entry for the `multiple-versions` check
+/// to pass, which is every version of a duplicated crate other than the highest
+///
+/// Only the duplicates reported by the bans check are considered, so crates
+/// that are already skipped in any way, or that are allowed to have multiple
+/// versions, don't get an entry
+pub fn unskipped_duplicates(krates: &Krates, cfg: ValidConfig) -> Vec<&Krate> {
+    findings(krates, cfg)
+        .duplicates
+        .into_iter()
+        .flat_map(|mut versions| {
+            versions.pop();
+            versions
+        })
+        .collect()
+}
+
+/// Adds a `skip` entry for each of the crates to the `[bans]` table of the
+/// specified config, returning the updated config
+///
+/// If the config already has an inline `skip` array the entries are appended
+/// to it, otherwise they are appended to the end of the config as
+/// `[[bans.skip]]` tables, so that the rest of the config is left untouched
+pub fn append_skips(config: &str, skips: &[&Krate]) -> anyhow::Result<String> {
+    const REASON: &str = "duplicate that existed when the skip was generated";
+
+    let value =
+        toml_span::parse(config).map_err(|err| anyhow::anyhow!("failed to parse config: {err}"))?;
+
+    let inline = value
+        .as_table()
+        .and_then(|root| root.iter().find(|(key, _)| key.name == "bans"))
+        .and_then(|(_, bans)| bans.as_table())
+        .and_then(|bans| bans.iter().find(|(key, _)| key.name == "skip"))
+        .map(|(_, skip)| skip.span)
+        .filter(|span| {
+            let array = &config[span.start..span.end];
+            array.starts_with('[') && !array.starts_with("[[") && array.ends_with(']')
+        });
+
+    let mut updated = String::with_capacity(config.len() + skips.len() * 100);
+
+    if let Some(span) = inline {
+        let (before, after) = config.split_at(span.end - 1);
+        let before = before.trim_end();
+        updated.push_str(before);
+        if !before.ends_with('[') && !before.ends_with(',') {
+            updated.push(',');
+        }
+
+        for krate in skips {
+            let _ = write!(
+                updated,
+                "\n    {{ crate = \"{}@{}\", reason = \"{REASON}\" }},",
+                krate.name, krate.version
+            );
+        }
+
+        updated.push('\n');
+        updated.push_str(after);
+    } else {
+        updated.push_str(config);
+        if !config.is_empty() && !config.ends_with('\n') {
+            updated.push('\n');
+        }
+
+        for krate in skips {
+            let _ = write!(
+                updated,
+                "\n[[bans.skip]]\ncrate = \"{}@{}\"\nreason = \"{REASON}\"\n",
+                krate.name, krate.version
+            );
+        }
+    }
+
+    // Ensure we haven't mangled the config, eg. due to a trailing comment
+    // after the last element of the array
+    toml_span::parse(&updated).map_err(|err| {
+        anyhow::anyhow!("failed to append skip entries, the result was not valid: {err}")
+    })?;
+
+    Ok(updated)
+}
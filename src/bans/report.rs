@@ -0,0 +1,119 @@
+use super::{cfg::ValidConfig, remediation};
+use crate::{diag, Krate, Krates};
+use krates::NodeId;
+use std::{collections::HashSet, fmt::Write};
+
+/// Gathers every node that is transitively reachable from the specified node
+pub(crate) fn reachable(krates: &Krates, nid: NodeId) -> HashSet<NodeId> {
+    let mut reachable = HashSet::new();
+    let mut stack = vec![nid];
+    while let Some(cur) = stack.pop() {
+        for dep in krates.direct_dependencies(cur) {
+            if reachable.insert(dep.node_id) {
+                stack.push(dep.node_id);
+            }
+        }
+    }
+
+    reachable
+}
+
+/// The banned and duplicate crates pulled in by a single workspace member
+pub struct MemberFindings<'k> {
+    pub member: &'k Krate,
+    /// The banned crates reachable from the member
+    pub banned: Vec<&'k Krate>,
+    /// The versions of crates with multiple versions reachable from the member
+    pub duplicates: Vec<&'k Krate>,
+    /// The member and every crate reachable from it
+    reachable: HashSet<&'k crate::Kid>,
+}
+
+/// Attributes the banned and duplicate crates in the graph to each of the
+/// workspace members that pull them in
+///
+/// The crates are the ones banned or reported as duplicates by the bans check,
+/// so skipped crates, including those skipped by `skip-tree`, and crates that
+/// are allowed to have multiple versions, are not considered duplicates
+pub fn member_findings<'k>(krates: &'k Krates, cfg: ValidConfig) -> Vec<MemberFindings<'k>> {
+    let remediation::Findings { banned, duplicates } = remediation::findings(krates, cfg);
+    let duplicates: Vec<_> = duplicates.into_iter().flatten().collect();
+
+    krates
+        .workspace_members()
+        .filter_map(|node| {
+            let krates::Node::Krate { id, krate, .. } = node else {
+                return None;
+            };
+            let nid = krates.nid_for_kid(id)?;
+
+            let mut reachable: HashSet<_> = reachable(krates, nid)
+                .into_iter()
+                .map(|nid| &krates[nid].id)
+                .collect();
+            reachable.insert(&krate.id);
+
+            Some(MemberFindings {
+                member: krate,
+                banned: banned
+                    .iter()
+                    .map(|bk| bk.krate)
+                    .filter(|bk| reachable.contains(&bk.id))
+                    .collect(),
+                duplicates: duplicates
+                    .iter()
+                    .copied()
+                    .filter(|dupe| reachable.contains(&dupe.id))
+                    .collect(),
+                reachable,
+            })
+        })
+        .collect()
+}
+
+/// Renders a section for each workspace member, listing the banned and
+/// duplicate crates it pulls in, along with the inclusion graph for each of
+/// them, limited to the paths that lead to the member
+pub fn member_report(
+    krates: &Krates,
+    findings: &[MemberFindings<'_>],
+) -> Result<String, diag::GraphError> {
+    let grapher = diag::InclusionGrapher::new(krates);
+    let mut report = String::new();
+
+    for mf in findings {
+        if !report.is_empty() {
+            writeln!(report)?;
+        }
+
+        writeln!(report, "# {} v{}", mf.member.name, mf.member.version)?;
+
+        if mf.banned.is_empty() && mf.duplicates.is_empty() {
+            writeln!(report, "\nno banned or duplicate crates")?;
+            continue;
+        }
+
+        for (title, list) in [("banned", &mf.banned), ("duplicate", &mf.duplicates)] {
+            if list.is_empty() {
+                continue;
+            }
+
+            writeln!(report, "\n## {title} crates ({})\n", list.len())?;
+
+            for krate in list {
+                let mut graph = grapher.build_graph(
+                    &diag::GraphNode {
+                        kid: krate.id.clone(),
+                        feature: None,
+                    },
+                    0,
+                )?;
+                graph.retain(&|kid| mf.reachable.contains(kid));
+
+                report.push_str(&diag::write_graph_as_text(&graph)?);
+            }
+        }
+    }
+
+    Ok(report)
+}
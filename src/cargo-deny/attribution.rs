@@ -0,0 +1,369 @@
+use crate::common::ValidConfig;
+use anyhow::{Context as _, Error};
+use cargo_deny::{diag::Files, licenses, Krate, Krates, Path, PathBuf};
+
+const SEPARATOR: &str =
+    "--------------------------------------------------------------------------------";
+
+#[derive(clap::Parser, Debug)]
+pub struct Args {
+    /// Path to the config to use
+    ///
+    /// Defaults to a deny.toml in the same folder as the manifest path, or a deny.toml in a parent directory.
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+    /// Path to write the attribution document to
+    ///
+    /// Defaults to writing the document to stdout.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Directory to copy the license texts of every crate into
+    ///
+    /// Identical license texts are only written once, and each crate in the attribution document lists the files in this directory that contain its license texts.
+    #[arg(long)]
+    license_texts: Option<PathBuf>,
+}
+
+pub fn cmd(
+    log_ctx: crate::common::LogContext,
+    args: Args,
+    krate_ctx: crate::common::KrateContext,
+) -> Result<(), Error> {
+    use licenses::LicenseInfo;
+
+    let cfg_path = krate_ctx.get_config_path(args.config.clone());
+
+    let mut files = Files::new();
+    let ValidConfig {
+        graph, licenses, ..
+    } = ValidConfig::load(
+        cfg_path,
+        krate_ctx.get_local_exceptions_path(),
+        None,
+        &mut files,
+        log_ctx,
+    )?;
+
+    let (krates, store) = rayon::join(
+        || krate_ctx.gather_krates(graph.targets, graph.exclude),
+        crate::common::load_license_store,
+    );
+
+    let krates = krates.context("failed to gather crates")?;
+    let store = store.context("failed to load license store")?;
+
+    let gatherer = licenses::Gatherer::default()
+        .with_store(std::sync::Arc::new(store))
+        .with_confidence_threshold(licenses.confidence_threshold);
+
+    let summary = gatherer.gather(&krates, &mut files, Some(&licenses));
+
+    if let Some(dir) = &args.license_texts {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create license texts directory '{dir}'"))?;
+    }
+
+    let attributed = summary
+        .nfos
+        .iter()
+        .map(|nfo| {
+            let license = match &nfo.lic_info {
+                LicenseInfo::SpdxExpression { expr, .. } => Some(expr.to_string()),
+                LicenseInfo::Unlicensed => None,
+            };
+
+            (nfo.krate, license)
+        })
+        .collect();
+
+    let output = write_document(&krates, attributed, args.license_texts.as_deref())?;
+
+    match args.output {
+        Some(path) => std::fs::write(&path, output)
+            .with_context(|| format!("failed to write attribution document to '{path}'"))?,
+        None => std::io::Write::write_all(&mut std::io::stdout(), output.as_bytes())?,
+    }
+
+    Ok(())
+}
+
+/// Writes the attribution document for the specified crates, along with the
+/// license each is used under, skipping any that are first-party
+///
+/// If a directory is specified, the license texts of each crate are written to
+/// it, and listed in the crate's entry
+fn write_document(
+    krates: &Krates,
+    mut attributed: Vec<(&Krate, Option<String>)>,
+    texts_dir: Option<&Path>,
+) -> Result<String, Error> {
+    use std::fmt::Write;
+
+    // The names of the license texts that have already been written, keyed by
+    // their contents, as the hash used in the names can collide
+    let mut written = std::collections::BTreeMap::<String, String>::new();
+
+    let mut output = String::with_capacity(16 * 1024);
+
+    writeln!(output, "THIRD-PARTY SOFTWARE NOTICES")?;
+    writeln!(output)?;
+    writeln!(
+        output,
+        "This document lists the third-party crates that are used, along with their licenses and copyright notices."
+    )?;
+
+    // Sort by crate so that the document is stable between runs
+    attributed.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (krate, license) in attributed {
+        if cargo_deny::is_first_party(krate, krates) {
+            continue;
+        }
+
+        writeln!(output)?;
+        writeln!(output, "{SEPARATOR}")?;
+        writeln!(output, "{} {}", krate.name, krate.version)?;
+        writeln!(
+            output,
+            "License: {}",
+            license.as_deref().unwrap_or("unknown")
+        )?;
+
+        if let Some(repo) = &krate.repository {
+            writeln!(output, "Repository: {repo}")?;
+        }
+
+        let texts = license_texts(krate);
+
+        if let Some(dir) = texts_dir {
+            if !texts.is_empty() {
+                let mut names = Vec::with_capacity(texts.len());
+
+                for (path, contents) in &texts {
+                    let name = if let Some(name) = written.get(contents) {
+                        name.clone()
+                    } else {
+                        let hash = cargo_deny::hash(contents.as_bytes());
+                        let base = format!("{}-{hash:08x}", path.file_name().unwrap_or("LICENSE"));
+
+                        // Different texts that happen to have the same hash
+                        // are given a unique suffix rather than overwritten
+                        let mut name = base.clone();
+                        let mut n = 1;
+                        while written.values().any(|written| *written == name) {
+                            n += 1;
+                            name = format!("{base}-{n}");
+                        }
+
+                        let text_path = dir.join(&name);
+                        std::fs::write(&text_path, contents).with_context(|| {
+                            format!("failed to write license text to '{text_path}'")
+                        })?;
+
+                        written.insert(contents.clone(), name.clone());
+                        name
+                    };
+
+                    names.push(name);
+                }
+
+                writeln!(output, "License texts: {}", names.join(", "))?;
+            }
+        }
+
+        let copyrights = copyright_lines(&texts);
+
+        if !copyrights.is_empty() {
+            writeln!(output)?;
+
+            for line in copyrights {
+                writeln!(output, "{line}")?;
+            }
+        } else if !krate.authors.is_empty() {
+            writeln!(output)?;
+            writeln!(output, "Authors: {}", krate.authors.join(", "))?;
+        }
+    }
+
+    Ok(output)
+}
+
+/// Reads the license files in the crate's root directory, normalizing their
+/// line endings so that identical texts have identical contents
+fn license_texts(krate: &Krate) -> Vec<(PathBuf, String)> {
+    let root = krate.manifest_path.parent().unwrap();
+
+    let mut paths = licenses::find_license_files(root).unwrap_or_default();
+    if let Some(lf) = &krate.license_file {
+        if !paths.iter().any(|p| p.ends_with(lf)) {
+            paths.push(lf.clone());
+        }
+    }
+
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| match std::fs::read_to_string(root.join(&path)) {
+            Ok(contents) => {
+                let mut normalized = String::with_capacity(contents.len());
+                for line in contents.lines() {
+                    normalized.push_str(line);
+                    normalized.push('\n');
+                }
+
+                Some((path, normalized))
+            }
+            Err(err) => {
+                log::warn!("unable to read license file '{path}' for crate '{krate}': {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Gathers the unique copyright notices from the crate's license texts, in the
+/// order they are encountered
+fn copyright_lines(texts: &[(PathBuf, String)]) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for (_, contents) in texts {
+        for line in contents.lines() {
+            let line = line.trim();
+            let lower = line.to_lowercase();
+
+            if !lower.starts_with("copyright") && !line.starts_with('©') {
+                continue;
+            }
+
+            // Skip the wrapped lines of license texts, eg. "copyright notice, this list of
+            // conditions...", as well as the placeholders in license templates, eg.
+            // "Copyright [yyyy] [name of copyright owner]"
+            if !line.contains(|c: char| c.is_ascii_digit())
+                || ["yyyy", "<year>", "[year]"]
+                    .iter()
+                    .any(|placeholder| lower.contains(placeholder))
+            {
+                continue;
+            }
+
+            if !lines.iter().any(|l| l == line) {
+                lines.push(line.to_owned());
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::{copyright_lines, write_document};
+    use cargo_deny::{Krate, Path, PathBuf, Source, Version};
+
+    fn krate(name: &str, root: &Path, authors: &[&str]) -> Krate {
+        Krate {
+            name: name.to_owned(),
+            id: krates::cm::PackageId {
+                repr: format!("registry+https://github.com/rust-lang/crates.io-index#{name}@1.0.0"),
+            }
+            .into(),
+            version: Version::new(1, 0, 0),
+            source: Some(Source::crates_io(false)),
+            authors: authors.iter().map(|a| (*a).to_owned()).collect(),
+            repository: None,
+            description: None,
+            manifest_path: root.join(name).join("Cargo.toml"),
+            license: None,
+            license_file: None,
+            deps: Vec::new(),
+            features: Default::default(),
+            targets: Vec::new(),
+            publish: None,
+            keywords: Vec::new(),
+            categories: Vec::new(),
+            links: None,
+            metadata: serde_json::Value::Null,
+            rust_version: None,
+        }
+    }
+
+    #[test]
+    fn filters_copyrights() {
+        let texts = [
+            (
+                PathBuf::from("LICENSE-MIT"),
+                "MIT License
+
+Copyright (c) 2018 Jane Doe
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+"
+                .to_owned(),
+            ),
+            (
+                PathBuf::from("LICENSE-APACHE"),
+                "   Copyright (c) 2010-[year] [fullname]
+   Copyright yyyy The 2D Team
+   Redistributions of source code must retain the above
+   copyright notice, this list of conditions and the following disclaimer.
+   Copyright (c) 2018 Jane Doe
+   © 2020 The Project Developers
+"
+                .to_owned(),
+            ),
+        ];
+
+        assert_eq!(
+            copyright_lines(&texts),
+            [
+                "Copyright (c) 2018 Jane Doe",
+                "© 2020 The Project Developers"
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_document() {
+        let krates = cargo_deny::test_utils::KrateGather::new("wildcards/maincrate").gather();
+        let first_party: Vec<_> = krates
+            .krates()
+            .filter(|k| k.name.starts_with("wildcards-test"))
+            .collect();
+        assert_eq!(first_party.len(), 2);
+
+        let root = tempfile::tempdir().unwrap();
+        let root = Path::from_path(root.path()).unwrap();
+
+        std::fs::create_dir_all(root.join("alpha")).unwrap();
+        std::fs::create_dir_all(root.join("zeta")).unwrap();
+        std::fs::write(
+            root.join("zeta/LICENSE"),
+            "Copyright (c) 2021 Zeta Developers\r\n",
+        )
+        .unwrap();
+
+        let alpha = krate("alpha", root, &["Jane Doe <jane@example.com>"]);
+        let zeta = krate("zeta", root, &["John Doe <john@example.com>"]);
+
+        let mut attributed = vec![(&zeta, Some("MIT".to_owned())), (&alpha, None)];
+        attributed.extend(first_party.into_iter().map(|k| (k, Some("MIT".to_owned()))));
+
+        let doc = write_document(&krates, attributed, None).unwrap();
+
+        // Workspace members and path dependencies are never included
+        assert!(!doc.contains("wildcards-test"));
+
+        // Crates are sorted, regardless of the order they are provided in, and
+        // the authors are only used if there are no copyright notices
+        let sections: Vec<_> = doc.split(super::SEPARATOR).skip(1).collect();
+        assert_eq!(
+            sections,
+            [
+                "\nalpha 1.0.0\nLicense: unknown\n\nAuthors: Jane Doe <jane@example.com>\n\n",
+                "\nzeta 1.0.0\nLicense: MIT\n\nCopyright (c) 2021 Zeta Developers\n",
+            ]
+        );
+    }
+}
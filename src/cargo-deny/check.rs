@@ -4,7 +4,9 @@ use crate::{
 };
 use cargo_deny::{
     advisories, bans,
-    diag::{CargoSpans, DiagnosticCode, DiagnosticOverrides, ErrorSink, Files, Severity},
+    diag::{
+        CargoSpans, CrateWaivers, DiagnosticCode, DiagnosticOverrides, ErrorSink, Files, Severity,
+    },
     licenses, sources, CheckCtx, PathBuf,
 };
 use log::error;
@@ -108,6 +110,21 @@ pub struct Args {
     /// Specifies the depth at which feature edges are added in inclusion graphs
     #[arg(long, conflicts_with = "hide_inclusion_graph")]
     pub feature_depth: Option<u32>,
+    /// Fails the check if the sha-256 checksum of the config file's contents,
+    /// followed by the contents of each file it includes, doesn't match the
+    /// one specified
+    ///
+    /// This can be used to ensure a pinned config isn't modified without review.
+    #[arg(long, value_name = "SHA256")]
+    pub frozen_config: Option<cargo_deny::cfg::Checksum>,
+    /// Appends a `skip` entry to the config for every crate that currently has
+    /// multiple versions, instead of performing any checks
+    ///
+    /// This allows adopting the bans check on an existing workspace without
+    /// hand writing an entry for each duplicate, and then removing the entries
+    /// as the duplicates are resolved.
+    #[arg(long)]
+    pub append_skips: bool,
     /// The check(s) to perform
     #[arg(value_enum)]
     pub which: Vec<WhichCheck>,
@@ -119,6 +136,7 @@ pub(crate) fn cmd(
     mut krate_ctx: crate::common::KrateContext,
 ) -> anyhow::Result<AllStats> {
     let mut files = Files::new();
+    let cfg_path = krate_ctx.get_config_path(args.config.clone());
     let ValidConfig {
         advisories,
         bans,
@@ -127,8 +145,9 @@ pub(crate) fn cmd(
         graph,
         output,
     } = ValidConfig::load(
-        krate_ctx.get_config_path(args.config.clone()),
+        cfg_path.clone(),
         krate_ctx.get_local_exceptions_path(),
+        args.frozen_config.as_ref(),
         &mut files,
         log_ctx,
     )?;
@@ -273,6 +292,22 @@ pub(crate) fn cmd(
 
     let krates = krates.unwrap()?;
 
+    if args.append_skips {
+        use anyhow::Context as _;
+
+        let cfg_path = cfg_path.context("unable to append skip entries, no config was found")?;
+        let config = std::fs::read_to_string(&cfg_path)
+            .with_context(|| format!("failed to read config '{cfg_path}'"))?;
+
+        let duplicates = bans::unskipped_duplicates(&krates, bans);
+        let config = bans::append_skips(&config, &duplicates)?;
+        std::fs::write(&cfg_path, config)
+            .with_context(|| format!("failed to write config '{cfg_path}'"))?;
+
+        log::info!("appended {} skip entries to '{cfg_path}'", duplicates.len());
+        return Ok(AllStats::default());
+    }
+
     let advisory_db_set = if check_advisories {
         let dbset = advisory_dbs.unwrap()?;
         Some(dbset)
@@ -313,6 +348,7 @@ pub(crate) fn cmd(
     let (tx, rx) = crossbeam::channel::unbounded();
 
     let krates = &krates;
+    let waivers = CrateWaivers::new(krates).map(std::sync::Arc::new);
 
     let mut stats = AllStats::default();
 
@@ -335,7 +371,7 @@ pub(crate) fn cmd(
     let show_inclusion_graphs = !args.hide_inclusion_graph;
     let serialize_extra = match log_ctx.format {
         crate::Format::Json => true,
-        crate::Format::Human => false,
+        crate::Format::Human | crate::Format::Github => false,
     };
     let audit_compatible_output =
         args.audit_compatible_output && log_ctx.format == crate::Format::Json;
@@ -359,12 +395,16 @@ pub(crate) fn cmd(
                 files,
                 &mut stats,
                 feature_depth,
+                &output.collapse,
+                output.members_only,
+                output.graph_budget,
             );
         });
 
         if let Some(summary) = license_summary {
             let sink = ErrorSink {
                 overrides: overrides.clone(),
+                waivers: waivers.clone(),
                 channel: tx.clone(),
             };
 
@@ -415,6 +455,7 @@ pub(crate) fn cmd(
 
             let bans_sink = ErrorSink {
                 overrides: overrides.clone(),
+                waivers: waivers.clone(),
                 channel: tx.clone(),
             };
 
@@ -428,6 +469,15 @@ pub(crate) fn cmd(
             };
 
             s.spawn(|_| {
+                // Annotating duplicates with the newest available version is
+                // best effort, so just skip it if the index can't be loaded
+                let output_graph = output_graph.map(|output| bans::GraphOutput {
+                    output,
+                    newest: tame_index::utils::cargo_home().ok().map(|cargo_home| {
+                        bans::NewestVersions::from(&advisories::Indices::load(krates, cargo_home))
+                    }),
+                });
+
                 log::info!("checking bans...");
                 let start = Instant::now();
                 bans::check(ctx, output_graph, cargo_spans, bans_sink);
@@ -439,6 +489,7 @@ pub(crate) fn cmd(
         if check_sources {
             let sources_sink = ErrorSink {
                 overrides: overrides.clone(),
+                waivers: waivers.clone(),
                 channel: tx.clone(),
             };
 
@@ -463,6 +514,7 @@ pub(crate) fn cmd(
         if let Some(dbset) = advisory_db_set {
             let mut advisories_sink = ErrorSink {
                 overrides,
+                waivers,
                 channel: tx,
             };
 
@@ -537,12 +589,25 @@ fn print_diagnostics(
     files: Files,
     stats: &mut AllStats,
     feature_depth: Option<u32>,
+    collapse: &[String],
+    members_only: bool,
+    graph_budget: cargo_deny::diag::GraphBudget,
 ) {
     use cargo_deny::diag::Check;
 
-    let dp = crate::common::DiagPrinter::new(log_ctx, krates, feature_depth);
+    let dp = crate::common::DiagPrinter::new(
+        log_ctx,
+        krates,
+        feature_depth,
+        collapse,
+        members_only,
+        graph_budget,
+    );
 
     for pack in rx {
+        // Checks push diagnostics from multiple threads, so sort them for a
+        // reproducible output
+        let pack = pack.sorted();
         let check_stats = match pack.check {
             Check::Advisories => stats.advisories.as_mut().unwrap(),
             Check::Bans => stats.bans.as_mut().unwrap(),
@@ -432,10 +432,15 @@ pub struct Json<'a> {
     grapher: Option<diag::InclusionGrapher<'a>>,
 }
 
+pub struct Github<'a> {
+    krates: Option<&'a cargo_deny::Krates>,
+}
+
 #[allow(clippy::large_enum_variant)]
 enum OutputFormat<'a> {
     Human(Human<'a>),
     Json(Json<'a>),
+    Github(Github<'a>),
 }
 
 impl<'a> OutputFormat<'a> {
@@ -448,6 +453,10 @@ impl<'a> OutputFormat<'a> {
                 human.feature_depth,
             ),
             Self::Json(json) => OutputLock::Json(json, max_severity, json.stream.lock()),
+            // Workflow commands are only recognized when written to stdout
+            Self::Github(github) => {
+                OutputLock::Github(github, max_severity, std::io::stdout().lock())
+            }
         }
     }
 }
@@ -481,6 +490,7 @@ pub enum OutputLock<'a, 'b> {
         Option<u32>,
     ),
     Json(&'a Json<'a>, Severity, StdLock<'b>),
+    Github(&'a Github<'a>, Severity, std::io::StdoutLock<'b>),
 }
 
 impl<'a, 'b> OutputLock<'a, 'b> {
@@ -508,6 +518,13 @@ impl<'a, 'b> OutputLock<'a, 'b> {
                     let _ = w.write(b"\n");
                 }
             }
+            Self::Github(_cfg, max, w) => {
+                if diag.severity < *max {
+                    return;
+                }
+
+                let _ = writeln!(w, "{}", diag::cs_diag_to_workflow_command(diag, files));
+            }
         }
     }
 
@@ -529,15 +546,17 @@ impl<'a, 'b> OutputLock<'a, 'b> {
                                 diag.diag
                                     .notes
                                     .push(format!("{} v{} (*)", krate.name, krate.version));
-                            } else if let Ok(graph) = grapher.build_graph(
-                                &gn,
-                                if diag.with_features {
-                                    fd.unwrap_or(1) as usize
-                                } else {
-                                    0
-                                },
-                            ) {
-                                let graph_text = diag::write_graph_as_text(&graph);
+                            } else if let Ok(graph_text) = grapher
+                                .build_graph(
+                                    &gn,
+                                    if diag.with_features {
+                                        fd.unwrap_or(1) as usize
+                                    } else {
+                                        0
+                                    },
+                                )
+                                .and_then(|graph| diag::write_graph_as_text(&graph))
+                            {
                                 diag.diag.notes.push(graph_text);
                                 emitted.insert(gn.kid);
                             }
@@ -564,6 +583,19 @@ impl<'a, 'b> OutputLock<'a, 'b> {
                     }
                 }
             }
+            Self::Github(cfg, max, w) => {
+                for diag in pack {
+                    if diag.diag.severity < *max {
+                        continue;
+                    }
+
+                    let _ = writeln!(
+                        w,
+                        "{}",
+                        diag::diag_to_workflow_command(diag, files, cfg.krates)
+                    );
+                }
+            }
         }
     }
 }
@@ -585,7 +617,19 @@ impl<'a> DiagPrinter<'a> {
         ctx: LogContext,
         krates: Option<&'a cargo_deny::Krates>,
         feature_depth: Option<u32>,
+        collapse: &[String],
+        members_only: bool,
+        graph_budget: diag::GraphBudget,
     ) -> Option<Self> {
+        let grapher = || {
+            krates.map(|krates| {
+                diag::InclusionGrapher::new(krates)
+                    .with_budget(graph_budget)
+                    .collapse(collapse.iter().map(String::as_str))
+                    .members_only(members_only)
+            })
+        };
+
         let max_severity = log_level_to_severity(ctx.log_level);
 
         max_severity.map(|max_severity| match ctx.format {
@@ -598,7 +642,7 @@ impl<'a> DiagPrinter<'a> {
                 Self {
                     which: OutputFormat::Human(Human {
                         stream,
-                        grapher: krates.map(diag::InclusionGrapher::new),
+                        grapher: grapher(),
                         config: term::Config::default(),
                         feature_depth,
                     }),
@@ -608,10 +652,14 @@ impl<'a> DiagPrinter<'a> {
             crate::Format::Json => Self {
                 which: OutputFormat::Json(Json {
                     stream: StdioStream::Err(std::io::stderr()),
-                    grapher: krates.map(diag::InclusionGrapher::new),
+                    grapher: grapher(),
                 }),
                 max_severity,
             },
+            crate::Format::Github => Self {
+                which: OutputFormat::Github(Github { krates }),
+                max_severity,
+            },
         })
     }
 
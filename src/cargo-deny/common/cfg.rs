@@ -18,6 +18,7 @@ impl ValidConfig {
     pub fn load(
         cfg_path: Option<PathBuf>,
         exceptions_cfg_path: Option<PathBuf>,
+        frozen_checksum: Option<&cargo_deny::cfg::Checksum>,
         files: &mut Files,
         log_ctx: crate::common::LogContext,
     ) -> Result<Self> {
@@ -48,7 +49,9 @@ impl ValidConfig {
                 return;
             }
 
-            if let Some(printer) = crate::common::DiagPrinter::new(log_ctx, None, None) {
+            if let Some(printer) =
+                crate::common::DiagPrinter::new(log_ctx, None, None, &[], false, Default::default())
+            {
                 let mut lock = printer.lock();
                 for diag in diags {
                     lock.print(diag, files);
@@ -98,6 +101,20 @@ impl ValidConfig {
                     files,
                     diagnostics: &mut diags,
                 });
+
+            // Included files are only known once the bans config has loaded them
+            if let Some(expected) = frozen_checksum {
+                cargo_deny::cfg::verify_checksum(
+                    &mut cargo_deny::cfg::ValidationContext {
+                        cfg_id: id,
+                        files,
+                        diagnostics: &mut diags,
+                    },
+                    expected,
+                    &bans.included_files,
+                );
+            }
+
             let mut licenses =
                 cfg.licenses
                     .unwrap_or_default()
@@ -32,6 +32,7 @@ pub fn cmd(
     let ValidConfig { advisories, .. } = ValidConfig::load(
         cfg_path,
         krate_ctx.get_local_exceptions_path(),
+        None,
         &mut files,
         log_ctx,
     )?;
@@ -53,6 +53,7 @@ pub fn cmd(
     let ValidConfig { graph, .. } = ValidConfig::load(
         cfg_path,
         krate_ctx.get_local_exceptions_path(),
+        None,
         &mut files,
         log_ctx,
     )?;
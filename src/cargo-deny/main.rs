@@ -4,6 +4,7 @@ use anyhow::{Context as _, Error};
 use cargo_deny::PathBuf;
 use clap::{Parser, Subcommand, ValueEnum};
 
+mod attribution;
 mod check;
 mod common;
 mod fetch;
@@ -25,12 +26,16 @@ enum Command {
     /// Outputs a listing of all licenses and the crates that use them
     #[command(name = "list")]
     List(list::Args),
+    /// Writes an attribution document for all third party crates
+    #[command(name = "attribution")]
+    Attribution(attribution::Args),
 }
 
 #[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Format {
     Human,
     Json,
+    Github,
 }
 
 #[derive(ValueEnum, Copy, Clone, Debug)]
@@ -151,7 +156,7 @@ fn setup_logger(
     let now = time::OffsetDateTime::now_utc();
 
     match format {
-        Format::Human => {
+        Format::Human | Format::Github => {
             const HUMAN: &[time::format_description::FormatItem<'static>] =
                 time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
 
@@ -341,6 +346,7 @@ fn real_main() -> Result<(), Error> {
         Command::Fetch(fargs) => fetch::cmd(log_ctx, fargs, krate_ctx),
         Command::Init(iargs) => init::cmd(iargs, krate_ctx),
         Command::List(largs) => list::cmd(log_ctx, largs, krate_ctx),
+        Command::Attribution(aargs) => attribution::cmd(log_ctx, aargs, krate_ctx),
     }
 }
 
@@ -33,7 +33,7 @@ pub(crate) fn print_stats(
     // of the output, but for JSON we still go to stderr since presumably computers
     // will be looking at that output and we don't want to confuse them
     match format {
-        Format::Human => {
+        Format::Human | Format::Github => {
             let mut summary = String::new();
 
             let color = crate::common::should_colorize(color, std::io::stdout());
@@ -16,6 +16,17 @@ impl<'ctx> ValidationContext<'ctx> {
         self.diagnostics.push(diag);
     }
 
+    /// Creates a context for validating items declared in a different file,
+    /// eg. one included by the config
+    #[inline]
+    pub fn for_file(&mut self, cfg_id: diag::FileId) -> ValidationContext<'_> {
+        ValidationContext {
+            cfg_id,
+            files: &mut *self.files,
+            diagnostics: &mut *self.diagnostics,
+        }
+    }
+
     /// Sorts a vec and prints a warning about duplicate items before removing them
     pub fn dedup<T>(&mut self, v: &mut Vec<Spanned<T>>)
     where
@@ -46,6 +57,29 @@ impl<'ctx> ValidationContext<'ctx> {
     }
 }
 
+/// Verifies the raw contents of the config file, followed by each of the files
+/// it included in the order they were loaded, match the expected sha-256
+/// checksum, emitting an error if they don't so that changes to a pinned config
+/// can't go unnoticed
+pub fn verify_checksum(
+    ctx: &mut ValidationContext<'_>,
+    expected: &Checksum,
+    included: &[diag::FileId],
+) -> bool {
+    let files = &*ctx.files;
+    let actual = Checksum::calculate_all(
+        std::iter::once(ctx.cfg_id)
+            .chain(included.iter().copied())
+            .map(|id| files.source(id).as_bytes()),
+    );
+    if actual.0 == expected.0 {
+        return true;
+    }
+
+    ctx.push(diag::general::ConfigChecksumMismatch { expected, actual }.into());
+    false
+}
+
 pub trait UnvalidatedConfig {
     type ValidCfg;
 
@@ -73,6 +107,175 @@ impl<'de> toml_span::Deserialize<'de> for Reason {
     }
 }
 
+/// A date, in `YYYY-MM-DD` format, after which a temporary config entry has
+/// expired and should be revisited
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct Expires(pub Spanned<time::Date>);
+
+impl Expires {
+    /// Checks if the entry has expired as of the specified date
+    #[inline]
+    pub fn is_expired(&self, today: time::Date) -> bool {
+        today > self.0.value
+    }
+}
+
+impl<'de> toml_span::Deserialize<'de> for Expires {
+    fn deserialize(
+        value: &mut toml_span::value::Value<'de>,
+    ) -> Result<Self, toml_span::DeserError> {
+        let date = value.take_string(Some("a date in the format YYYY-MM-DD"))?;
+
+        let parse = || -> Option<time::Date> {
+            let mut parts = date.splitn(3, '-');
+            let year = parts.next()?.parse().ok()?;
+            let month: u8 = parts.next()?.parse().ok()?;
+            let day = parts.next()?.parse().ok()?;
+
+            time::Date::from_calendar_date(year, month.try_into().ok()?, day).ok()
+        };
+
+        match parse() {
+            Some(date) => Ok(Self(Spanned::with_span(date, value.span))),
+            None => Err(toml_span::Error {
+                kind: toml_span::ErrorKind::Custom(
+                    format!("'{date}' is not a valid date in the format YYYY-MM-DD").into(),
+                ),
+                span: value.span,
+                line_info: None,
+            }
+            .into()),
+        }
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct Checksum(pub [u8; 32]);
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+pub enum ChecksumParseError {
+    /// The checksum string had an invalid length
+    InvalidLength(usize),
+    /// The checksum string contained a non-hex character
+    InvalidValue(char),
+}
+
+impl std::fmt::Display for ChecksumParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength(len) => write!(
+                f,
+                "expected a sha-256 hex encoded string of length 64 but got a string of length '{len}'"
+            ),
+            Self::InvalidValue(c) => write!(f, "'{c}' is not a hex character"),
+        }
+    }
+}
+
+impl std::error::Error for ChecksumParseError {}
+
+impl std::str::FromStr for Checksum {
+    type Err = ChecksumParseError;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        if data.len() != 64 {
+            return Err(ChecksumParseError::InvalidLength(data.len()));
+        }
+
+        let mut array = [0u8; 32];
+
+        for (ind, chunk) in data.as_bytes().chunks(2).enumerate() {
+            #[inline]
+            fn parse_hex(b: u8) -> Result<u8, ChecksumParseError> {
+                Ok(match b {
+                    b'A'..=b'F' => b - b'A' + 10,
+                    b'a'..=b'f' => b - b'a' + 10,
+                    b'0'..=b'9' => b - b'0',
+                    c => {
+                        return Err(ChecksumParseError::InvalidValue(c as char));
+                    }
+                })
+            }
+
+            let mut cur = parse_hex(chunk[0])?;
+            cur <<= 4;
+            cur |= parse_hex(chunk[1])?;
+
+            array[ind] = cur;
+        }
+
+        Ok(Self(array))
+    }
+}
+
+impl<'de> toml_span::Deserialize<'de> for Checksum {
+    fn deserialize(
+        value: &mut toml_span::value::Value<'de>,
+    ) -> Result<Self, toml_span::DeserError> {
+        let val = value.take_string(Some("a sha-256 hex encoded string"))?;
+
+        val.parse().map_err(|err| {
+            let err = match err {
+                ChecksumParseError::InvalidLength(len) => {
+                    toml_span::Error::from((toml_span::ErrorKind::Custom(format!("a sha-256 hex encoded string of length 64 but got a string of length '{len}'").into()), value.span))
+                }
+                ChecksumParseError::InvalidValue(c) => toml_span::Error::from((toml_span::ErrorKind::Unexpected(c), value.span)),
+            };
+            err.into()
+        })
+    }
+}
+
+impl Checksum {
+    /// Calculates the sha-256 checksum of the specified data
+    #[inline]
+    pub fn calculate(data: &[u8]) -> Self {
+        Self::calculate_all([data])
+    }
+
+    /// Calculates the sha-256 checksum of several pieces of data, in order, as
+    /// if they were a single contiguous buffer
+    pub fn calculate_all<'d>(data: impl IntoIterator<Item = &'d [u8]>) -> Self {
+        let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+        for d in data {
+            ctx.update(d);
+        }
+        let digest = ctx.finish();
+        let mut checksum = [0; 32];
+        checksum.copy_from_slice(digest.as_ref());
+        Self(checksum)
+    }
+}
+
+impl std::fmt::Display for Checksum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut hexs = [0; 64];
+
+        const CHARS: &[u8] = b"0123456789abcdef";
+
+        for (i, &byte) in self.0.iter().enumerate() {
+            let i = i * 2;
+            hexs[i] = CHARS[(byte >> 4) as usize];
+            hexs[i + 1] = CHARS[(byte & 0xf) as usize];
+        }
+
+        f.write_str(std::str::from_utf8(&hexs).unwrap())
+    }
+}
+
+#[cfg(test)]
+impl serde::Serialize for Checksum {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 /// Deserialize a field from the table if it exists, but append the key's span
 /// so it can be marked as deprecated
 pub fn deprecated<'de, T>(
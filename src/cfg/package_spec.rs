@@ -228,6 +228,118 @@ impl PartialOrd for PackageSpec {
     }
 }
 
+impl PackageSpec {
+    /// Checks if there is at least one version of a crate that would be
+    /// matched by both specs
+    pub fn overlaps(&self, other: &Self) -> bool {
+        if self.name.value != other.name.value {
+            return false;
+        }
+
+        let (Some(a), Some(b)) = (&self.version_req, &other.version_req) else {
+            return true;
+        };
+
+        let (lower, upper) = a
+            .comparators
+            .iter()
+            .chain(b.comparators.iter())
+            .map(comparator_bounds)
+            .fold((None, None), |(lower, upper), (clower, cupper)| {
+                (
+                    tightest(lower, clower, Ordering::Greater),
+                    tightest(upper, cupper, Ordering::Less),
+                )
+            });
+
+        match (lower, upper) {
+            (Some((lower, linc)), Some((upper, uinc))) => {
+                lower < upper || (lower == upper && linc && uinc)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// A version bound, and whether the bound itself is included
+type Bound = Option<(semver::Version, bool)>;
+
+/// Picks the tighter of two bounds, ie. the greater lower bound or the lesser
+/// upper bound
+fn tightest(a: Bound, b: Bound, tighter: Ordering) -> Bound {
+    match (a, b) {
+        (None, bound) | (bound, None) => bound,
+        (Some(a), Some(b)) => match a.0.cmp(&b.0) {
+            Ordering::Equal => Some(if a.1 { b } else { a }),
+            ord if ord == tighter => Some(a),
+            _ => Some(b),
+        },
+    }
+}
+
+/// Gets the lower and upper bounds of the versions matched by a comparator,
+/// following the same rules as cargo for partial versions
+fn comparator_bounds(comp: &semver::Comparator) -> (Bound, Bound) {
+    use semver::{Op, Version};
+
+    let major = comp.major;
+    let minor = comp.minor.unwrap_or(0);
+    let patch = comp.patch.unwrap_or(0);
+
+    let mut floor = Version::new(major, minor, patch);
+    floor.pre = comp.pre.clone();
+
+    // The first version _not_ covered by a partial version, eg. `1.3.0` for
+    // `1.2`, or `None` if the version is complete
+    let next = if comp.patch.is_some() {
+        None
+    } else if comp.minor.is_some() {
+        Some(Version::new(major, minor + 1, 0))
+    } else {
+        Some(Version::new(major + 1, 0, 0))
+    };
+
+    match comp.op {
+        Op::Exact | Op::Wildcard => match next {
+            Some(next) => (Some((floor, true)), Some((next, false))),
+            None => (Some((floor.clone(), true)), Some((floor, true))),
+        },
+        Op::Greater => match next {
+            Some(next) => (Some((next, true)), None),
+            None => (Some((floor, false)), None),
+        },
+        Op::GreaterEq => (Some((floor, true)), None),
+        Op::Less => (None, Some((floor, false))),
+        Op::LessEq => match next {
+            Some(next) => (None, Some((next, false))),
+            None => (None, Some((floor, true))),
+        },
+        Op::Tilde => {
+            let upper = if comp.minor.is_some() {
+                Version::new(major, minor + 1, 0)
+            } else {
+                Version::new(major + 1, 0, 0)
+            };
+
+            (Some((floor, true)), Some((upper, false)))
+        }
+        Op::Caret => {
+            let upper = if major > 0 || comp.minor.is_none() {
+                Version::new(major + 1, 0, 0)
+            } else if minor > 0 || comp.patch.is_none() {
+                Version::new(0, minor + 1, 0)
+            } else {
+                Version::new(0, 0, patch + 1)
+            };
+
+            (Some((floor, true)), Some((upper, false)))
+        }
+        // Be conservative with operators we don't know about and assume they
+        // could match any version
+        _ => (None, None),
+    }
+}
+
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct PackageSpecOrExtended<T> {
     pub spec: PackageSpec,
@@ -408,4 +520,30 @@ mod test {
 
         insta::assert_json_snapshot!(validated);
     }
+
+    #[test]
+    fn detects_overlapping_specs() {
+        let spec = |name: &str, req: Option<&str>| PackageSpec {
+            name: Spanned::new(name.to_owned()),
+            version_req: req.map(|r| r.parse().unwrap()),
+        };
+
+        let overlaps = |a: Option<&str>, b: Option<&str>| {
+            spec("foo", a).overlaps(&spec("foo", b)) && spec("foo", b).overlaps(&spec("foo", a))
+        };
+
+        assert!(overlaps(None, Some("=1.5")));
+        assert!(overlaps(Some(">=1"), Some("=1.5")));
+        assert!(overlaps(Some("^0.2"), Some("<0.2.1")));
+        assert!(overlaps(Some("<=1.2"), Some(">1.2.7")));
+        assert!(overlaps(Some("~1.2.3"), Some(">=1.2.9, <2")));
+
+        assert!(!overlaps(Some(">=1"), Some("<1")));
+        assert!(!overlaps(Some("^0.2"), Some("^0.3")));
+        assert!(!overlaps(Some("=1.5.0"), Some(">1.5.0")));
+        assert!(!overlaps(Some("<=1.2"), Some(">1.2")));
+        assert!(!overlaps(Some("^0.0.3"), Some("=0.0.4")));
+
+        assert!(!spec("foo", None).overlaps(&spec("bar", None)));
+    }
 }
@@ -1,9 +1,16 @@
 pub mod general;
-mod grapher;
+mod github;
+pub mod grapher;
 mod sink;
 
-pub use grapher::{cs_diag_to_json, diag_to_json, write_graph_as_text, InclusionGrapher};
-pub use sink::{DiagnosticOverrides, ErrorSink};
+pub use github::{cs_diag_to_workflow_command, diag_to_workflow_command};
+
+pub use grapher::{
+    cs_diag_to_json, diag_to_json, write_graph_as_text, write_graph_as_text_with_anchors,
+    write_graph_as_text_with_options, GraphBudget, GraphError, InclusionGrapher, TextGraphOptions,
+    VersionPrecision,
+};
+pub use sink::{CrateWaivers, DiagnosticOverrides, ErrorSink};
 
 use std::{collections::HashMap, ops::Range};
 
@@ -120,8 +127,50 @@ impl Pack {
     pub fn iter(&self) -> impl Iterator<Item = &Diag> {
         self.diags.iter()
     }
+
+    /// Sorts the diagnostics in the pack into a total order, so that the
+    /// output is reproducible regardless of the order they were pushed in.
+    ///
+    /// Diagnostics are ordered by severity, most severe first, then by the
+    /// span of their first label, then by the id of the first crate they
+    /// reference. Diagnostics that compare equal keep their insertion order.
+    pub fn sorted(mut self) -> Self {
+        use std::cmp::Ordering;
+
+        self.diags.sort_by(|a, b| {
+            b.diag
+                .severity
+                .cmp(&a.diag.severity)
+                .then_with(|| {
+                    let a = a.diag.labels.first().map(|l| (l.file_id, l.range.clone()));
+                    let b = b.diag.labels.first().map(|l| (l.file_id, l.range.clone()));
+
+                    match (a, b) {
+                        (Some(a), Some(b)) => {
+                            a.0.cmp(&b.0)
+                                .then(a.1.start.cmp(&b.1.start))
+                                .then(a.1.end.cmp(&b.1.end))
+                        }
+                        (None, Some(_)) => Ordering::Less,
+                        (Some(_), None) => Ordering::Greater,
+                        (None, None) => Ordering::Equal,
+                    }
+                })
+                .then_with(|| {
+                    a.graph_nodes
+                        .first()
+                        .map(|gn| &gn.kid)
+                        .cmp(&b.graph_nodes.first().map(|gn| &gn.kid))
+                })
+        });
+
+        self
+    }
 }
 
+/// Iterates the diagnostics in the order they were pushed to the pack, which
+/// is not deterministic for checks that run in parallel, use [`Pack::sorted`]
+/// first if a stable order is required
 impl IntoIterator for Pack {
     type Item = Diag;
     type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -232,6 +281,33 @@ impl KrateSpans {
     }
 }
 
+/// A 1-based line and column in a file
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Resolves the start and end of a span in the specified file to their line
+/// and column, returning `None` if the span is outside the bounds of the file
+pub fn resolve_span(
+    files: &Files,
+    file_id: FileId,
+    span: impl Into<Span>,
+) -> Option<(LineColumn, LineColumn)> {
+    let span = span.into();
+
+    let resolve = |byte: usize| -> Option<LineColumn> {
+        let loc = files.location(file_id, u32::try_from(byte).ok()?).ok()?;
+        Some(LineColumn {
+            line: loc.line.to_usize() + 1,
+            column: loc.column.to_usize() + 1,
+        })
+    };
+
+    Some((resolve(span.start)?, resolve(span.end)?))
+}
+
 pub type KrateCoord = Coord;
 pub type CfgCoord = Coord;
 
@@ -326,4 +402,69 @@ mod test {
 
         insta::assert_debug_snapshot!(unique);
     }
+
+    #[test]
+    fn resolves_spans() {
+        use super::{resolve_span, Files, LineColumn};
+
+        let mut files = Files::new();
+        let id = files.add(
+            "deny.toml",
+            "[bans]\nmultiple-versions = 'deny'\n".to_owned(),
+        );
+
+        let (start, end) = resolve_span(&files, id, 27..33).unwrap();
+        assert_eq!(
+            start,
+            LineColumn {
+                line: 2,
+                column: 21
+            }
+        );
+        assert_eq!(
+            end,
+            LineColumn {
+                line: 2,
+                column: 27
+            }
+        );
+
+        assert!(resolve_span(&files, id, 28..100).is_none());
+    }
+
+    #[test]
+    fn sorts_packs() {
+        use super::{Check, Diagnostic, Files, Label, Pack};
+
+        let mut files = Files::new();
+        let id = files.add("sorted.toml", "a = 1\nb = 2\nc = 3\n".to_owned());
+
+        let mut pack = Pack::new(Check::Bans);
+        pack.push(Diagnostic::note().with_message("note"));
+        pack.push(
+            Diagnostic::warning()
+                .with_message("second warning")
+                .with_labels(vec![Label::primary(id, 6..11)]),
+        );
+        pack.push(Diagnostic::error().with_message("error"));
+        pack.push(
+            Diagnostic::warning()
+                .with_message("first warning")
+                .with_labels(vec![Label::primary(id, 0..5)]),
+        );
+        pack.push(Diagnostic::warning().with_message("unlabeled warning"));
+
+        let sorted: Vec<_> = pack.sorted().into_iter().map(|d| d.diag.message).collect();
+
+        assert_eq!(
+            sorted,
+            [
+                "error",
+                "unlabeled warning",
+                "first warning",
+                "second warning",
+                "note"
+            ]
+        );
+    }
 }
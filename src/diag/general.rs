@@ -20,6 +20,7 @@ use std::fmt;
 #[strum(serialize_all = "kebab-case")]
 pub enum Code {
     Deprecated,
+    ConfigChecksumMismatch,
 }
 
 impl From<Code> for String {
@@ -81,3 +82,22 @@ impl From<Deprecated> for Diagnostic {
             .with_code(Code::Deprecated)
     }
 }
+
+pub struct ConfigChecksumMismatch<'a> {
+    pub expected: &'a crate::cfg::Checksum,
+    pub actual: crate::cfg::Checksum,
+}
+
+impl<'a> From<ConfigChecksumMismatch<'a>> for Diagnostic {
+    fn from(ccm: ConfigChecksumMismatch<'a>) -> Self {
+        Diagnostic::new(Severity::Error)
+            .with_message(format!(
+                "config checksum mismatch, expected {} but calculated {}",
+                ccm.expected, ccm.actual
+            ))
+            .with_notes(vec![
+                "the config has been modified since the expected checksum was recorded".to_owned(),
+            ])
+            .with_code(Code::ConfigChecksumMismatch)
+    }
+}
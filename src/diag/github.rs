@@ -0,0 +1,157 @@
+use super::{Diag, Diagnostic, Files, Severity};
+use crate::Krates;
+use std::fmt::Write;
+
+/// Converts a diagnostic into a GitHub Actions [workflow command](https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions),
+/// which is displayed as an annotation on the relevant file in the workflow
+/// summary and pull requests
+///
+/// The location is that of the diagnostic's primary label, if it has one
+pub fn cs_diag_to_workflow_command(diag: Diagnostic, files: &Files) -> String {
+    let location = diag
+        .labels
+        .first()
+        .and_then(|label| label_location(label, files));
+
+    write_command(&diag, location)
+}
+
+/// Converts a crate diagnostic into a GitHub Actions workflow command
+///
+/// Since crate diagnostics point to a synthesized lock file that doesn't exist
+/// on disk, the location is instead the manifest of the first crate the
+/// diagnostic pertains to, at the line of its package declaration if it can
+/// be found
+///
+/// Annotations can only be shown for files in the repository, so if that
+/// manifest is outside the workspace root, eg. a registry or git crate, the
+/// location is instead the first primary label in the config, if there is one
+pub fn diag_to_workflow_command(diag: Diag, files: &Files, krates: Option<&Krates>) -> String {
+    let Some(krates) = krates else {
+        return cs_diag_to_workflow_command(diag.diag, files);
+    };
+
+    let root = krates.workspace_root();
+    let manifest = diag
+        .graph_nodes
+        .first()
+        .and_then(|gn| Some(&krates[krates.nid_for_kid(&gn.kid)?].manifest_path))
+        .filter(|manifest| manifest.starts_with(root));
+
+    let Some(manifest) = manifest else {
+        let lock_path = root.join("Cargo.lock");
+        let location = diag
+            .diag
+            .labels
+            .iter()
+            .filter(|label| {
+                label.style == codespan_reporting::diagnostic::LabelStyle::Primary
+                    && files.name(label.file_id) != lock_path.as_std_path().as_os_str()
+            })
+            .find_map(|label| label_location(label, files));
+
+        return write_command(&diag.diag, location);
+    };
+
+    let line = std::fs::read_to_string(manifest).ok().and_then(|contents| {
+        contents
+            .lines()
+            .position(|line| line.trim() == "[package]")
+            .map(|i| i + 1)
+    });
+
+    write_command(
+        &diag.diag,
+        Some(Location {
+            file: manifest.to_string(),
+            line,
+            column: None,
+        }),
+    )
+}
+
+/// Resolves the location of a label's start
+fn label_location(label: &super::Label, files: &Files) -> Option<Location> {
+    let (start, _end) = super::resolve_span(files, label.file_id, label.range.clone())?;
+
+    Some(Location {
+        file: files.name(label.file_id).to_string_lossy().into_owned(),
+        line: Some(start.line),
+        column: Some(start.column),
+    })
+}
+
+struct Location {
+    file: String,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+fn write_command(diag: &Diagnostic, location: Option<Location>) -> String {
+    let mut cmd = String::from(match diag.severity {
+        Severity::Bug | Severity::Error => "::error",
+        Severity::Warning => "::warning",
+        Severity::Note | Severity::Help => "::notice",
+    });
+
+    let mut props = Vec::new();
+    if let Some(loc) = location {
+        props.push(format!("file={}", escape_property(&loc.file)));
+        if let Some(line) = loc.line {
+            props.push(format!("line={line}"));
+        }
+        if let Some(col) = loc.column {
+            props.push(format!("col={col}"));
+        }
+    }
+    if let Some(code) = &diag.code {
+        props.push(format!("title={}", escape_property(code)));
+    }
+
+    if !props.is_empty() {
+        cmd.push(' ');
+        cmd.push_str(&props.join(","));
+    }
+
+    let _ = write!(cmd, "::{}", escape_data(&diag.message));
+    for note in &diag.notes {
+        let _ = write!(cmd, "%0A{}", escape_data(note));
+    }
+
+    cmd
+}
+
+#[inline]
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+#[inline]
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::diag::Label;
+
+    #[test]
+    fn formats_error_as_workflow_command() {
+        let mut files = Files::new();
+        let id = files.add("deny.toml", "[bans]\ndeny = ['openssl']\n".to_owned());
+
+        let diag = Diagnostic::error()
+            .with_message("crate 'openssl = 0.10.64' is explicitly banned")
+            .with_code("banned")
+            .with_labels(vec![Label::primary(id, 15..22)])
+            .with_notes(vec!["use rustls, 100% rust".to_owned()]);
+
+        assert_eq!(
+            cs_diag_to_workflow_command(diag, &files),
+            "::error file=deny.toml,line=2,col=9,title=banned::crate 'openssl = 0.10.64' is explicitly banned%0Ause rustls, 100%25 rust"
+        );
+    }
+}
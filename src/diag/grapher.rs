@@ -1,8 +1,43 @@
 use super::NodePrint;
-use crate::{DepKind, Krates};
-use anyhow::Context;
+use crate::{DepKind, Kid, Krates};
 use krates::{petgraph as pg, Edge, Node};
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+/// Errors that can occur when building or writing an inclusion graph
+#[derive(Debug)]
+pub enum GraphError {
+    /// The crate, or crate feature, was not present in the crate graph
+    NodeNotFound(Kid),
+    /// Failed to format the graph
+    Format(fmt::Error),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NodeNotFound(kid) => write!(f, "unable to find node for '{kid}'"),
+            Self::Format(err) => write!(f, "failed to format graph: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NodeNotFound(_) => None,
+            Self::Format(err) => Some(err),
+        }
+    }
+}
+
+impl From<fmt::Error> for GraphError {
+    fn from(err: fmt::Error) -> Self {
+        Self::Format(err)
+    }
+}
 
 #[derive(serde::Serialize)]
 pub struct GraphNode {
@@ -12,6 +47,85 @@ pub struct GraphNode {
     repeat: bool,
     #[serde(skip_serializing_if = "is_empty")]
     parents: Vec<GraphNode>,
+    /// True if the traversal budget was exceeded before all of the node's
+    /// parents could be added
+    #[serde(skip_serializing_if = "is_false")]
+    truncated: bool,
+    /// True if the node is on a path that has been highlighted
+    #[serde(skip_serializing_if = "is_false")]
+    highlighted: bool,
+    /// The crate the node is or belongs to, used to generate its anchor
+    #[serde(skip)]
+    kid: Kid,
+}
+
+impl GraphNode {
+    /// Gets a short anchor that is stable for the node's crate (and feature)
+    /// across graphs, so that a repeated node can reference the location its
+    /// full expansion is printed at
+    pub fn anchor(&self) -> String {
+        // FNV-1a, we want the same anchor regardless of platform or compiler
+        // version so the std hasher is not suitable
+        fn hash(mut h: u64, bytes: &[u8]) -> u64 {
+            for b in bytes {
+                h ^= *b as u64;
+                h = h.wrapping_mul(0x100000001b3);
+            }
+            h
+        }
+
+        let mut h = hash(0xcbf29ce484222325, self.kid.repr.as_bytes());
+        if let NodeInner::Feature { name, .. } = &self.inner {
+            h = hash(hash(h, b"/"), name.as_bytes());
+        }
+
+        format!("#{:08x}", h as u32)
+    }
+
+    /// True if the node is on a path that has been highlighted
+    #[inline]
+    pub fn is_highlighted(&self) -> bool {
+        self.highlighted
+    }
+
+    /// The nodes that depend on this node
+    #[inline]
+    pub fn parents(&self) -> &[GraphNode] {
+        &self.parents
+    }
+
+    /// Highlights the node and every path that leads to it
+    pub fn highlight(&mut self) {
+        self.highlighted = true;
+        for parent in &mut self.parents {
+            parent.highlight();
+        }
+    }
+
+    /// The crate the node is or belongs to
+    #[inline]
+    pub fn kid(&self) -> &Kid {
+        &self.kid
+    }
+
+    /// Removes the parents, and thus every path through them, of each node
+    /// whose crate doesn't satisfy the predicate
+    pub fn retain(&mut self, keep: &impl Fn(&Kid) -> bool) {
+        self.parents.retain(|parent| keep(&parent.kid));
+        for parent in &mut self.parents {
+            parent.retain(keep);
+        }
+    }
+
+    /// True if the graph was truncated due to exceeding the [`GraphBudget`]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated || self.parents.iter().any(Self::is_truncated)
+    }
+
+    /// The total number of nodes in the graph
+    pub fn node_count(&self) -> usize {
+        1 + self.parents.iter().map(Self::node_count).sum::<usize>()
+    }
 }
 
 #[derive(serde::Serialize)]
@@ -21,6 +135,10 @@ pub enum NodeInner {
         version: semver::Version,
         #[serde(skip_serializing_if = "Option::is_none")]
         kind: Option<&'static str>,
+        /// The number of crates collapsed into this crate, if it was
+        /// specified as a crate whose dependencies are collapsed
+        #[serde(skip_serializing_if = "Option::is_none")]
+        collapsed: Option<usize>,
     },
     Feature {
         crate_name: String,
@@ -38,15 +156,141 @@ fn is_empty(v: &Vec<GraphNode>) -> bool {
     v.is_empty()
 }
 
+/// Limits on the traversal done when building a single inclusion graph, so
+/// that pathological crate graphs don't consume unbounded time or stack
+#[derive(Copy, Clone, Debug)]
+pub struct GraphBudget {
+    /// The maximum number of unique nodes visited
+    pub max_nodes: usize,
+    /// The maximum depth of the graph, relative to the root
+    pub max_depth: usize,
+}
+
+impl Default for GraphBudget {
+    fn default() -> Self {
+        Self {
+            max_nodes: 50_000,
+            max_depth: 512,
+        }
+    }
+}
+
 /// Provides the `InclusionGrapher::write_graph` method which creates a reverse
 /// dependency graph rooted at a specific node
 pub struct InclusionGrapher<'a> {
     pub krates: &'a Krates,
+    budget: GraphBudget,
+    /// The number of crates collapsed into each collapsed crate
+    collapsed: HashMap<usize, usize>,
+    /// The collapsed crates each crate has been collapsed into
+    collapsed_into: HashMap<usize, Vec<usize>>,
+    /// The workspace members, if only they are shown as the dependents of
+    /// the root crate
+    members: Option<HashSet<krates::NodeId>>,
 }
 
 impl<'a> InclusionGrapher<'a> {
     pub fn new(krates: &'a Krates) -> Self {
-        Self { krates }
+        Self {
+            krates,
+            budget: GraphBudget::default(),
+            collapsed: HashMap::new(),
+            collapsed_into: HashMap::new(),
+            members: None,
+        }
+    }
+
+    /// Sets the traversal budget for each graph that is built
+    ///
+    /// If the budget is exceeded the graph is truncated rather than failing,
+    /// and the truncation is noted when the graph is written
+    pub fn with_budget(mut self, budget: GraphBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Collapses the dependencies of the crates with the specified names, eg.
+    /// meta crates such as `windows`, into the crate itself
+    ///
+    /// Only the dependencies that are in the graph solely due to the collapsed
+    /// crate are collapsed, and rather than being shown individually, the
+    /// collapsed crate is shown in their place along with the number of crates
+    /// collapsed into it. This only affects how graphs are displayed, the
+    /// crates are still present in the crate graph for every check.
+    pub fn collapse<'n>(mut self, names: impl IntoIterator<Item = &'n str>) -> Self {
+        let names: HashSet<_> = names.into_iter().collect();
+
+        for i in 0..self.krates.len() {
+            if !names.contains(self.krates[i].name.as_str()) {
+                continue;
+            }
+
+            let nid = krates::NodeId::new(i);
+
+            // Gather every crate the collapsed crate transitively depends on
+            let mut subtree = HashSet::new();
+            let mut stack = vec![nid];
+            while let Some(cur) = stack.pop() {
+                for dep in self.krates.direct_dependencies(cur) {
+                    if subtree.insert(dep.node_id) {
+                        stack.push(dep.node_id);
+                    }
+                }
+            }
+            subtree.remove(&nid);
+
+            // Remove any crates that are also depended upon by crates outside
+            // of the subtree, until only the crates that would not be in the
+            // graph without the collapsed crate remain
+            loop {
+                let shared: Vec<_> = subtree
+                    .iter()
+                    .copied()
+                    .filter(|dep| {
+                        self.krates
+                            .direct_dependents(*dep)
+                            .into_iter()
+                            .any(|dd| dd.node_id != nid && !subtree.contains(&dd.node_id))
+                    })
+                    .collect();
+
+                if shared.is_empty() {
+                    break;
+                }
+
+                for dep in shared {
+                    subtree.remove(&dep);
+                }
+            }
+
+            self.collapsed.insert(i, subtree.len());
+            for dep in subtree {
+                self.collapsed_into.entry(dep.index()).or_default().push(i);
+            }
+        }
+
+        self
+    }
+
+    /// Only shows the workspace members that ultimately depend on the root
+    /// crate of each graph, rather than every path to them
+    ///
+    /// Every intermediate crate between the root and the workspace members is
+    /// omitted, and the root is annotated with the number of crates omitted.
+    pub fn members_only(mut self, members_only: bool) -> Self {
+        self.members = members_only.then(|| {
+            self.krates
+                .workspace_members()
+                .filter_map(|wm| {
+                    if let Node::Krate { id, .. } = wm {
+                        self.krates.nid_for_kid(id)
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        });
+        self
     }
 
     /// Creates an inclusion graph rooted at the specified node.
@@ -54,53 +298,117 @@ impl<'a> InclusionGrapher<'a> {
         &self,
         id: &super::GraphNode,
         max_feature_depth: usize,
-    ) -> anyhow::Result<GraphNode> {
+    ) -> Result<GraphNode, GraphError> {
+        if let Some(members) = &self.members {
+            return self.build_members_graph(&id.kid, members);
+        }
+
         let mut visited = HashSet::new();
 
         let (node_id, _node) = self
             .krates
             .get_node(&id.kid, id.feature.as_deref())
-            .context("unable to find node")?;
+            .ok_or_else(|| GraphError::NodeNotFound(id.kid.clone()))?;
 
         let np = NodePrint {
             node: node_id,
             edge: None,
         };
 
-        let root = self.append_node(np, 0, max_feature_depth, &mut visited)?;
+        let root = self.append_node(np, 0, max_feature_depth, &mut visited);
 
         // If the graph was rooted on a feature node, we want to use that as the
         // root when building the graph, but want the actual crate the feature
         // belongs to be the root of the graph the user sees
         if id.feature.is_some() {
-            let (_id, root_krate) = self.krates.get_node(&id.kid, None).with_context(|| {
-                format!(
-                    "graph was built but we were unable to find the node for {}",
-                    id.kid
-                )
-            })?;
+            let Some((_id, Node::Krate { krate, .. })) = self.krates.get_node(&id.kid, None) else {
+                return Err(GraphError::NodeNotFound(id.kid.clone()));
+            };
 
-            let inner = if let Node::Krate { krate, .. } = root_krate {
-                NodeInner::Krate {
-                    name: krate.name.clone(),
-                    version: krate.version.clone(),
-                    kind: None,
-                }
-            } else {
-                anyhow::bail!("unable to find crate node for {}", id.kid);
+            let inner = NodeInner::Krate {
+                name: krate.name.clone(),
+                version: krate.version.clone(),
+                kind: None,
+                collapsed: None,
             };
 
             Ok(GraphNode {
                 inner,
                 repeat: false,
                 parents: vec![root],
+                truncated: false,
+                highlighted: false,
+                kid: id.kid.clone(),
             })
         } else {
             Ok(root)
         }
     }
 
-    fn make_node(&self, np: NodePrint) -> NodeInner {
+    /// Creates a graph where the root crate's only parents are the workspace
+    /// members that transitively depend on it
+    fn build_members_graph(
+        &self,
+        kid: &Kid,
+        members: &HashSet<krates::NodeId>,
+    ) -> Result<GraphNode, GraphError> {
+        let root = self
+            .krates
+            .nid_for_kid(kid)
+            .ok_or_else(|| GraphError::NodeNotFound(kid.clone()))?;
+
+        // Gather every crate that transitively depends on the root
+        let mut ancestors = HashSet::new();
+        let mut stack = vec![root];
+        while let Some(cur) = stack.pop() {
+            for dd in self.krates.direct_dependents(cur) {
+                if dd.node_id != root && ancestors.insert(dd.node_id) {
+                    stack.push(dd.node_id);
+                }
+            }
+        }
+
+        let mut parents: Vec<_> = ancestors
+            .iter()
+            .filter(|nid| members.contains(nid))
+            .map(|nid| {
+                let (inner, kid) = self.make_node(NodePrint {
+                    node: *nid,
+                    edge: None,
+                });
+                GraphNode {
+                    inner,
+                    repeat: false,
+                    parents: Vec::new(),
+                    truncated: false,
+                    highlighted: false,
+                    kid,
+                }
+            })
+            .collect();
+        parents.sort_by(|a, b| a.kid.cmp(&b.kid));
+
+        let (mut inner, kid) = self.make_node(NodePrint {
+            node: root,
+            edge: None,
+        });
+
+        if let NodeInner::Krate { collapsed, .. } = &mut inner {
+            let omitted = ancestors.len() - parents.len();
+            *collapsed = (omitted > 0).then_some(omitted);
+        }
+
+        Ok(GraphNode {
+            inner,
+            repeat: false,
+            parents,
+            truncated: false,
+            highlighted: false,
+            kid,
+        })
+    }
+
+    fn make_node(&self, np: NodePrint) -> (NodeInner, Kid) {
         match &self.krates.graph()[np.node] {
             Node::Krate { krate, .. } => {
                 let kind = np.edge.and_then(|eid| match self.krates.graph()[eid] {
@@ -112,24 +420,27 @@ impl<'a> InclusionGrapher<'a> {
                     Edge::Feature => None,
                 });
 
-                NodeInner::Krate {
-                    name: krate.name.clone(),
-                    version: krate.version.clone(),
-                    kind,
-                }
+                (
+                    NodeInner::Krate {
+                        name: krate.name.clone(),
+                        version: krate.version.clone(),
+                        kind,
+                        collapsed: self.collapsed.get(&np.node.index()).copied(),
+                    },
+                    krate.id.clone(),
+                )
             }
             Node::Feature { name, krate_index } => {
-                let crate_name =
-                    if let Node::Krate { krate, .. } = &self.krates.graph()[*krate_index] {
-                        krate.name.clone()
-                    } else {
-                        "".to_owned()
-                    };
+                // Feature nodes always point to the crate they belong to
+                let krate = &self.krates[*krate_index];
 
-                NodeInner::Feature {
-                    crate_name,
-                    name: name.clone(),
-                }
+                (
+                    NodeInner::Feature {
+                        crate_name: krate.name.clone(),
+                        name: name.clone(),
+                    },
+                    krate.id.clone(),
+                )
             }
         }
     }
@@ -140,15 +451,19 @@ impl<'a> InclusionGrapher<'a> {
         depth: usize,
         max_feature_depth: usize,
         visited: &mut HashSet<krates::NodeId>,
-    ) -> anyhow::Result<GraphNode> {
+    ) -> GraphNode {
         use pg::visit::EdgeRef;
 
         if !visited.insert(np.node) {
-            return Ok(GraphNode {
-                inner: self.make_node(np),
+            let (inner, kid) = self.make_node(np);
+            return GraphNode {
+                inner,
                 repeat: true,
                 parents: Vec::new(),
-            });
+                truncated: false,
+                highlighted: false,
+                kid,
+            };
         }
 
         let mut node_parents = smallvec::SmallVec::<[NodePrint; 10]>::new();
@@ -176,6 +491,27 @@ impl<'a> InclusionGrapher<'a> {
             );
         }
 
+        // Replace any dependents that have been collapsed with the crate(s)
+        // they were collapsed into
+        if !self.collapsed_into.is_empty() {
+            node_parents = node_parents
+                .into_iter()
+                .flat_map(
+                    |parent| match self.collapsed_into.get(&parent.node.index()) {
+                        Some(into) => into
+                            .iter()
+                            .map(|ci| NodePrint {
+                                node: krates::NodeId::new(*ci),
+                                edge: None,
+                            })
+                            .collect(),
+                        None => vec![parent],
+                    },
+                )
+                .collect();
+        }
+
+        let mut truncated = false;
         let parents = if !node_parents.is_empty() {
             // Resolve uses Hash data types internally but we want consistent output ordering
             node_parents.sort_by(|a, b| match (&graph[a.node], &graph[b.node]) {
@@ -184,11 +520,18 @@ impl<'a> InclusionGrapher<'a> {
                 (Node::Feature { .. }, Node::Krate { .. }) => std::cmp::Ordering::Greater,
                 (Node::Feature { name: a, .. }, Node::Feature { name: b, .. }) => a.cmp(b),
             });
+            // Multiple collapsed dependents will be replaced by the same crate
+            node_parents.dedup_by(|a, b| a.node == b.node && a.edge.is_none());
 
             let mut parents = Vec::with_capacity(node_parents.len());
 
             for parent in node_parents {
-                let pnode = self.append_node(parent, depth + 1, max_feature_depth, visited)?;
+                if depth >= self.budget.max_depth || visited.len() >= self.budget.max_nodes {
+                    truncated = true;
+                    break;
+                }
+
+                let pnode = self.append_node(parent, depth + 1, max_feature_depth, visited);
                 parents.push(pnode);
             }
 
@@ -197,11 +540,15 @@ impl<'a> InclusionGrapher<'a> {
             Vec::new()
         };
 
-        Ok(GraphNode {
-            inner: self.make_node(np),
+        let (inner, kid) = self.make_node(np);
+        GraphNode {
+            inner,
             repeat: false,
             parents,
-        })
+            truncated,
+            highlighted: false,
+            kid,
+        }
     }
 }
 
@@ -236,14 +583,13 @@ pub fn cs_diag_to_json(diag: CsDiag, files: &Files) -> serde_json::Value {
             let mut labels = Vec::with_capacity(diag.labels.len());
 
             for label in diag.labels {
-                let location = files
-                    .location(label.file_id, label.range.start as u32)
-                    .unwrap();
+                let (start, _end) =
+                    super::resolve_span(files, label.file_id, label.range.clone()).unwrap();
                 labels.push(serde_json::json!({
                     "message": label.message,
                     "span": files.source(label.file_id)[label.range].trim_matches('"'),
-                    "line": location.line.to_usize() + 1,
-                    "column": location.column.to_usize() + 1,
+                    "line": start.line,
+                    "column": start.column,
                 }));
             }
 
@@ -298,7 +644,49 @@ pub fn diag_to_json(
     to_print
 }
 
-pub fn write_graph_as_text(root: &GraphNode) -> String {
+/// How much of a crate's version is displayed when writing a graph
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum VersionPrecision {
+    /// The full version, eg. `1.2.3-rc.1`
+    #[default]
+    Full,
+    /// Only the major and minor versions, eg. `1.2`
+    MajorMinor,
+    /// Only the major version, eg. `1`
+    Major,
+}
+
+/// Options for writing a graph as text
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TextGraphOptions {
+    /// If true, each node's [`GraphNode::anchor`] is appended, so that a
+    /// repeated `(*)` node can be matched to its full expansion, even if that
+    /// is in a different graph
+    pub anchors: bool,
+    /// How much of each crate's version is displayed
+    pub version_precision: VersionPrecision,
+}
+
+pub fn write_graph_as_text(root: &GraphNode) -> Result<String, GraphError> {
+    write_graph_as_text_with_options(root, TextGraphOptions::default())
+}
+
+/// Writes the graph the same as [`write_graph_as_text`], but with each node's
+/// [`GraphNode::anchor`] appended
+pub fn write_graph_as_text_with_anchors(root: &GraphNode) -> Result<String, GraphError> {
+    write_graph_as_text_with_options(
+        root,
+        TextGraphOptions {
+            anchors: true,
+            ..Default::default()
+        },
+    )
+}
+
+pub fn write_graph_as_text_with_options(
+    root: &GraphNode,
+    opts: TextGraphOptions,
+) -> Result<String, GraphError> {
     use std::fmt::Write;
 
     const DWN: char = '│';
@@ -313,49 +701,212 @@ pub fn write_graph_as_text(root: &GraphNode) -> String {
         node: &GraphNode,
         out: &mut String,
         levels_continue: &mut smallvec::SmallVec<[bool; 10]>,
-    ) {
+        opts: TextGraphOptions,
+    ) -> fmt::Result {
         let star = if !node.repeat { "" } else { " (*)" };
+        let anchor = if opts.anchors {
+            format!(" [{}]", node.anchor())
+        } else {
+            String::new()
+        };
 
-        if let Some((&last_continues, rest)) = levels_continue.split_last() {
-            for &continues in rest {
-                let c = if continues { DWN } else { ' ' };
-                write!(out, "{c}   ").unwrap();
-            }
-
-            let c = if last_continues { TEE } else { ELL };
-            write!(out, "{c}{0}{0} ", RGT).unwrap();
-        }
+        prefix(out, levels_continue)?;
 
         match &node.inner {
             NodeInner::Krate {
                 name,
                 version,
                 kind,
+                collapsed,
             } => {
                 if let Some(kind) = kind {
-                    write!(out, "({kind}) ").unwrap();
+                    write!(out, "({kind}) ")?;
+                }
+
+                match opts.version_precision {
+                    VersionPrecision::Full => write!(out, "{name} v{version}")?,
+                    VersionPrecision::MajorMinor => {
+                        write!(out, "{name} v{}.{}", version.major, version.minor)?;
+                    }
+                    VersionPrecision::Major => write!(out, "{name} v{}", version.major)?,
+                }
+
+                if let Some(collapsed) = collapsed {
+                    write!(out, " [+{collapsed} crates]")?;
                 }
 
-                writeln!(out, "{name} v{version}{star}").unwrap();
+                writeln!(out, "{star}{anchor}")?;
             }
             NodeInner::Feature { crate_name, name } => {
-                writeln!(out, "{crate_name} feature '{name}' {star}").unwrap();
+                writeln!(out, "{crate_name} feature '{name}' {star}{anchor}")?;
             }
         }
 
-        if node.parents.is_empty() {
-            return;
+        let children = node.parents.len() + usize::from(node.truncated);
+        if children == 0 {
+            return Ok(());
         }
 
-        let cont = node.parents.len() - 1;
+        let cont = children - 1;
 
         for (i, parent) in node.parents.iter().enumerate() {
             levels_continue.push(i < cont);
-            write(parent, out, levels_continue);
+            write(parent, out, levels_continue, opts)?;
+            levels_continue.pop();
+        }
+
+        if node.truncated {
+            levels_continue.push(false);
+            prefix(out, levels_continue)?;
+            writeln!(out, "... graph truncated (budget exceeded)")?;
             levels_continue.pop();
         }
+
+        Ok(())
+    }
+
+    fn prefix(out: &mut String, levels_continue: &[bool]) -> fmt::Result {
+        if let Some((&last_continues, rest)) = levels_continue.split_last() {
+            for &continues in rest {
+                let c = if continues { DWN } else { ' ' };
+                write!(out, "{c}   ")?;
+            }
+
+            let c = if last_continues { TEE } else { ELL };
+            write!(out, "{c}{0}{0} ", RGT)?;
+        }
+
+        Ok(())
     }
 
-    write(root, &mut out, &mut levels);
-    out
+    write(root, &mut out, &mut levels, opts)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn node(name: &str, repeat: bool, parents: Vec<GraphNode>) -> GraphNode {
+        GraphNode {
+            inner: NodeInner::Krate {
+                name: name.to_owned(),
+                version: semver::Version::new(1, 2, 3),
+                kind: None,
+                collapsed: None,
+            },
+            repeat,
+            parents,
+            truncated: false,
+            highlighted: false,
+            kid: krates::cm::PackageId {
+                repr: format!("registry+https://github.com/rust-lang/crates.io-index#{name}@1.0.0"),
+            }
+            .into(),
+        }
+    }
+
+    #[test]
+    fn truncates_graphs_exceeding_budget() {
+        let krates = crate::test_utils::KrateGather {
+            name: "features-galore",
+            all_features: true,
+            ..Default::default()
+        }
+        .gather();
+
+        let cfg_if = krates
+            .krates()
+            .find(|k| k.name == "cfg-if")
+            .expect("failed to find cfg-if");
+        let root = crate::diag::GraphNode {
+            kid: cfg_if.id.clone(),
+            feature: None,
+        };
+
+        fn depth(node: &GraphNode) -> usize {
+            node.parents.iter().map(|p| depth(p) + 1).max().unwrap_or(0)
+        }
+
+        // Including every feature edge results in a large graph
+        let full = InclusionGrapher::new(&krates)
+            .build_graph(&root, usize::MAX)
+            .unwrap();
+        assert!(!full.is_truncated());
+        assert!(full.node_count() > 20);
+
+        let grapher = InclusionGrapher::new(&krates).with_budget(GraphBudget {
+            max_nodes: 10,
+            max_depth: usize::MAX,
+        });
+        let graph = grapher.build_graph(&root, usize::MAX).unwrap();
+        assert!(graph.is_truncated());
+        assert!(graph.node_count() < full.node_count());
+
+        let text = write_graph_as_text(&graph).unwrap();
+        assert!(text.starts_with("cfg-if v"));
+        assert!(text.contains("... graph truncated (budget exceeded)\n"));
+
+        let grapher = InclusionGrapher::new(&krates).with_budget(GraphBudget {
+            max_nodes: usize::MAX,
+            max_depth: 2,
+        });
+        let graph = grapher.build_graph(&root, usize::MAX).unwrap();
+        assert!(graph.is_truncated());
+        assert!(depth(&graph) <= 2);
+    }
+
+    #[test]
+    fn anchors_match_revisits() {
+        let root = node(
+            "root",
+            false,
+            vec![
+                node("first", false, vec![node("shared", false, Vec::new())]),
+                node("second", false, vec![node("shared", true, Vec::new())]),
+            ],
+        );
+
+        let graph = write_graph_as_text_with_anchors(&root).unwrap();
+        let anchor_for = |needle: &str| {
+            let line = graph
+                .lines()
+                .find(|l| l.contains(needle))
+                .unwrap_or_else(|| panic!("failed to find '{needle}' in\n{graph}"));
+            let start = line.rfind('[').unwrap();
+            line[start + 1..line.len() - 1].to_owned()
+        };
+
+        let full = anchor_for("shared v1.2.3 [");
+        let revisit = anchor_for("shared v1.2.3 (*) [");
+
+        assert_eq!(full, revisit);
+        assert!(full.starts_with('#'));
+        assert_ne!(full, anchor_for("first v1.2.3"));
+        assert_ne!(full, anchor_for("second v1.2.3"));
+
+        // Anchors are only emitted on request
+        assert!(!write_graph_as_text(&root).unwrap().contains('['));
+    }
+
+    #[test]
+    fn abbreviates_versions() {
+        let root = node("root", false, Vec::new());
+
+        let write = |version_precision| {
+            write_graph_as_text_with_options(
+                &root,
+                TextGraphOptions {
+                    version_precision,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        assert_eq!(write(VersionPrecision::Full), "root v1.2.3\n");
+        assert_eq!(write(VersionPrecision::MajorMinor), "root v1.2\n");
+        assert_eq!(write(VersionPrecision::Major), "root v1\n");
+        assert_eq!(write_graph_as_text(&root).unwrap(), "root v1.2.3\n");
+    }
 }
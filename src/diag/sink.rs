@@ -3,6 +3,7 @@ use super::Pack;
 #[derive(Clone)]
 pub struct ErrorSink {
     pub overrides: Option<std::sync::Arc<DiagnosticOverrides>>,
+    pub waivers: Option<std::sync::Arc<CrateWaivers>>,
     pub channel: super::PackChannel,
 }
 
@@ -10,6 +11,7 @@ impl From<super::PackChannel> for ErrorSink {
     fn from(channel: super::PackChannel) -> Self {
         Self {
             overrides: None,
+            waivers: None,
             channel,
         }
     }
@@ -19,6 +21,10 @@ impl ErrorSink {
     pub fn push(&mut self, pack: impl Into<Pack>) {
         let mut pack = pack.into();
 
+        if let Some(waivers) = &self.waivers {
+            pack.diags.retain(|diag| !waivers.is_waived(diag));
+        }
+
         if let Some(overrides) = &self.overrides {
             for diag in &mut pack.diags {
                 if let Some(new_severity) = diag
@@ -64,3 +70,55 @@ impl DiagnosticOverrides {
             .unwrap_or(severity)
     }
 }
+
+/// Crates can waive specific diagnostics for themselves via a
+/// `[package.metadata.cargo-deny]` table in their manifest
+#[derive(Clone, Default)]
+pub struct CrateWaivers {
+    waivers: std::collections::BTreeMap<crate::Kid, Vec<String>>,
+}
+
+impl CrateWaivers {
+    /// Gathers the waivers for every first-party crate in the graph, returning
+    /// `None` if no crate has waived anything
+    ///
+    /// Waivers from third-party crates are ignored, as otherwise a crate could
+    /// hide the findings about itself, eg. that it is banned
+    pub fn new(krates: &crate::Krates) -> Option<Self> {
+        let waivers: std::collections::BTreeMap<_, Vec<_>> = krates
+            .krates()
+            .filter(|krate| crate::is_first_party(krate, krates))
+            .filter_map(|krate| {
+                let codes: Vec<_> = krate.waived_codes().map(String::from).collect();
+                (!codes.is_empty()).then(|| (krate.id.clone(), codes))
+            })
+            .collect();
+
+        (!waivers.is_empty()).then_some(Self { waivers })
+    }
+
+    /// Waives additional codes for the specified crate
+    pub(crate) fn waive(&mut self, kid: crate::Kid, codes: impl IntoIterator<Item = String>) {
+        let waived = self.waivers.entry(kid).or_default();
+        for code in codes {
+            if !waived.contains(&code) {
+                waived.push(code);
+            }
+        }
+    }
+
+    /// A diagnostic is waived if every crate it pertains to has waived its
+    /// code, so that a waiver never hides a finding for a different crate
+    fn is_waived(&self, diag: &super::Diag) -> bool {
+        let Some(code) = diag.diag.code.as_deref() else {
+            return false;
+        };
+
+        !diag.graph_nodes.is_empty()
+            && diag.graph_nodes.iter().all(|gn| {
+                self.waivers
+                    .get(&gn.kid)
+                    .map_or(false, |codes| codes.iter().any(|c| c == code))
+            })
+    }
+}
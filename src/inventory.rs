@@ -0,0 +1,162 @@
+//! Inventories of the crate versions in a graph, used to determine the crates
+//! that have been added relative to a baseline, eg. the base branch of a PR
+
+use crate::{
+    diag::{self, grapher::GraphNode, GraphError, InclusionGrapher},
+    Krate, Krates, Span, Spanned,
+};
+use semver::Version;
+use std::collections::BTreeSet;
+use toml_span::{de_helpers::TableHelper, value::Value, DeserError, Deserialize};
+
+/// The set of crate versions present in a crate graph
+#[derive(Default)]
+pub struct Inventory {
+    krates: BTreeSet<(String, Version)>,
+}
+
+impl Inventory {
+    /// Creates an inventory of the crates in the graph
+    pub fn from_krates(krates: &Krates) -> Self {
+        Self {
+            krates: krates
+                .krates()
+                .map(|krate| (krate.name.clone(), krate.version.clone()))
+                .collect(),
+        }
+    }
+
+    /// Creates an inventory from the contents of a `Cargo.lock`, so that
+    /// a baseline can be retrieved without resolving the full crate graph
+    pub fn from_lockfile(contents: &str) -> Result<Self, DeserError> {
+        let mut lock = toml_span::parse(contents)?;
+        let lock = Lockfile::deserialize(&mut lock)?;
+
+        Ok(Self {
+            krates: lock
+                .packages
+                .into_iter()
+                .map(|pkg| (pkg.name, pkg.version))
+                .collect(),
+        })
+    }
+
+    /// Checks if the specific version of the crate is in the inventory
+    #[inline]
+    pub fn contains(&self, krate: &Krate) -> bool {
+        self.krates
+            .contains(&(krate.name.clone(), krate.version.clone()))
+    }
+
+    /// Gets the crates in the graph that are not present in the inventory
+    pub fn added<'k>(&self, krates: &'k Krates) -> Vec<&'k Krate> {
+        krates
+            .krates()
+            .filter(|krate| !self.contains(krate))
+            .collect()
+    }
+}
+
+/// Builds an inclusion graph for each crate version in the graph that is not
+/// present in the baseline, showing why each new crate was added
+pub fn delta_graphs(krates: &Krates, baseline: &Inventory) -> Result<Vec<GraphNode>, GraphError> {
+    let grapher = InclusionGrapher::new(krates);
+
+    baseline
+        .added(krates)
+        .into_iter()
+        .map(|krate| {
+            grapher.build_graph(
+                &diag::GraphNode {
+                    kid: krate.id.clone(),
+                    feature: None,
+                },
+                0,
+            )
+        })
+        .collect()
+}
+
+struct Lockfile {
+    packages: Vec<LockedPackage>,
+}
+
+impl<'de> Deserialize<'de> for Lockfile {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let packages = th.optional("package").unwrap_or_default();
+        th.finalize(Some(value))?;
+
+        Ok(Self { packages })
+    }
+}
+
+struct LockedPackage {
+    name: String,
+    version: Version,
+}
+
+impl<'de> Deserialize<'de> for LockedPackage {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let name = th.required("name")?;
+        let version: Spanned<String> = th.required_s("version")?;
+        th.finalize(Some(value))?;
+
+        let version = parse_version(&version.value, version.span)?;
+        Ok(Self { name, version })
+    }
+}
+
+fn parse_version(version: &str, span: Span) -> Result<Version, toml_span::Error> {
+    version
+        .parse()
+        .map_err(|err: semver::Error| toml_span::Error {
+            kind: toml_span::ErrorKind::Custom(
+                format!("invalid version '{version}': {err}").into(),
+            ),
+            span,
+            line_info: None,
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::KrateGather;
+
+    #[test]
+    fn graphs_added_crates() {
+        let krates = KrateGather::new("categories").gather();
+
+        // The baseline doesn't have the `untagged` crate
+        let baseline = Inventory::from_lockfile(
+            r#"
+version = 3
+
+[[package]]
+name = "categories"
+version = "0.1.0"
+dependencies = [
+ "tagged",
+]
+
+[[package]]
+name = "tagged"
+version = "0.1.0"
+"#,
+        )
+        .unwrap();
+
+        let graphs = delta_graphs(&krates, &baseline).unwrap();
+        assert_eq!(graphs.len(), 1);
+        assert_eq!(
+            diag::write_graph_as_text(&graphs[0]).unwrap(),
+            "untagged v0.1.0\n└── categories v0.1.0\n"
+        );
+
+        assert!(delta_graphs(&krates, &Inventory::from_krates(&krates))
+            .unwrap()
+            .is_empty());
+    }
+}
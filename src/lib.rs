@@ -8,6 +8,7 @@ pub mod advisories;
 pub mod bans;
 pub mod cfg;
 pub mod diag;
+pub mod inventory;
 /// Configuration and logic for checking crate licenses
 pub mod licenses;
 pub mod root_cfg;
@@ -242,6 +243,14 @@ pub struct Krate {
     pub features: BTreeMap<String, Vec<String>>,
     pub targets: Vec<cm::Target>,
     pub publish: Option<Vec<String>>,
+    pub keywords: Vec<String>,
+    pub categories: Vec<String>,
+    /// The name of the native library the crate links, if any
+    pub links: Option<String>,
+    /// The crate's `[package.metadata]` table
+    pub metadata: serde_json::Value,
+    /// The minimum rust version the crate declares it supports
+    pub rust_version: Option<Version>,
 }
 
 #[cfg(test)]
@@ -262,6 +271,11 @@ impl Default for Krate {
             manifest_path: PathBuf::new(),
             repository: None,
             publish: None,
+            keywords: Vec::new(),
+            categories: Vec::new(),
+            links: None,
+            metadata: serde_json::Value::Null,
+            rust_version: None,
         }
     }
 }
@@ -342,11 +356,27 @@ impl From<cm::Package> for Krate {
             },
             features: pkg.features,
             publish: pkg.publish,
+            keywords: pkg.keywords,
+            categories: pkg.categories,
+            links: pkg.links,
+            metadata: pkg.metadata,
+            rust_version: pkg.rust_version,
         }
     }
 }
 
 impl Krate {
+    /// The diagnostic codes the crate has waived for itself via its
+    /// `[package.metadata.cargo-deny]` table, eg. `waive = ["duplicate"]`
+    pub fn waived_codes(&self) -> impl Iterator<Item = &str> {
+        self.metadata
+            .pointer("/cargo-deny/waive")
+            .and_then(|waive| waive.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|code| code.as_str())
+    }
+
     /// Returns true if the crate is marked as `publish = false`, or
     /// it is only published to the specified private registries
     pub(crate) fn is_private(&self, private_registries: &[&str]) -> bool {
@@ -462,6 +492,25 @@ pub fn match_krate(krate: &Krate, pid: &cfg::PackageSpec) -> bool {
     krate.name == pid.name.value && match_req(&krate.version, pid.version_req.as_ref())
 }
 
+/// Determines if the crate with the specified id is a workspace member
+#[inline]
+pub fn is_workspace_member(krates: &Krates, kid: &Kid) -> bool {
+    krates.workspace_members().any(|n| {
+        if let krates::Node::Krate { id, .. } = n {
+            id == kid
+        } else {
+            false
+        }
+    })
+}
+
+/// Determines if a crate is first-party, ie. it is either a workspace member,
+/// or a path dependency that isn't sourced from a registry or git repository
+#[inline]
+pub fn is_first_party(krate: &Krate, krates: &Krates) -> bool {
+    krate.source.is_none() || is_workspace_member(krates, &krate.id)
+}
+
 use sources::cfg::GitSpec;
 
 #[inline]
@@ -592,7 +641,20 @@ pub fn krates_with_index(
 
 #[cfg(test)]
 mod test {
-    use super::Source;
+    use super::{is_first_party, Source};
+
+    #[test]
+    fn first_party() {
+        let krates = crate::test_utils::KrateGather::new("wildcards/maincrate").gather();
+        let krate = |name: &str| krates.krates().find(|k| k.name == name).unwrap();
+
+        // workspace member
+        assert!(is_first_party(krate("wildcards-test-crate"), &krates));
+        // path dependency
+        assert!(is_first_party(krate("wildcards-test-dep"), &krates));
+        // crates.io
+        assert!(!is_first_party(krate("ansi_term"), &krates));
+    }
 
     #[test]
     fn parses_sources() {
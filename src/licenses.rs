@@ -21,7 +21,7 @@ use crate::{
     LintLevel,
 };
 use cfg::BlanketAgreement;
-pub use gather::{Gatherer, LicenseInfo, LicenseStore};
+pub use gather::{find_license_files, Gatherer, LicenseInfo, LicenseStore};
 use gather::{KrateLicense, LicenseExprInfo, LicenseExprSource, Summary};
 
 pub use diags::Code;
@@ -51,6 +51,8 @@ fn evaluate_expression(
         ExplicitAllowance,
         ExplicitException,
         IsCopyleft,
+        CopyleftDenied,
+        CopyleftWarned,
         Default,
         NotExplicitlyAllowed,
     }
@@ -109,17 +111,45 @@ fn evaluate_expression(
             }
         }
 
-        // 3. A license that is specifically allowed will of course mean
+        // 3. Copyleft licenses can be denied, or warned about, by their
+        // strength, even if they are allowed, rather than needing to remove
+        // every variant of them from the allow list
+        let mut warn_copyleft = false;
+        if let spdx::LicenseItem::Spdx { id, .. } = req.license {
+            if id.is_copyleft() {
+                let level = if is_weak_copyleft(id) {
+                    cfg.weak_copyleft
+                } else {
+                    cfg.strong_copyleft
+                };
+
+                match level {
+                    LintLevel::Deny => {
+                        deny!(CopyleftDenied);
+                    }
+                    LintLevel::Warn => warn_copyleft = true,
+                    LintLevel::Allow => {}
+                }
+            }
+        }
+
+        // 4. A license that is specifically allowed will of course mean
         // that the requirement is met.
         for (i, allow) in cfg.allowed.iter().enumerate() {
             if allow.0.value.satisfies(req) {
                 hits.allowed.as_mut_bitslice().set(i, true);
+
+                if warn_copyleft {
+                    warnings += 1;
+                    allow!(CopyleftWarned);
+                }
+
                 allow!(ExplicitAllowance);
             }
         }
 
         if let Some(dep_cfg) = &cfg.deprecated {
-            // 4. If the license isn't explicitly allowed, it still may
+            // 5. If the license isn't explicitly allowed, it still may
             // be allowed by the blanket "OSI Approved" or "FSF Free/Libre"
             // allowances
             if let spdx::LicenseItem::Spdx { id, .. } = req.license {
@@ -183,7 +213,7 @@ fn evaluate_expression(
                 }
             }
 
-            // 5. Whelp, this license just won't do!
+            // 6. Whelp, this license just won't do!
             match dep_cfg.default {
                 LintLevel::Deny => {
                     deny!(Default);
@@ -256,12 +286,32 @@ fn evaluate_expression(
                 }
 
                 if id.is_copyleft() {
-                    notes.push("  - Copyleft".into());
+                    notes.push(format!(
+                        "  - Copyleft ({})",
+                        if is_weak_copyleft(id) {
+                            "weak"
+                        } else {
+                            "strong"
+                        }
+                    ));
                 }
 
                 if len == notes.len() {
                     notes.push("  - No additional metadata available for license".into());
                 }
+            } else if let Some(lr) = ctx
+                .cfg
+                .license_refs
+                .iter()
+                .find(|lr| lr.id.value == failed_req.req.license.to_string())
+            {
+                notes.push(format!("{}:", lr.id.value));
+                notes.push(format!(
+                    "  - {}",
+                    lr.description
+                        .as_deref()
+                        .unwrap_or("No additional metadata available for license")
+                ));
             } else {
                 // This would only happen if askalono used a newer license list than spdx, but we update
                 // both simultaneously
@@ -289,6 +339,9 @@ fn evaluate_expression(
                     Reason::NotExplicitlyAllowed => "license was not explicitly allowed",
                     Reason::IsBothFreeAndOsi => "license is FSF AND OSI approved",
                     Reason::IsCopyleft => "license is considered copyleft",
+                    Reason::CopyleftDenied =>
+                        "license is copyleft, which is denied for licenses of its strength",
+                    Reason::CopyleftWarned => "license is explicitly allowed, but is copyleft",
                     Reason::Default => {
                         match cfg
                             .deprecated
@@ -316,6 +369,21 @@ fn evaluate_expression(
         .with_notes(notes)
 }
 
+/// The prefixes of the weak copyleft licenses, every other copyleft license
+/// is considered strong
+const WEAK_COPYLEFT: &[&str] = &[
+    "LGPL-", "LGPLLR", "MPL-", "EPL-", "CDDL-", "CPL-", "MS-RL", "ErlPL-", "CECILL-C",
+];
+
+/// Determines if a copyleft license is weak, where only modifications to the
+/// licensed files must be shared under the same terms, rather than strong,
+/// where the entire work that includes them must be
+fn is_weak_copyleft(id: spdx::LicenseId) -> bool {
+    WEAK_COPYLEFT
+        .iter()
+        .any(|prefix| id.name.starts_with(prefix))
+}
+
 pub fn check(
     ctx: crate::CheckCtx<'_, cfg::ValidConfig>,
     summary: Summary<'_>,
@@ -334,8 +402,9 @@ pub fn check(
         .map(|s| s.as_str())
         .collect();
 
-    for krate_lic_nfo in summary.nfos {
+    for mut krate_lic_nfo in summary.nfos {
         let mut pack = Pack::with_kid(Check::Licenses, krate_lic_nfo.krate.id.clone());
+        let invalidated = std::mem::take(&mut krate_lic_nfo.invalidated);
 
         // If the user has set this, check if it's a private workspace
         // crate or a crate from a private registry and just print out
@@ -381,10 +450,18 @@ pub fn check(
                     krate: krate_lic_nfo.krate,
                     severity,
                     breadcrumbs: krate_lic_nfo.labels.into_iter().collect(),
+                    notes: krate_lic_nfo.notes,
                 });
             }
         }
 
+        for clarify_cfg in invalidated {
+            pack.push(diags::ClarificationInvalidated {
+                krate: krate_lic_nfo.krate,
+                clarify_cfg,
+            });
+        }
+
         if !pack.is_empty() {
             sink.push(pack);
         }
@@ -444,3 +521,40 @@ pub fn check(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::is_weak_copyleft;
+
+    #[test]
+    fn classifies_copyleft_strength() {
+        let weak = [
+            "LGPL-2.1-only",
+            "LGPL-3.0-or-later",
+            "LGPLLR",
+            "MPL-2.0",
+            "EPL-2.0",
+            "CDDL-1.0",
+            "CPL-1.0",
+            "MS-RL",
+            "ErlPL-1.1",
+            "CECILL-C",
+        ];
+
+        for id in weak {
+            assert!(is_weak_copyleft(spdx::license_id(id).unwrap()), "{id}");
+        }
+
+        let strong = [
+            "GPL-2.0-only",
+            "GPL-3.0-or-later",
+            "AGPL-3.0-only",
+            "CECILL-2.1",
+            "OSL-3.0",
+        ];
+
+        for id in strong {
+            assert!(!is_weak_copyleft(spdx::license_id(id).unwrap()), "{id}");
+        }
+    }
+}
@@ -99,6 +99,38 @@ impl<'de> Deserialize<'de> for FileSource {
     }
 }
 
+/// A custom `LicenseRef-` identifier for a license that is not part of the
+/// SPDX license list, eg. a proprietary or internal license, so that it can be
+/// used in the `allow` list like any other license.
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct LicenseRef {
+    /// The `LicenseRef-` identifier
+    pub id: Spanned<String>,
+    /// A human readable description of the license
+    pub description: Option<String>,
+    /// The hash of the license text. Any license file in a crate with this
+    /// exact hash is attributed to this license.
+    pub hash: Option<u32>,
+}
+
+impl<'de> Deserialize<'de> for LicenseRef {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+
+        let id = th.required("id")?;
+        let description = th.optional("description");
+        let hash = th.optional("hash");
+
+        th.finalize(None)?;
+
+        Ok(Self {
+            id,
+            description,
+            hash,
+        })
+    }
+}
+
 /// Some crates have complicated LICENSE files that eg contain multiple license
 /// texts in a single file, or are otherwise sufficiently different from the
 /// canonical license text that the confidence level cargo-deny can attribute to
@@ -222,12 +254,20 @@ pub struct Config {
     /// Determines the response to licenses in th `allow`ed list which do not
     /// exist in the dependency tree.
     pub unused_allowed_license: LintLevel,
+    /// Determines what happens when a strong copyleft license, eg. `GPL-3.0`,
+    /// is encountered, even if it is allowed
+    pub strong_copyleft: LintLevel,
+    /// Determines what happens when a weak copyleft license, eg. `MPL-2.0`,
+    /// is encountered, even if it is allowed
+    pub weak_copyleft: LintLevel,
     /// Overrides the license expression used for a particular crate as long as
     /// it exactly matches the specified license files and hashes
     pub clarify: Vec<Clarification>,
     /// Allow 1 or more additional licenses on a per-crate basis, so particular
     /// licenses aren't accepted for every possible crate and must be opted into
     pub exceptions: Vec<Exception>,
+    /// Custom `LicenseRef-` identifiers for licenses not in the SPDX license list
+    pub license_refs: Vec<LicenseRef>,
     /// If true, performs license checks for dev-dependencies for workspace
     /// crates as well
     pub include_dev: bool,
@@ -240,10 +280,13 @@ impl Default for Config {
         Self {
             private: Private::default(),
             unused_allowed_license: LintLevel::Warn,
+            strong_copyleft: LintLevel::Allow,
+            weak_copyleft: LintLevel::Allow,
             confidence_threshold: DEFAULT_CONFIDENCE_THRESHOLD,
             allow: Vec::new(),
             clarify: Vec::new(),
             exceptions: Vec::new(),
+            license_refs: Vec::new(),
             include_dev: false,
             deprecated: None,
             deprecated_spans: Vec::new(),
@@ -273,8 +316,11 @@ impl<'de> Deserialize<'de> for Config {
         let unused_allowed_license = th
             .optional("unused-allowed-license")
             .unwrap_or(LintLevel::Warn);
+        let strong_copyleft = th.optional("strong-copyleft").unwrap_or(LintLevel::Allow);
+        let weak_copyleft = th.optional("weak-copyleft").unwrap_or(LintLevel::Allow);
         let clarify = th.optional("clarify").unwrap_or_default();
         let exceptions = th.optional("exceptions").unwrap_or_default();
+        let license_refs = th.optional("license-refs").unwrap_or_default();
         let include_dev = th.optional("include-dev").unwrap_or_default();
 
         th.finalize(None)?;
@@ -296,8 +342,11 @@ impl<'de> Deserialize<'de> for Config {
             confidence_threshold,
             allow,
             unused_allowed_license,
+            strong_copyleft,
+            weak_copyleft,
             clarify,
             exceptions,
+            license_refs,
             include_dev,
             deprecated,
             deprecated_spans: fdeps,
@@ -313,6 +362,7 @@ impl crate::cfg::UnvalidatedConfig for Config {
     /// 1. Ensures all SPDX identifiers are valid
     /// 1. Ensures all SPDX expressions are valid
     /// 1. Ensures the same license is not both allowed and denied
+    /// 1. Ensures all custom license references are valid `LicenseRef-` identifiers
     fn validate(self, mut ctx: ValidationContext<'_>) -> Self::ValidCfg {
         use rayon::prelude::*;
 
@@ -398,6 +448,24 @@ impl crate::cfg::UnvalidatedConfig for Config {
             });
         }
 
+        let mut license_refs = Vec::with_capacity(self.license_refs.len());
+        for lr in self.license_refs {
+            if !lr.id.value.starts_with("LicenseRef-")
+                || spdx::Licensee::parse(&lr.id.value).is_err()
+            {
+                ctx.push(
+                    Diagnostic::error()
+                        .with_message("invalid license reference")
+                        .with_labels(vec![Label::primary(ctx.cfg_id, lr.id.span)
+                            .with_message("expected an identifier prefixed with `LicenseRef-`")]),
+                );
+
+                continue;
+            }
+
+            license_refs.push(lr);
+        }
+
         use crate::diag::general::{Deprecated, DeprecationReason};
 
         // Output any deprecations, we'll remove the fields at the same time we
@@ -419,8 +487,11 @@ impl crate::cfg::UnvalidatedConfig for Config {
             file_id: ctx.cfg_id,
             private: self.private,
             unused_allowed_license: self.unused_allowed_license,
+            strong_copyleft: self.strong_copyleft,
+            weak_copyleft: self.weak_copyleft,
             confidence_threshold: self.confidence_threshold,
             clarifications,
+            license_refs,
             exceptions,
             denied,
             allowed,
@@ -515,10 +586,13 @@ pub struct ValidConfig {
     pub file_id: FileId,
     pub private: Private,
     pub unused_allowed_license: LintLevel,
+    pub strong_copyleft: LintLevel,
+    pub weak_copyleft: LintLevel,
     pub confidence_threshold: f32,
     pub denied: Vec<Licensee>,
     pub allowed: Vec<Licensee>,
     pub clarifications: Vec<ValidClarification>,
+    pub license_refs: Vec<LicenseRef>,
     pub exceptions: Vec<ValidException>,
     pub ignore_sources: Vec<url::Url>,
     pub(crate) deprecated: Option<Deprecated>,
@@ -587,4 +661,34 @@ deny = [
             },
         );
     }
+
+    #[test]
+    fn rejects_invalid_license_refs() {
+        let cfg = r#"[licenses]
+license-refs = [
+    { id = "LicenseRef-Acme-Proprietary" },
+    { id = "Acme-Proprietary" },
+    { id = "LicenseRef-Acme OR MIT" },
+]"#;
+
+        let cd = ConfigData::<Licenses>::load_str("invalid-license-refs", cfg);
+        let validated: ValidConfig = cd.validate_with_diags(
+            |l| l.licenses,
+            |_files, diags| {
+                assert_eq!(
+                    diags
+                        .iter()
+                        .filter(|d| d.message == "invalid license reference")
+                        .count(),
+                    2
+                );
+            },
+        );
+
+        assert_eq!(validated.license_refs.len(), 1);
+        assert_eq!(
+            validated.license_refs[0].id.value,
+            "LicenseRef-Acme-Proprietary"
+        );
+    }
 }
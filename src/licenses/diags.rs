@@ -23,6 +23,7 @@ pub enum Code {
     LicenseNotEncountered,
     LicenseExceptionNotEncountered,
     MissingClarificationFile,
+    ClarificationInvalidated,
 }
 
 impl From<Code> for String {
@@ -35,6 +36,7 @@ pub(crate) struct Unlicensed<'a> {
     pub(crate) severity: Severity,
     pub(crate) krate: &'a Krate,
     pub(crate) breadcrumbs: Vec<Label>,
+    pub(crate) notes: Vec<String>,
 }
 
 impl<'a> From<Unlicensed<'a>> for Diag {
@@ -43,6 +45,7 @@ impl<'a> From<Unlicensed<'a>> for Diag {
             .with_message(format!("{} is unlicensed", u.krate))
             .with_code(Code::Unlicensed)
             .with_labels(u.breadcrumbs)
+            .with_notes(u.notes)
             .into()
     }
 }
@@ -95,6 +98,31 @@ impl From<UnmatchedLicenseException> for Diag {
     }
 }
 
+pub(crate) struct ClarificationInvalidated<'a> {
+    pub(crate) krate: &'a Krate,
+    pub(crate) clarify_cfg: CfgCoord,
+}
+
+impl<'a> From<ClarificationInvalidated<'a>> for Diag {
+    fn from(ci: ClarificationInvalidated<'a>) -> Self {
+        Diagnostic::new(Severity::Warning)
+            .with_message(format!(
+                "clarification for crate '{}' no longer applies as its license files have changed",
+                ci.krate
+            ))
+            .with_code(Code::ClarificationInvalidated)
+            .with_labels(vec![ci
+                .clarify_cfg
+                .into_label()
+                .with_message("clarification")])
+            .with_notes(vec![
+                "review the license files again, and update the clarification with their new hashes"
+                    .to_owned(),
+            ])
+            .into()
+    }
+}
+
 pub(crate) struct MissingClarificationFile<'a> {
     pub(crate) expected: &'a crate::cfg::Spanned<crate::PathBuf>,
     pub(crate) cfg_file_id: crate::diag::FileId,
@@ -1,4 +1,4 @@
-use super::cfg::{FileSource, ValidClarification, ValidConfig};
+use super::cfg::{FileSource, LicenseRef, ValidClarification, ValidConfig};
 use crate::{
     diag::{FileId, Files, Label},
     Krate, Path, PathBuf,
@@ -27,7 +27,9 @@ impl fmt::Debug for FileSource {
     }
 }
 
-fn find_license_files(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+/// Finds the files in the specified directory that are prefixed with `LICENSE`
+/// or `COPYING`, returning their paths relative to the directory
+pub fn find_license_files(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
     let entries = std::fs::read_dir(dir)?;
     Ok(entries
         .filter_map(|e| {
@@ -203,6 +205,7 @@ impl LicensePack {
         file: FileId,
         strategy: &askalono::ScanStrategy<'_>,
         confidence: f32,
+        license_refs: &[LicenseRef],
     ) -> Result<GatheredExpr, (String, Vec<Label>)> {
         use std::fmt::Write;
 
@@ -236,6 +239,21 @@ impl LicensePack {
                 PackFileData::Good(data) => {
                     write!(synth_toml, "hash = 0x{:08x}, ", data.hash).unwrap();
 
+                    // License texts that exactly match a user provided license
+                    // reference are attributed to it without any fuzzy matching
+                    if let Some(lr) = license_refs.iter().find(|lr| lr.hash == Some(data.hash)) {
+                        write!(synth_toml, "license = \"{}\"", lr.id.value).unwrap();
+                        writeln!(synth_toml, " }},").unwrap();
+
+                        if !sources.is_empty() {
+                            expr.push_str(" AND ");
+                        }
+
+                        expr.push_str(&lr.id.value);
+                        sources.push(lic_contents.path.as_str().to_owned());
+                        continue;
+                    }
+
                     let text = askalono::TextData::new(&data.content);
                     match strategy.scan(&text) {
                         Ok(lic_match) => {
@@ -386,6 +404,10 @@ pub struct KrateLicense<'a> {
     // Reasons for why the license was determined (or not!) when
     // gathering the license information
     pub(crate) labels: SmallVec<[Label; 1]>,
+
+    // Clarifications that matched the crate, but whose license files have
+    // changed since they were reviewed
+    pub(crate) invalidated: Vec<crate::diag::CfgCoord>,
 }
 
 pub struct Summary<'a> {
@@ -530,6 +552,7 @@ impl Gatherer {
                 let mut synth_id = None;
 
                 let mut labels = smallvec::SmallVec::<[Label; 1]>::new();
+                let mut invalidated = Vec::new();
 
                 let mut get_span = |key: &'static str| -> (FileId, std::ops::Range<usize>) {
                     if let Some(id) = synth_id {
@@ -574,19 +597,25 @@ impl Gatherer {
                         let clarifications_match = clarification.license_files.iter().all(|clf| {
                             match lp.insert_clarification(clf) {
                                 Ok(_) => true,
-                                Err(reason) => {
-                                    if let MismatchReason::Error(err) = reason {
-                                        if err.kind() == std::io::ErrorKind::NotFound {
-                                            labels.push(
-                                                super::diags::MissingClarificationFile {
-                                                    expected: &clf.path,
-                                                    cfg_file_id: cfg.file_id,
-                                                }
-                                                .into(),
-                                            );
-                                        }
+                                Err(MismatchReason::Error(err)) => {
+                                    if err.kind() == std::io::ErrorKind::NotFound {
+                                        labels.push(
+                                            super::diags::MissingClarificationFile {
+                                                expected: &clf.path,
+                                                cfg_file_id: cfg.file_id,
+                                            }
+                                            .into(),
+                                        );
                                     }
 
+                                    false
+                                }
+                                Err(MismatchReason::HashDiffers) => {
+                                    invalidated.push(crate::diag::CfgCoord {
+                                        file: cfg.file_id,
+                                        span: clarification.spec.name.span,
+                                    });
+
                                     false
                                 }
                             }
@@ -605,6 +634,7 @@ impl Gatherer {
                                 },
                                 labels,
                                 notes: Vec::new(),
+                                invalidated,
                             };
                         }
                     }
@@ -643,6 +673,7 @@ impl Gatherer {
                                     },
                                     labels,
                                     notes: Vec::new(),
+                                    invalidated,
                                 };
                             }
                             Err(err) => {
@@ -681,6 +712,7 @@ impl Gatherer {
                                         },
                                         labels,
                                         notes: Vec::new(),
+                                        invalidated,
                                     };
                                 }
                             }
@@ -698,11 +730,14 @@ impl Gatherer {
                 // 4
                 // We might have already loaded the licenses to check them against a clarification
                 let license_pack = license_pack.unwrap_or_else(|| LicensePack::read(krate));
+                let mut notes = Vec::new();
 
                 if !license_pack.license_files.is_empty() {
                     let (id, _) = get_span("license");
 
-                    match license_pack.get_expression(id, &strategy, threshold) {
+                    let license_refs = cfg.map_or(&[][..], |cfg| cfg.license_refs.as_slice());
+
+                    match license_pack.get_expression(id, &strategy, threshold, license_refs) {
                         Ok(GatheredExpr {
                             synthesized_toml,
                             failures,
@@ -751,6 +786,7 @@ impl Gatherer {
                                 },
                                 labels,
                                 notes,
+                                invalidated,
                             };
                         }
                         Err((new_toml, lic_file_labels)) => {
@@ -777,6 +813,11 @@ impl Gatherer {
                             }
                         }
                     }
+                } else {
+                    notes.push(
+                        "no files prefixed with LICENSE or COPYING were found in the crate's root directory"
+                            .to_owned(),
+                    );
                 }
 
                 // Just get a label for the crate name
@@ -793,7 +834,8 @@ impl Gatherer {
                     krate,
                     lic_info: LicenseInfo::Unlicensed,
                     labels,
-                    notes: Vec::new(),
+                    notes,
+                    invalidated,
                 }
             })
             .collect();
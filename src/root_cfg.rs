@@ -75,14 +75,35 @@ impl<'de> Deserialize<'de> for GraphConfig {
 #[derive(Default)]
 pub struct OutputConfig {
     pub feature_depth: Option<u32>,
+    /// Crates whose dependencies are collapsed into them in inclusion graphs
+    pub collapse: Vec<String>,
+    /// Only show the workspace members that depend on a crate in inclusion
+    /// graphs
+    pub members_only: bool,
+    /// Limits on the traversal done when building each inclusion graph
+    pub graph_budget: crate::diag::GraphBudget,
 }
 
 impl<'de> Deserialize<'de> for OutputConfig {
     fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
         let mut th = TableHelper::new(value)?;
         let feature_depth = th.optional("feature-depth");
+        let collapse = th.optional("collapse").unwrap_or_default();
+        let members_only = th.optional("members-only").unwrap_or_default();
+        let graph_budget = {
+            let default = crate::diag::GraphBudget::default();
+            crate::diag::GraphBudget {
+                max_nodes: th.optional("max-graph-nodes").unwrap_or(default.max_nodes),
+                max_depth: th.optional("max-graph-depth").unwrap_or(default.max_depth),
+            }
+        };
         th.finalize(None)?;
-        Ok(Self { feature_depth })
+        Ok(Self {
+            feature_depth,
+            collapse,
+            members_only,
+            graph_budget,
+        })
     }
 }
 
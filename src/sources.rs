@@ -14,7 +14,11 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
     use bitvec::prelude::*;
 
     // early out if everything is allowed
-    if ctx.cfg.unknown_registry == LintLevel::Allow && ctx.cfg.unknown_git == LintLevel::Allow {
+    let check_unknown =
+        ctx.cfg.unknown_registry != LintLevel::Allow || ctx.cfg.unknown_git != LintLevel::Allow;
+    let check_expected =
+        !ctx.cfg.expected_sources.is_empty() && ctx.cfg.unexpected_source != LintLevel::Allow;
+    if !check_unknown && !check_expected {
         return;
     }
 
@@ -38,11 +42,6 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
     });
 
     for (i, krate) in ctx.krates.krates().enumerate() {
-        let source = match &krate.source {
-            Some(source) => source,
-            None => continue,
-        };
-
         let mut pack = Pack::with_kid(Check::Sources, krate.id.clone());
 
         let mut sl = None;
@@ -52,6 +51,39 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
                 .with_message("source")
         };
 
+        // Ensure crates with a well known name come from the source they are
+        // expected to, rather than being shadowed by a crate from elsewhere
+        if check_expected {
+            if let Some(es) = ctx
+                .cfg
+                .expected_sources
+                .iter()
+                .find(|es| es.name.value == krate.name)
+            {
+                if !krate.matches_url(&es.source.value, true) {
+                    pack.push(diags::UnexpectedSource {
+                        krate,
+                        src_label: sl.get_or_insert_with(label),
+                        expected_cfg: CfgCoord {
+                            file: ctx.cfg.file_id,
+                            span: es.source.span,
+                        },
+                        lint_level: ctx.cfg.unexpected_source,
+                    });
+                }
+            }
+        }
+
+        let source = match &krate.source {
+            Some(source) if check_unknown => source,
+            _ => {
+                if !pack.is_empty() {
+                    sink.push(pack);
+                }
+                continue;
+            }
+        };
+
         // get allowed list of sources to check
         let (lint_level, type_name) = if source.is_registry() {
             (ctx.cfg.unknown_registry, "registry")
@@ -70,6 +102,9 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
 
             (ctx.cfg.unknown_git, "git")
         } else {
+            if !pack.is_empty() {
+                sink.push(pack);
+            }
             continue;
         };
 
@@ -86,6 +121,9 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
             // it's crates.io since that will be a vast majority of crates and
             // is the default, so we might not have a real source location anyways
             if krate.is_crates_io() {
+                if !pack.is_empty() {
+                    sink.push(pack);
+                }
                 continue;
             }
 
@@ -148,6 +186,11 @@ pub fn check(ctx: crate::CheckCtx<'_, ValidConfig>, sink: impl Into<ErrorSink>)
         sink.push(pack);
     }
 
+    // The allowed sources are only relevant if unknown sources are checked
+    if !check_unknown {
+        return;
+    }
+
     let mut pack = Pack::new(Check::Sources);
 
     for src in source_hits
@@ -75,6 +75,25 @@ impl fmt::Display for GitSpec {
     }
 }
 
+/// A crate name along with the only source it is expected to come from
+pub struct ExpectedSource {
+    /// The name of the crate
+    pub name: Spanned<String>,
+    /// The url of the source the crate is expected to come from
+    pub source: Spanned<String>,
+}
+
+impl<'de> Deserialize<'de> for ExpectedSource {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let name = th.required_s("crate")?;
+        let source = th.required_s("source")?;
+        th.finalize(None)?;
+
+        Ok(Self { name, source })
+    }
+}
+
 pub struct Config {
     /// How to handle registries that weren't listed
     pub unknown_registry: LintLevel,
@@ -93,6 +112,11 @@ pub struct Config {
     /// The minimum specification required for git sources. Defaults to allowing
     /// any.
     pub required_git_spec: Option<Spanned<GitSpec>>,
+    /// Crates that are expected to only come from a specific source, eg. to
+    /// detect a crate that shadows the name of a well known crate
+    pub expected_sources: Vec<ExpectedSource>,
+    /// How to handle crates that don't come from their expected source
+    pub unexpected_source: LintLevel,
 }
 
 impl<'de> Deserialize<'de> for Config {
@@ -107,6 +131,8 @@ impl<'de> Deserialize<'de> for Config {
         let allow_org = th.optional("allow-org").unwrap_or_default();
         let private = th.optional("private").unwrap_or_default();
         let required_git_spec = th.optional("required-git-spec");
+        let expected_sources = th.optional("expected-sources").unwrap_or_default();
+        let unexpected_source = th.optional("unexpected-source").unwrap_or(LintLevel::Deny);
 
         th.finalize(None)?;
 
@@ -118,6 +144,8 @@ impl<'de> Deserialize<'de> for Config {
             allow_org,
             private,
             required_git_spec,
+            expected_sources,
+            unexpected_source,
         })
     }
 }
@@ -132,6 +160,8 @@ impl Default for Config {
             allow_org: Orgs::default(),
             private: Vec::new(),
             required_git_spec: None,
+            expected_sources: Vec::new(),
+            unexpected_source: LintLevel::Deny,
         }
     }
 }
@@ -153,47 +183,18 @@ impl cfg::UnvalidatedConfig for Config {
             .chain(self.allow_git.into_iter().map(|u| (u, true, true)))
             .chain(self.private.into_iter().map(|u| (u, false, false)))
         {
-            let astr = aurl.as_ref();
-            let mut skip = 0;
-
-            if let Some(start_scheme) = astr.find("://") {
-                if let Some(i) = astr[..start_scheme].find('+') {
-                    ctx.push(
-                        Diagnostic::warning()
-                            .with_message("scheme modifiers are unnecessary")
-                            .with_labels(vec![Label::primary(
-                                ctx.cfg_id,
-                                aurl.span.start..aurl.span.start + start_scheme,
-                            )]),
-                    );
-
-                    skip = i + 1;
+            if let Some(mut url) = parse_url(&mut ctx, &aurl) {
+                if is_git {
+                    crate::normalize_git_url(&mut url);
                 }
-            }
 
-            match url::Url::parse(&astr[skip..]) {
-                Ok(mut url) => {
-                    if is_git {
-                        crate::normalize_git_url(&mut url);
-                    }
-
-                    allowed_sources.push(UrlSource {
-                        url: UrlSpan {
-                            value: url,
-                            span: aurl.span,
-                        },
-                        exact,
-                    });
-                }
-                Err(pe) => {
-                    ctx.push(
-                        Diagnostic::error()
-                            .with_message("failed to parse url")
-                            .with_labels(vec![
-                                Label::primary(ctx.cfg_id, aurl.span).with_message(pe.to_string())
-                            ]),
-                    );
-                }
+                allowed_sources.push(UrlSource {
+                    url: UrlSpan {
+                        value: url,
+                        span: aurl.span,
+                    },
+                    exact,
+                });
             }
         }
 
@@ -216,6 +217,23 @@ impl cfg::UnvalidatedConfig for Config {
             )
             .collect();
 
+        let expected_sources = self
+            .expected_sources
+            .into_iter()
+            .filter_map(|es| {
+                let mut url = parse_url(&mut ctx, &es.source)?;
+                crate::normalize_git_url(&mut url);
+
+                Some(ValidExpectedSource {
+                    name: es.name,
+                    source: UrlSpan {
+                        value: url,
+                        span: es.source.span,
+                    },
+                })
+            })
+            .collect();
+
         ValidConfig {
             file_id: ctx.cfg_id,
             unknown_registry: self.unknown_registry,
@@ -223,6 +241,47 @@ impl cfg::UnvalidatedConfig for Config {
             allowed_sources,
             allowed_orgs,
             required_git_spec: self.required_git_spec,
+            expected_sources,
+            unexpected_source: self.unexpected_source,
+        }
+    }
+}
+
+/// Parses a source url from the config, emitting a warning if it has an
+/// unnecessary scheme modifier, eg. `registry+`, or an error if it is invalid
+pub(crate) fn parse_url(
+    ctx: &mut ValidationContext<'_>,
+    aurl: &Spanned<String>,
+) -> Option<url::Url> {
+    let astr = aurl.as_ref();
+    let mut skip = 0;
+
+    if let Some(start_scheme) = astr.find("://") {
+        if let Some(i) = astr[..start_scheme].find('+') {
+            ctx.push(
+                Diagnostic::warning()
+                    .with_message("scheme modifiers are unnecessary")
+                    .with_labels(vec![Label::primary(
+                        ctx.cfg_id,
+                        aurl.span.start..aurl.span.start + start_scheme,
+                    )]),
+            );
+
+            skip = i + 1;
+        }
+    }
+
+    match url::Url::parse(&astr[skip..]) {
+        Ok(url) => Some(url),
+        Err(pe) => {
+            ctx.push(
+                Diagnostic::error()
+                    .with_message("failed to parse url")
+                    .with_labels(vec![
+                        Label::primary(ctx.cfg_id, aurl.span).with_message(pe.to_string())
+                    ]),
+            );
+            None
         }
     }
 }
@@ -235,6 +294,12 @@ pub struct UrlSource {
     pub exact: bool,
 }
 
+#[derive(PartialEq, Eq, Debug)]
+pub struct ValidExpectedSource {
+    pub name: Spanned<String>,
+    pub source: UrlSpan,
+}
+
 #[doc(hidden)]
 #[cfg_attr(test, derive(Debug))]
 pub struct ValidConfig {
@@ -245,6 +310,8 @@ pub struct ValidConfig {
     pub allowed_sources: Vec<UrlSource>,
     pub allowed_orgs: Vec<(OrgType, Spanned<String>)>,
     pub required_git_spec: Option<Spanned<GitSpec>>,
+    pub expected_sources: Vec<ValidExpectedSource>,
+    pub unexpected_source: LintLevel,
 }
 
 #[cfg(test)]
@@ -22,6 +22,7 @@ pub enum Code {
     SourceNotAllowed,
     UnmatchedSource,
     UnmatchedOrganization,
+    UnexpectedSource,
 }
 
 impl From<Code> for String {
@@ -150,3 +151,30 @@ impl From<UnmatchedAllowOrg> for Diag {
             .into()
     }
 }
+
+pub(crate) struct UnexpectedSource<'a> {
+    pub(crate) krate: &'a crate::Krate,
+    pub(crate) src_label: &'a Label,
+    pub(crate) expected_cfg: CfgCoord,
+    pub(crate) lint_level: LintLevel,
+}
+
+impl<'a> From<UnexpectedSource<'a>> for Diag {
+    fn from(us: UnexpectedSource<'a>) -> Self {
+        Diagnostic::new(us.lint_level.into())
+            .with_message(format!(
+                "crate '{}' is not from its expected source",
+                us.krate
+            ))
+            .with_code(Code::UnexpectedSource)
+            .with_labels(vec![
+                us.src_label.clone(),
+                us.expected_cfg.into_label().with_message("expected source"),
+            ])
+            .with_notes(vec![match &us.krate.source {
+                Some(source) => format!("actual source: {source}"),
+                None => "actual source: local path".to_owned(),
+            }])
+            .into()
+    }
+}
@@ -365,7 +365,16 @@ pub fn gather_bans(
     let cfg = cfg.into();
 
     gather_diagnostics::<crate::bans::cfg::Config, _, _>(&krates, name, cfg, |ctx, cs, tx, _| {
-        crate::bans::check(ctx, None, cs, tx);
+        crate::bans::check(
+            ctx,
+            None,
+            cs,
+            ErrorSink {
+                overrides: None,
+                waivers: diag::CrateWaivers::new(&krates).map(std::sync::Arc::new),
+                channel: tx,
+            },
+        );
     })
 }
 
@@ -386,6 +395,7 @@ pub fn gather_bans_with_overrides(
             cs,
             ErrorSink {
                 overrides: Some(std::sync::Arc::new(overrides)),
+                waivers: diag::CrateWaivers::new(&krates).map(std::sync::Arc::new),
                 channel: tx,
             },
         );
@@ -1,4 +1,4 @@
-use cargo_deny::{func_name, test_utils::*};
+use cargo_deny::{assert_field_eq, field_eq, func_name, test_utils::*};
 
 /// Covers issue <https://github.com/EmbarkStudios/cargo-deny/issues/184>
 #[test]
@@ -27,6 +27,67 @@ wrappers = ["safe-wrapper"]
     insta::assert_json_snapshot!(diags);
 }
 
+/// Validates the note emitted for a banned crate that is allowed by a wrapper
+/// names the wrapper, as well as the reason for the ban, and that it can be
+/// disabled
+#[test]
+fn allowed_by_wrapper_note() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("allow_wrappers/maincrate"),
+        r#"
+[[deny]]
+name = "dangerous-dep"
+reason = "we need to update 'safe-wrapper' to not use this"
+wrappers = ["safe-wrapper"]
+"#,
+    );
+
+    let note = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "allowed-by-wrapper"))
+        .expect("expected a note for the wrapper");
+
+    assert_field_eq!(note, "/fields/severity", "note");
+    assert_field_eq!(
+        note,
+        "/fields/message",
+        "banned crate 'dangerous-dep = 0.1.0' allowed by wrapper 'safe-wrapper = 0.1.0'"
+    );
+    assert_field_eq!(note, "/fields/labels/1/message", "reason");
+    assert_field_eq!(note, "/fields/labels/2/message", "allowed wrapper");
+    assert_field_eq!(note, "/fields/labels/2/span", "safe-wrapper");
+
+    // The note can be disabled, without affecting the ban itself
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("allow_wrappers/maincrate"),
+        r#"
+wrapper-notes = false
+deny = [{ name = "dangerous-dep", wrappers = ["safe-wrapper"] }]
+"#,
+    );
+
+    assert!(!diags.iter().any(|d| {
+        field_eq!(d, "/fields/code", "allowed-by-wrapper") || field_eq!(d, "/fields/code", "banned")
+    }));
+}
+
+/// Validates wrappers also apply to crates banned by a glob pattern
+#[test]
+fn allow_wrappers_with_globs() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("allow_wrappers/maincrate"),
+        "deny = [{ crate = 'dangerous-*', wrappers = ['safe-wrapper'] }]",
+    );
+
+    assert!(diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "allowed-by-wrapper")));
+    assert!(!diags.iter().any(|d| field_eq!(d, "/fields/code", "banned")));
+}
+
 /// Validates a wrapper that doesn't exist emits a warning
 #[test]
 fn warns_on_unused_wrappers() {
@@ -73,6 +134,179 @@ wrappers = ["other-crate"]
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures the reason for a skip entry is included in the diagnostic itself,
+/// not just as a label pointing at the config
+#[test]
+fn notes_skip_reason() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("cyclic_dependencies"),
+        "multiple-versions = 'deny'
+skip = [{ crate = 'ansi_term@0.11.0', reason = 'waiting on clap 3' }]",
+    );
+
+    let skipped = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "skipped"))
+        .expect("expected a skipped note");
+
+    assert_field_eq!(skipped, "/fields/notes/0", "reason: waiting on clap 3");
+    assert_field_eq!(skipped, "/fields/labels/1/message", "reason");
+}
+
+/// Ensures a skip entry restricted to a source only skips the crate when it
+/// comes from that source
+#[test]
+fn skips_by_source() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("sources"),
+        r#"
+skip = [
+    { crate = "anyhow", source = "https://github.com/dtolnay/anyhow" },
+    { crate = "krates", source = "https://gitlab.com/EmbarkStudios/krates" },
+]
+"#,
+    );
+
+    let messages = |code: &str| -> Vec<_> {
+        diags
+            .iter()
+            .filter(|d| field_eq!(d, "/fields/code", code))
+            .map(|d| d.pointer("/fields/message").unwrap().clone())
+            .collect()
+    };
+
+    assert_eq!(
+        messages("skipped"),
+        ["crate 'anyhow = 1.0.68' skipped when checking for duplicates"]
+    );
+    assert_eq!(
+        messages("unmatched-skip"),
+        ["skipped crate 'krates' was not encountered"]
+    );
+}
+
+/// Ensures a banned crate notes the dependency edges that, if removed, would
+/// remove it from the graph entirely, when `removal-notes` is enabled
+#[test]
+fn notes_dominating_edges() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("wildcards/maincrate"),
+        r#"
+removal-notes = true
+deny = ['ansi_term']
+"#,
+    );
+
+    let banned = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "banned"))
+        .expect("expected ansi_term to be banned");
+
+    assert_field_eq!(
+        banned,
+        "/fields/notes",
+        ["remove dependency 'wildcards-test-crate = 0.1.0' -> 'ansi_term = 0.12.1' to eliminate 'ansi_term = 0.12.1' entirely"]
+    );
+}
+
+/// Ensures deny entries can match crates by their categories and keywords,
+/// that crates without the metadata don't match, and that exempt crates are
+/// not banned
+#[test]
+fn matches_categories_and_keywords() {
+    let banned = |cfg: &str| -> Vec<_> {
+        gather_bans(func_name!(), KrateGather::new("categories"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "banned"))
+            .map(|d| d.pointer("/fields/message").unwrap().clone())
+            .collect()
+    };
+
+    let tagged = ["crate 'tagged = 0.1.0' is explicitly banned"];
+
+    assert_eq!(
+        banned("deny = [{ crate = '*', categories = ['cryptography'] }]"),
+        tagged
+    );
+    assert_eq!(
+        banned("deny = [{ crate = '*', keywords = ['crypto'] }]"),
+        tagged
+    );
+    assert!(banned("deny = [{ crate = '*', categories = ['parsing'] }]").is_empty());
+    assert!(
+        banned("deny = [{ crate = '*', categories = ['cryptography'], exempt = ['tagged'] }]")
+            .is_empty()
+    );
+}
+
+/// Ensures path dependencies are only matched by `deny`, `allow`, and `skip`
+/// entries if `exclude-workspace` is disabled
+#[test]
+fn excludes_workspace_crates() {
+    let codes = |cfg: &str| -> Vec<_> {
+        gather_bans(func_name!(), KrateGather::new("categories"), cfg)
+            .into_iter()
+            .filter_map(|d| {
+                let code = d.pointer("/fields/code")?.as_str()?;
+                (code == "banned" || code == "not-allowed").then(|| code.to_owned())
+            })
+            .collect()
+    };
+
+    assert_eq!(codes("deny = ['tagged']"), ["banned"]);
+    assert_eq!(codes("allow = ['categories', 'tagged']"), ["not-allowed"]);
+    assert!(codes("exclude-workspace = true\ndeny = ['tagged']").is_empty());
+    assert!(codes("exclude-workspace = true\nallow = ['tagged']").is_empty());
+
+    // Member overrides are subject to the same exclusion
+    assert_eq!(
+        codes("[overrides.categories]\ndeny = ['tagged']"),
+        ["banned"]
+    );
+    assert!(
+        codes("exclude-workspace = true\n[overrides.categories]\ndeny = ['tagged']").is_empty()
+    );
+}
+
+/// Ensures an allow entry restricted to a source allows every crate from that
+/// source, while crates from other sources still need to be allowed
+#[test]
+fn allows_entire_source() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("non-crates-io"),
+        r#"
+allow = [
+    "non-crates-io-registry",
+    { crate = "*", source = "https://dl.cloudsmith.io/public/embark/deny/cargo/index.git" },
+]
+"#,
+    );
+
+    let messages = |code: &str| -> Vec<_> {
+        diags
+            .iter()
+            .filter(|d| field_eq!(d, "/fields/code", code))
+            .map(|d| d.pointer("/fields/message").unwrap().clone())
+            .collect()
+    };
+
+    assert_eq!(
+        messages("allowed"),
+        [
+            "crate 'crate-one = 0.1.0' is explicitly allowed",
+            "crate 'non-crates-io-registry = 0.1.0' is explicitly allowed",
+        ]
+    );
+    assert_eq!(
+        messages("not-allowed"),
+        ["crate 'crate-two = 0.2.0' is not explicitly allowed"]
+    );
+}
+
 /// Validates that wildcard '*' dependencies can be detected and banned
 #[test]
 fn deny_wildcards() {
@@ -85,6 +319,70 @@ fn deny_wildcards() {
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures a crate can waive a diagnostic for itself via its
+/// `[package.metadata.cargo-deny]` table without affecting other crates
+#[test]
+fn honors_crate_metadata_waivers() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("metadata-waivers"),
+        "wildcards = 'deny'",
+    );
+
+    let wildcards: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "wildcard"))
+        .collect();
+
+    assert_eq!(wildcards.len(), 1);
+    assert_field_eq!(
+        wildcards[0],
+        "/fields/message",
+        "found 1 wildcard dependency for crate 'metadata-waivers'"
+    );
+}
+
+/// Ensures waivers are only read from first-party crates, so that a crate
+/// from a registry can't hide the findings about itself
+#[test]
+fn ignores_third_party_metadata_waivers() {
+    use cargo_deny::{bans, diag};
+
+    let mut cmd = krates::Cmd::new();
+    cmd.current_dir("./tests/test_data/metadata-waivers");
+    let mut md = krates::cm::MetadataCommand::from(cmd)
+        .exec()
+        .expect("failed to gather metadata");
+
+    for pkg in md.packages.iter_mut().filter(|pkg| pkg.name == "itoa") {
+        pkg.metadata = serde_json::json!({ "cargo-deny": { "waive": ["banned"] } });
+    }
+
+    let krates = krates::Builder::new()
+        .build_with_metadata(md, krates::NoneFilter)
+        .expect("failed to build crate graph");
+
+    let diags = gather_diagnostics::<bans::cfg::Config, _, _>(
+        &krates,
+        func_name!(),
+        "deny = ['itoa']".into(),
+        |ctx, cs, tx, _| {
+            bans::check(
+                ctx,
+                None,
+                cs,
+                diag::ErrorSink {
+                    overrides: None,
+                    waivers: diag::CrateWaivers::new(&krates).map(std::sync::Arc::new),
+                    channel: tx,
+                },
+            );
+        },
+    );
+
+    assert!(diags.iter().any(|d| field_eq!(d, "/fields/code", "banned")));
+}
+
 /// Ensures that wildcard dependencies are still banned when
 /// allow-wildcard-paths is set to true but the package is public.
 #[test]
@@ -168,6 +466,212 @@ skip = [
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures optional dependencies only contribute to duplicate detection when
+/// they are actually activated, as the crate graph only contains the edges
+/// of the resolved features
+#[test]
+fn ignores_inactive_optional_dependencies() {
+    let idna_dupes = |features: &'static [&'static str]| {
+        gather_bans(
+            func_name!(),
+            KrateGather {
+                name: "features",
+                features,
+                ..Default::default()
+            },
+            "multiple-versions = 'deny'",
+        )
+        .into_iter()
+        .filter(|d| {
+            field_eq!(d, "/fields/code", "duplicate")
+                && field_eq!(
+                    d,
+                    "/fields/message",
+                    "found 2 duplicate entries for crate 'idna'"
+                )
+        })
+        .count()
+    };
+
+    // `idna 0.3.0` is only pulled in via the optional `request` dependency,
+    // which is not activated by default, the `request?/json` feature in the
+    // default feature set is a weak dependency feature
+    assert_eq!(idna_dupes(&[]), 0);
+    assert_eq!(idna_dupes(&["request"]), 1);
+}
+
+/// Ensures crates that link a native library are reported, and pure Rust
+/// crates are not
+#[test]
+fn flags_native_links() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("build-bans"),
+        "links = 'deny'",
+    );
+
+    let ring = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "links-native-library"))
+        .expect("expected ring to be flagged");
+    assert_field_eq!(
+        ring,
+        "/fields/message",
+        "crate 'ring = 0.16.20' links the native library 'ring-asm'"
+    );
+    assert_field_eq!(ring, "/fields/severity", "error");
+    assert_field_eq!(ring, "/fields/labels/0/message", "links a native library");
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("categories"),
+        "links = 'deny'",
+    );
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "links-native-library")));
+}
+
+/// Ensures a crate redirected via `[patch]` can be treated as replacing the
+/// registry version it patches, rather than being a duplicate of it
+#[test]
+fn patches_replace_originals() {
+    let ansi_dupes = |cfg: &'static str| {
+        gather_bans(func_name!(), KrateGather::new("patched"), cfg)
+            .into_iter()
+            .filter(|d| {
+                field_eq!(d, "/fields/code", "duplicate")
+                    && field_eq!(
+                        d,
+                        "/fields/message",
+                        "found 2 duplicate entries for crate 'ansi_term'"
+                    )
+            })
+            .count()
+    };
+
+    assert_eq!(ansi_dupes("multiple-versions = 'deny'"), 1);
+    assert_eq!(
+        ansi_dupes("multiple-versions = 'deny'\n[patches]\nreplace-originals = true"),
+        0
+    );
+}
+
+/// Ensures patches can be required, and that unexpected patches are reported
+/// at the dependencies they apply to
+#[test]
+fn checks_patches() {
+    let patch_diags = |cfg: &'static str| -> Vec<_> {
+        gather_bans(func_name!(), KrateGather::new("patched"), cfg)
+            .into_iter()
+            .filter(|d| {
+                field_eq!(d, "/fields/code", "unexpected-patch")
+                    || field_eq!(d, "/fields/code", "patch-not-found")
+            })
+            .collect()
+    };
+
+    assert!(patch_diags("patches = { require = ['ansi_term'], unexpected = 'deny' }").is_empty());
+
+    let diags = patch_diags("patches = { unexpected = 'deny' }");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/code", "unexpected-patch");
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'ansi_term = 0.11.1' replaces a registry crate via [patch], but is not a required patch"
+    );
+    assert_field_eq!(diags[0], "/fields/labels/0/message", "patched dependency");
+
+    let diags = patch_diags("patches = { require = ['openssl'] }");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/code", "patch-not-found");
+    assert_field_eq!(diags[0], "/fields/labels/0/span", "openssl");
+}
+
+/// Ensures crates resolved through a `[replace]` entry are reported, along
+/// with the entry and manifest responsible
+#[test]
+fn lints_replaced_crates() {
+    let replaced = |cfg: &'static str| -> Vec<_> {
+        gather_bans(func_name!(), KrateGather::new("replaced"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "replaced"))
+            .collect()
+    };
+
+    assert!(replaced("").is_empty());
+
+    let diags = replaced("replace = 'deny'");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'ansi_term = 0.11.0' is resolved through a [replace] entry"
+    );
+    assert!(diags[0]
+        .pointer("/fields/notes/0")
+        .and_then(|note| note.as_str())
+        .unwrap()
+        .starts_with("replaced by 'ansi_term:0.11.0' in '"));
+}
+
+/// Ensures workspace members that declare the same dependency with different
+/// version requirements are reported
+#[test]
+fn detects_requirement_drift() {
+    let drift: Vec<_> = gather_bans(
+        func_name!(),
+        KrateGather::new("requirement-drift"),
+        "requirement-drift = 'warn'",
+    )
+    .into_iter()
+    .filter(|d| field_eq!(d, "/fields/code", "requirement-drift"))
+    .collect();
+
+    // 'member-a' declares 'cfg-if' with different requirements in its normal
+    // and dev dependencies, but no other member declares it, so it isn't drift
+    assert_eq!(drift.len(), 1);
+    assert_field_eq!(drift[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        drift[0],
+        "/fields/message",
+        "workspace members declare 'ansi_term' with 2 different version requirements"
+    );
+    assert_field_eq!(
+        drift[0],
+        "/fields/notes",
+        [
+            "'member-a' requires '^0.11'",
+            "'member-b' requires '=0.11.0'"
+        ]
+    );
+}
+
+/// Ensures workspace members that redeclare a dependency from
+/// `[workspace.dependencies]` rather than inheriting it are reported
+#[test]
+fn requires_workspace_inheritance() {
+    let diags: Vec<_> = gather_bans(
+        func_name!(),
+        KrateGather::new("workspace-inheritance"),
+        "workspace-inheritance = 'deny'",
+    )
+    .into_iter()
+    .filter(|d| field_eq!(d, "/fields/code", "not-inherited"))
+    .collect();
+
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "workspace member 'redeclares' declares 'ansi_term' itself instead of inheriting it from the workspace"
+    );
+}
+
 /// Ensures duplicate graphs match expectations
 #[test]
 fn duplicate_graphs() {
@@ -186,10 +690,13 @@ multiple-versions-include-dev = true
     gather_diagnostics::<bans::cfg::Config, _, _>(&krates, func_name!(), cfg, |ctx, cs, tx, _f| {
         bans::check(
             ctx,
-            Some(Box::new(move |dg| {
-                duped_graphs.lock().push(dg);
-                Ok(())
-            })),
+            Some(bans::GraphOutput {
+                output: Box::new(move |dg| {
+                    duped_graphs.lock().push(dg);
+                    Ok(())
+                }),
+                newest: None,
+            }),
             cs,
             tx,
         );
@@ -271,3 +778,1430 @@ deny = [
 
     insta::assert_json_snapshot!(diags);
 }
+
+/// Ensures crates that are the sole path to more crates than the limit are
+/// flagged, and crates under the limit are not
+#[test]
+fn limits_unique_dependencies() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("allow_wrappers/maincrate"),
+        "max-unique-dependencies = { limit = 0, level = 'deny' }",
+    );
+
+    let diags: Vec<_> = diags
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "too-many-unique-dependencies"))
+        .collect();
+
+    assert_eq!(diags.len(), 1);
+
+    let diag = &diags[0];
+    assert_field_eq!(diag, "/fields/severity", "error");
+    assert_field_eq!(diag, "/fields/graphs/0/Krate/name", "safe-wrapper");
+    assert_field_eq!(
+        diag,
+        "/fields/message",
+        "crate 'safe-wrapper = 0.1.0' pulls in 1 crate that is only reachable through it, exceeding the limit of 0"
+    );
+    assert_field_eq!(
+        diag,
+        "/fields/notes/0",
+        "'dangerous-dep = 0.1.0' accounts for 1"
+    );
+}
+
+/// Ensures the same version of a crate being pulled from multiple sources is
+/// flagged, but crates from a single source are not
+#[test]
+fn flags_multiple_sources() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("multiple-sources"),
+        "multiple-sources = 'deny'",
+    );
+
+    let diags: Vec<_> = diags
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "multiple-sources"))
+        .collect();
+
+    assert_eq!(diags.len(), 1);
+
+    let diag = &diags[0];
+    assert_field_eq!(diag, "/fields/severity", "error");
+    assert_field_eq!(
+        diag,
+        "/fields/message",
+        "crate 'ansi_term = 0.12.1' is present from 2 different sources"
+    );
+    assert_field_eq!(diag, "/fields/graphs/0/Krate/name", "ansi_term");
+    assert_field_eq!(diag, "/fields/graphs/1/Krate/name", "ansi_term");
+    assert_eq!(
+        diag.pointer("/fields/labels")
+            .and_then(|l| l.as_array())
+            .map(|l| l.len()),
+        Some(2)
+    );
+}
+
+/// Ensures building an inclusion graph for a crate that isn't in the crate
+/// graph reports which crate was missing
+#[test]
+fn graph_node_not_found() {
+    use cargo_deny::diag;
+
+    let krates = KrateGather::new("allow_wrappers/maincrate").gather();
+    let grapher = diag::InclusionGrapher::new(&krates);
+
+    let missing = diag::GraphNode {
+        kid: cargo_deny::Kid::default(),
+        feature: None,
+    };
+
+    match grapher.build_graph(&missing, 0) {
+        Err(diag::GraphError::NodeNotFound(kid)) => assert_eq!(kid, missing.kid),
+        Err(err) => panic!("unexpected error: {err}"),
+        Ok(_) => panic!("built a graph for a crate that is not in the crate graph"),
+    }
+}
+
+/// Ensures crates from a source in `skip-sources` don't count towards
+/// duplicates, while duplicates that come from other sources are still reported
+#[test]
+fn skips_duplicates_from_sources() {
+    let duplicates = |cfg: &str| -> Vec<_> {
+        gather_bans(func_name!(), KrateGather::new("skip-sources"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "duplicate"))
+            .map(|d| d.pointer("/fields/message").unwrap().clone())
+            .collect()
+    };
+
+    assert_eq!(
+        duplicates("multiple-versions = 'deny'"),
+        [
+            "found 2 duplicate entries for crate 'anyhow'",
+            "found 2 duplicate entries for crate 'cfg-if'",
+        ]
+    );
+
+    // The git version of anyhow no longer counts, but the crates.io versions
+    // of cfg-if are still duplicates
+    assert_eq!(
+        duplicates(
+            "multiple-versions = 'deny'\nskip-sources = ['https://github.com/dtolnay/anyhow']"
+        ),
+        ["found 2 duplicate entries for crate 'cfg-if'"]
+    );
+}
+
+/// Ensures a glob deny entry that matches more crates than `max-glob-matches`
+/// emits a single aggregated diagnostic rather than one per crate
+#[test]
+fn aggregates_glob_bans() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("duplicates"),
+        r#"
+multiple-versions = "allow"
+max-glob-matches = 3
+
+[[deny]]
+name = "unicode*"
+"#,
+    );
+
+    assert!(!diags.iter().any(|d| field_eq!(d, "/fields/code", "banned")));
+
+    let aggregated: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "banned-by-glob"))
+        .collect();
+    assert_eq!(aggregated.len(), 1);
+
+    let diag = aggregated[0];
+    assert_field_eq!(diag, "/fields/severity", "warning");
+    assert_field_eq!(
+        diag,
+        "/fields/message",
+        "5 crates are banned by 'unicode*', exceeding the limit of 3"
+    );
+    assert_field_eq!(
+        diag,
+        "/fields/notes/0",
+        "crate 'unicode-bidi = 0.3.8' is explicitly banned"
+    );
+    assert_field_eq!(
+        diag,
+        "/fields/notes/4",
+        "crate 'unicode_categories = 0.1.1' is explicitly banned"
+    );
+}
+
+/// Ensures required crates that aren't in the graph are reported
+#[test]
+fn requires_crates() {
+    let diags: Vec<_> = gather_bans(
+        func_name!(),
+        KrateGather::new("categories"),
+        "require = ['tagged', 'untagged@0.2.0', 'patched-fork']",
+    )
+    .into_iter()
+    .filter(|d| field_eq!(d, "/fields/code", "required-not-found"))
+    .collect();
+
+    assert_eq!(diags.len(), 2);
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "required crate 'untagged = =0.2.0' was not found in the graph"
+    );
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(diags[1], "/fields/labels/0/span", "patched-fork");
+}
+
+/// Ensures pre-release versions can be banned, with exceptions
+#[test]
+fn bans_prereleases() {
+    let prereleases = |cfg: &str| -> Vec<_> {
+        gather_bans(func_name!(), KrateGather::new("prerelease"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "prerelease"))
+            .collect()
+    };
+
+    assert!(prereleases("").is_empty());
+
+    let diags = prereleases("prerelease = 'deny'\nprerelease-exceptions = ['beta']");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'alpha = 0.1.0-alpha.1' is a pre-release version, which are not allowed"
+    );
+    assert_field_eq!(diags[0], "/fields/labels/0/message", "pre-release version");
+
+    let diags = prereleases("prerelease = 'warn'\nprerelease-exceptions = ['beta']");
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "warning");
+}
+
+/// Ensures the total number of crates is checked against `max-total-crates`,
+/// and that the crates contributing the most crates that are only reachable
+/// through them are noted
+#[test]
+fn limits_total_crates() {
+    let over = |cfg: &str| {
+        gather_bans(
+            func_name!(),
+            KrateGather::new("allow_wrappers/maincrate"),
+            cfg,
+        )
+        .into_iter()
+        .find(|d| field_eq!(d, "/fields/code", "too-many-crates"))
+    };
+
+    let diag = over("max-total-crates = { limit = 2, level = 'deny' }")
+        .expect("expected the crate limit to be exceeded");
+    assert_field_eq!(diag, "/fields/severity", "error");
+    assert_field_eq!(
+        diag,
+        "/fields/message",
+        "found 3 crates in the graph, exceeding the limit of 2"
+    );
+    assert_field_eq!(diag, "/fields/labels/0/message", "limit");
+    assert_field_eq!(
+        diag,
+        "/fields/notes/0",
+        "'safe-wrapper = 0.1.0' pulls in 1 crate that is only reachable through it"
+    );
+    assert!(diag.pointer("/fields/notes/1").is_none());
+
+    assert!(over("max-total-crates = 3").is_none());
+}
+
+/// Ensures duplicates whose versions are all semver compatible can be ignored,
+/// while incompatible duplicates are still reported
+#[test]
+fn ignores_semver_compatible_duplicates() {
+    let has_dupe = |name: &'static str| {
+        let diags = gather_bans(
+            func_name!(),
+            KrateGather::new(name),
+            "multiple-versions = 'deny'
+multiple-versions-policy = 'semver-incompatible'",
+        );
+
+        diags
+            .iter()
+            .any(|d| field_eq!(d, "/fields/code", "duplicate"))
+    };
+
+    // ansi_term 0.11.0 and 0.11.1
+    assert!(!has_dupe("patched"));
+    // ansi_term 0.11.0 and 0.12.1
+    assert!(has_dupe("cyclic_dependencies"));
+}
+
+/// Validates duplicates only fail the check once they exceed the budget
+#[test]
+fn enforces_duplicate_budget() {
+    let budget = |limit: usize| {
+        gather_bans(
+            func_name!(),
+            KrateGather::new("cyclic_dependencies"),
+            format!("multiple-versions = 'deny'\nmax-duplicates = {{ limit = {limit}, level = 'deny' }}"),
+        )
+    };
+
+    let within = budget(1);
+    let dupe = within
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "duplicate"))
+        .expect("expected a duplicate diagnostic");
+    assert_field_eq!(dupe, "/fields/severity", "note");
+
+    let summary = within
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "duplicate-budget"))
+        .expect("expected a budget summary");
+    assert_field_eq!(summary, "/fields/severity", "note");
+    assert_field_eq!(
+        summary,
+        "/fields/message",
+        "found 1 crate with multiple versions, within the budget of 1"
+    );
+
+    let exceeded = budget(0);
+    let summary = exceeded
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "duplicate-budget"))
+        .expect("expected a budget summary");
+    assert_field_eq!(summary, "/fields/severity", "error");
+    assert_field_eq!(
+        summary,
+        "/fields/message",
+        "found 1 crate with multiple versions, exceeding the budget of 0"
+    );
+}
+
+/// Ensures the inclusion graphs for every version of each duplicate can be
+/// retrieved in one go, with the configured paths highlighted
+#[test]
+fn enumerates_duplicate_graphs() {
+    use cargo_deny::{bans, diag};
+
+    let krates = KrateGather::new("cyclic_dependencies").gather();
+
+    let dupes = bans::duplicate_graphs(&krates, bans::cfg::GraphHighlight::LowestVersion).unwrap();
+    assert_eq!(dupes.len(), 1);
+
+    let (name, versions) = &dupes[0];
+    assert_eq!(name, "ansi_term");
+    assert_eq!(
+        versions
+            .iter()
+            .map(|(v, _)| v.to_string())
+            .collect::<Vec<_>>(),
+        ["0.11.0", "0.12.1"]
+    );
+
+    let (_, lowest) = &versions[0];
+    assert!(lowest.is_highlighted());
+    assert_eq!(
+        diag::write_graph_as_text(lowest).unwrap(),
+        "ansi_term v0.11.0\n└── root v0.1.0\n    └── (dev) leaf v0.1.0\n        └── root v0.1.0 (*)\n"
+    );
+
+    let (_, highest) = &versions[1];
+    assert!(!highest.is_highlighted());
+    assert_eq!(highest.parents().len(), 1);
+}
+
+/// Ensures crates further than `max-depth` edges from the workspace are
+/// reported with the path to them
+#[test]
+fn limits_dependency_depth() {
+    let too_deep = |limit: usize| -> Vec<_> {
+        gather_bans(
+            func_name!(),
+            KrateGather::new("allow_wrappers/maincrate"),
+            format!("max-depth = {{ limit = {limit}, level = 'deny' }}"),
+        )
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "dependency-too-deep"))
+        .collect()
+    };
+
+    assert!(too_deep(2).is_empty());
+
+    // Only the crate that is wrapped is too deep, the wrapper itself is a
+    // direct dependency of the workspace member
+    let diags = too_deep(1);
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'dangerous-dep = 0.1.0' is 2 edges from the nearest workspace member, exceeding the limit of 1"
+    );
+    assert_field_eq!(
+        diags[0],
+        "/fields/notes/0",
+        "shortest path: 'allow-wrappers-crate = 0.1.0' -> 'safe-wrapper = 0.1.0' -> 'dangerous-dep = 0.1.0'"
+    );
+
+    // Crates beneath one that is already too deep aren't reported separately
+    let diags = too_deep(0);
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'safe-wrapper = 0.1.0' is 1 edge from the nearest workspace member, exceeding the limit of 0"
+    );
+}
+
+/// Ensures the highest version of a duplicate can be highlighted instead
+#[test]
+fn highlights_highest_version() {
+    use cargo_deny::bans;
+
+    let krates = KrateGather::new("cyclic_dependencies").gather();
+
+    let dupes = bans::duplicate_graphs(&krates, bans::cfg::GraphHighlight::HighestVersion).unwrap();
+    let (_, versions) = &dupes[0];
+
+    assert!(!versions[0].1.is_highlighted());
+    assert_eq!(versions[1].0.to_string(), "0.12.1");
+    assert!(versions[1].1.is_highlighted());
+}
+
+/// Ensures deny entries restricted to particular kinds of dependency only ban
+/// crates reached as one of those kinds
+#[test]
+fn bans_by_dependency_kind() {
+    let banned = |cfg: &str| -> Vec<_> {
+        gather_bans(func_name!(), KrateGather::new("cyclic_dependencies"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "banned"))
+            .map(|d| d.pointer("/fields/message").unwrap().clone())
+            .collect()
+    };
+
+    assert_eq!(
+        banned("deny = [{ crate = 'ansi_term', kinds = ['dev'] }]"),
+        ["crate 'ansi_term = 0.12.1' is explicitly banned"]
+    );
+    assert_eq!(
+        banned("deny = [{ crate = 'ansi_term', kinds = ['normal'] }]"),
+        ["crate 'ansi_term = 0.11.0' is explicitly banned"]
+    );
+    assert!(banned("deny = [{ crate = 'ansi_term', kinds = ['build'] }]").is_empty());
+}
+
+/// Ensures deny entries restricted by default features only ban crates whose
+/// default features match
+#[test]
+fn bans_by_default_features() {
+    let cfg = "deny = [{ crate = 'features-galore', default-features = true }]";
+    let banned = |kg: KrateGather<'_>| {
+        gather_bans(func_name!(), kg, cfg)
+            .into_iter()
+            .any(|d| field_eq!(d, "/fields/code", "banned"))
+    };
+
+    assert!(banned(KrateGather::new("features-galore")));
+    assert!(!banned(KrateGather {
+        name: "features-galore",
+        no_default_features: true,
+        ..Default::default()
+    }));
+}
+
+/// Ensures deny entries restricted to targets only ban crates that would be
+/// built for one of them
+#[test]
+fn bans_by_target() {
+    let banned = |cfg: &str| {
+        gather_bans(func_name!(), KrateGather::new("features-galore"), cfg)
+            .into_iter()
+            .any(|d| field_eq!(d, "/fields/code", "banned"))
+    };
+
+    assert!(banned(
+        "deny = [{ crate = 'windows-sys', targets = ['x86_64-pc-windows-msvc'] }]"
+    ));
+    assert!(banned(
+        "deny = [{ crate = 'windows-sys', targets = ['cfg(windows)'] }]"
+    ));
+    assert!(!banned(
+        "deny = [{ crate = 'windows-sys', targets = ['x86_64-unknown-linux-gnu', 'cfg(target_os = \"macos\")'] }]"
+    ));
+}
+
+/// Ensures that in strict mode every crate must be allowed with an exact
+/// version, and that the crates that aren't are reported together
+#[test]
+fn requires_exact_allow() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("categories"),
+        "allow-exact = true
+allow = ['categories@0.1.0', 'tagged@0.1.0', 'untagged']",
+    );
+
+    let unapproved: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "unapproved"))
+        .collect();
+
+    assert_eq!(unapproved.len(), 1);
+    assert_field_eq!(unapproved[0], "/fields/severity", "error");
+    assert_field_eq!(
+        unapproved[0],
+        "/fields/message",
+        "1 crate is not allowed with an exact version"
+    );
+    assert_field_eq!(
+        unapproved[0],
+        "/fields/notes/0",
+        "add 'untagged@0.1.0' to the allow list"
+    );
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "not-allowed")));
+}
+
+/// Ensures downloaded crates larger than the limit are reported, unless they
+/// are excepted, while path crates are never sized
+#[test]
+fn limits_crate_size() {
+    let too_large = |cfg: &str| -> Vec<String> {
+        gather_bans(func_name!(), KrateGather::new("multiple-sources"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "crate-too-large"))
+            .filter_map(|d| {
+                d.pointer("/fields/graphs/0/Krate/name")
+                    .and_then(|name| name.as_str())
+                    .map(String::from)
+            })
+            .collect()
+    };
+
+    // Only the crates.io copy of ansi_term is sized, not the local fork
+    let flagged = too_large("max-crate-size = { limit = '1B', level = 'deny' }");
+    assert_eq!(
+        flagged.iter().filter(|name| *name == "ansi_term").count(),
+        1
+    );
+    assert!(!flagged.iter().any(|name| name == "multiple-sources"));
+
+    assert!(!too_large(
+        "max-crate-size = '1B'
+crate-size-exceptions = ['ansi_term']"
+    )
+    .iter()
+    .any(|name| name == "ansi_term"));
+    assert!(too_large("max-crate-size = '1GB'").is_empty());
+}
+
+/// Ensures crates with more direct dependencies than the limit are reported
+#[test]
+fn limits_direct_dependencies() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("categories"),
+        "max-direct-deps = { limit = 1, level = 'deny' }",
+    );
+
+    let fanout: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "too-many-direct-dependencies"))
+        .collect();
+
+    assert_eq!(fanout.len(), 1);
+    assert_field_eq!(fanout[0], "/fields/severity", "error");
+    assert_field_eq!(
+        fanout[0],
+        "/fields/message",
+        "crate 'categories = 0.1.0' has 2 direct dependencies, exceeding the limit of 1"
+    );
+}
+
+/// Ensures crates that wrap the same native library are detected, even if
+/// only one of them declares it via `links`
+#[test]
+fn detects_duplicate_links() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("native-links"),
+        "duplicate-links = 'deny'",
+    );
+
+    let dupes: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "duplicate-links"))
+        .collect();
+
+    assert_eq!(dupes.len(), 1);
+    assert_field_eq!(dupes[0], "/fields/severity", "error");
+    assert_field_eq!(
+        dupes[0],
+        "/fields/message",
+        "native library 'foo' is wrapped by 2 crates"
+    );
+    assert_field_eq!(
+        dupes[0],
+        "/fields/notes/0",
+        "'foo-sys = 0.1.0' is a -sys crate"
+    );
+    assert_field_eq!(
+        dupes[0],
+        "/fields/notes/1",
+        "'libfoo-sys = 0.1.0' links 'foo'"
+    );
+}
+
+/// Ensures the usage of each `skip-tree` entry can be reported, so that overly
+/// broad entries can be narrowed
+#[test]
+fn reports_skip_tree_usage() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("cyclic_dependencies"),
+        "multiple-versions = 'deny'
+skip-tree-usage = 'warn'
+skip-tree = [{ crate = 'root', depth = 2 }]",
+    );
+
+    let usage = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "skip-tree-usage"))
+        .expect("expected the skip-tree usage to be reported");
+
+    assert_field_eq!(usage, "/fields/severity", "warning");
+    assert!(usage
+        .pointer("/fields/message")
+        .and_then(|m| m.as_str())
+        .unwrap()
+        .starts_with("skip tree root 'root' suppressed "));
+    assert_field_eq!(usage, "/fields/notes/0", "configured depth: 2");
+    assert_field_eq!(usage, "/fields/notes/1", "deepest suppressed crate: 2");
+}
+
+/// Ensures `skip` and `skip-tree` entries that don't match any crate are
+/// reported, so they can be cleaned up
+#[test]
+fn warns_on_unmatched_skips() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("cyclic_dependencies"),
+        "skip = ['not-a-crate']
+skip-tree = ['also-not-a-crate']",
+    );
+
+    let skip = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "unmatched-skip"))
+        .expect("expected a warning for the unmatched skip");
+    assert_field_eq!(skip, "/fields/severity", "warning");
+    assert_field_eq!(skip, "/fields/labels/0/span", "not-a-crate");
+
+    let root = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "unmatched-skip-root"))
+        .expect("expected a warning for the unmatched skip-tree");
+    assert_field_eq!(root, "/fields/severity", "warning");
+}
+
+/// Ensures skip entries can use glob patterns to skip a family of crates
+#[test]
+fn skips_globs() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("cyclic_dependencies"),
+        "multiple-versions = 'deny'
+skip = ['ansi_*']",
+    );
+
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "duplicate")
+            || field_eq!(d, "/fields/code", "unmatched-skip")));
+    assert_eq!(
+        diags
+            .iter()
+            .filter(|d| field_eq!(d, "/fields/code", "skipped"))
+            .count(),
+        2
+    );
+}
+
+/// Ensures a skip entry can waive specific diagnostic codes for a crate while
+/// the crate still triggers every other diagnostic
+#[test]
+fn skip_waives_codes() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("cyclic_dependencies"),
+        r#"
+multiple-versions = 'deny'
+deny = ['ansi_term']
+skip = [{ crate = 'ansi_term', waive-codes = ['duplicate'] }]
+"#,
+    );
+
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "duplicate")));
+    assert_eq!(
+        diags
+            .iter()
+            .filter(|d| field_eq!(d, "/fields/code", "banned"))
+            .count(),
+        2
+    );
+}
+
+/// Ensures the remediation script for banned crates names each banned crate
+/// and the workspace member responsible for it
+#[test]
+fn writes_remediation_script() {
+    use cargo_deny::bans;
+
+    let krates = KrateGather::new("allow_wrappers/maincrate").gather();
+    let cfg = ConfigData::<bans::cfg::Config>::load_str(
+        "deny.toml",
+        "deny = [{ crate = 'dangerous-dep', reason = 'it is dangerous' }]",
+    )
+    .validate(|c| c);
+
+    let banned = bans::banned_krates(&krates, cfg);
+    assert_eq!(banned.len(), 1);
+    assert_eq!(banned[0].krate.name, "dangerous-dep");
+
+    let script = bans::remediation_script(&krates, &banned);
+    assert!(script.contains("# crate 'dangerous-dep = 0.1.0' is banned"));
+    assert!(script.contains("# reason: it is dangerous"));
+    assert!(script.contains(
+        "# it is a transitive dependency of workspace member 'allow-wrappers-crate', via 'safe-wrapper = 0.1.0'"
+    ));
+    assert!(script.contains("cargo tree -p allow-wrappers-crate -i dangerous-dep@0.1.0"));
+
+    // Crates only used by their allowed wrappers are not banned
+    let cfg = ConfigData::<bans::cfg::Config>::load_str(
+        "deny.toml",
+        "deny = [{ crate = 'dangerous-dep', wrappers = ['safe-wrapper'] }]",
+    )
+    .validate(|c| c);
+    assert!(bans::banned_krates(&krates, cfg).is_empty());
+}
+
+/// Ensures the banned crates are attributed to the workspace member that pulls
+/// them in, with a separate section for each member
+#[test]
+fn reports_findings_per_member() {
+    use cargo_deny::bans;
+
+    let krates = KrateGather::new("member-report/workspace").gather();
+    let cfg =
+        ConfigData::<bans::cfg::Config>::load_str("deny.toml", "deny = ['banned-a', 'banned-b']")
+            .validate(|c| c);
+
+    let findings = bans::member_findings(&krates, cfg);
+    assert_eq!(findings.len(), 2);
+    for mf in &findings {
+        assert_eq!(mf.banned.len(), 1);
+        assert_eq!(
+            mf.banned[0].name,
+            mf.member.name.replace("member", "banned")
+        );
+        assert!(mf.duplicates.is_empty());
+    }
+
+    let report = bans::member_report(&krates, &findings).unwrap();
+    assert_eq!(
+        report,
+        "# member-a v0.1.0
+
+## banned crates (1)
+
+banned-a v0.1.0
+└── member-a v0.1.0
+
+# member-b v0.1.0
+
+## banned crates (1)
+
+banned-b v0.1.0
+└── member-b v0.1.0
+"
+    );
+}
+
+/// Ensures the duplicates attributed to each member are the ones reported by
+/// the check, so crates skipped by `skip-tree` are not included
+#[test]
+fn attributes_duplicates_to_members() {
+    use cargo_deny::bans;
+
+    let krates = KrateGather::new("rooted/workspace").gather();
+    let duplicates = |cfg: &str| -> Vec<(String, usize)> {
+        let cfg = ConfigData::<bans::cfg::Config>::load_str("deny.toml", cfg).validate(|c| c);
+
+        bans::member_findings(&krates, cfg)
+            .into_iter()
+            .map(|mf| (mf.member.name.clone(), mf.duplicates.len()))
+            .collect()
+    };
+
+    assert_eq!(
+        duplicates(""),
+        [("app".to_owned(), 1), ("experimental".to_owned(), 2)]
+    );
+    assert_eq!(
+        duplicates("skip-tree = ['dup@0.2.0']"),
+        [("app".to_owned(), 0), ("experimental".to_owned(), 0)]
+    );
+}
+
+/// Ensures the dependencies of a collapsed crate are shown as the collapsed
+/// crate in inclusion graphs, but are still checked for duplicates
+#[test]
+fn collapses_virtual_crates() {
+    use cargo_deny::diag;
+
+    let krates = KrateGather::new("duplicates").gather();
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("duplicates"),
+        "multiple-versions = 'deny'",
+    );
+    assert!(diags.iter().any(|d| {
+        field_eq!(d, "/fields/code", "duplicate")
+            && field_eq!(
+                d,
+                "/fields/message",
+                "found 2 duplicate entries for crate 'block-buffer'"
+            )
+    }));
+
+    let block_buffer = krates
+        .krates()
+        .find(|k| k.name == "block-buffer" && k.version == semver::Version::new(0, 10, 2))
+        .unwrap();
+
+    let grapher = diag::InclusionGrapher::new(&krates).collapse(["sqlx"]);
+    let graph = grapher
+        .build_graph(
+            &diag::GraphNode {
+                kid: block_buffer.id.clone(),
+                feature: None,
+            },
+            0,
+        )
+        .unwrap();
+    let graph = diag::write_graph_as_text(&graph).unwrap();
+
+    let mut lines = graph.lines();
+    assert_eq!(lines.next(), Some("block-buffer v0.10.2"));
+    assert!(
+        lines
+            .next()
+            .map_or(false, |l| l.starts_with("└── sqlx v0.5.13 [+")),
+        "{graph}"
+    );
+    assert!(!graph.contains("sqlx-core"), "{graph}");
+    assert!(graph.contains("duplicates v0.1.0"), "{graph}");
+}
+
+/// Ensures `cargo update` commands are only suggested for duplicates when
+/// every dependent would accept the newer version
+#[test]
+fn suggests_updates_only_for_compatible_duplicates() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("duplicates"),
+        "multiple-versions = 'deny'",
+    );
+
+    let block_buffer = diags
+        .iter()
+        .find(|d| {
+            field_eq!(
+                d,
+                "/fields/message",
+                "found 2 duplicate entries for crate 'block-buffer'"
+            )
+        })
+        .expect("failed to find duplicate");
+
+    // 0.9 and 0.10 are not semver compatible, and no dependent accepts both
+    assert!(block_buffer["fields"]["notes"]
+        .as_array()
+        .map_or(true, |notes| notes.is_empty()));
+}
+
+/// Ensures the multiple versions lint level can be overridden for specific
+/// crates
+#[test]
+fn uses_per_crate_multiple_versions_levels() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("duplicates"),
+        "multiple-versions = 'allow'\nmultiple-versions-levels = [{ crate = 'block-buffer', level = 'deny' }]",
+    );
+
+    let dupes: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "duplicate"))
+        .collect();
+
+    assert_eq!(dupes.len(), 1);
+    assert_field_eq!(
+        dupes[0],
+        "/fields/message",
+        "found 2 duplicate entries for crate 'block-buffer'"
+    );
+    assert_field_eq!(dupes[0], "/fields/severity", "error");
+}
+
+/// Ensures members only inclusion graphs omit every crate between the root and
+/// the workspace members that depend on it
+#[test]
+fn shows_only_workspace_members() {
+    use cargo_deny::diag;
+
+    let krates = KrateGather::new("duplicates").gather();
+
+    let block_buffer = krates
+        .krates()
+        .find(|k| k.name == "block-buffer" && k.version == semver::Version::new(0, 10, 2))
+        .unwrap();
+
+    let grapher = diag::InclusionGrapher::new(&krates).members_only(true);
+    let graph = grapher
+        .build_graph(
+            &diag::GraphNode {
+                kid: block_buffer.id.clone(),
+                feature: None,
+            },
+            0,
+        )
+        .unwrap();
+    let graph = diag::write_graph_as_text(&graph).unwrap();
+
+    let mut lines = graph.lines();
+    assert!(
+        lines
+            .next()
+            .map_or(false, |l| l.starts_with("block-buffer v0.10.2 [+")),
+        "{graph}"
+    );
+    assert_eq!(lines.next(), Some("└── duplicates v0.1.0"), "{graph}");
+    assert_eq!(lines.next(), None, "{graph}");
+}
+
+/// Ensures duplicate versions with a newer release available in the index are
+/// annotated in the graph, and the ones already up to date are left as is
+#[test]
+fn annotates_newest_versions() {
+    use cargo_deny::bans;
+
+    let krates = KrateGather::new("duplicates").gather();
+    let cfg = "multiple-versions = 'deny'".into();
+
+    let mut newest = bans::NewestVersions::default();
+    for krate in krates.krates().filter(|k| k.name == "block-buffer") {
+        newest.insert(krate, "0.10.2".parse().unwrap());
+    }
+
+    let dup_graphs = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+
+    let duped_graphs = dup_graphs.clone();
+    gather_diagnostics::<bans::cfg::Config, _, _>(&krates, func_name!(), cfg, |ctx, cs, tx, _f| {
+        bans::check(
+            ctx,
+            Some(bans::GraphOutput {
+                output: Box::new(move |dg| {
+                    duped_graphs.lock().push(dg);
+                    Ok(())
+                }),
+                newest: Some(newest),
+            }),
+            cs,
+            tx,
+        );
+    });
+
+    let graphs = dup_graphs.lock();
+    let block_buffer = graphs
+        .iter()
+        .find(|dg| dg.duplicate == "block-buffer")
+        .expect("expected a graph for block-buffer");
+
+    assert!(block_buffer
+        .graph
+        .contains(r#"[label="0.7.3 (newest 0.10.2)""#));
+    assert!(block_buffer.graph.contains(r#"[label="0.10.2""#));
+}
+
+/// Ensures that duplicates are only detected in the crates reachable from the
+/// configured roots, and that roots that aren't workspace members are reported
+#[test]
+fn restricts_duplicates_to_roots() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("rooted/workspace"),
+        "multiple-versions = 'deny'",
+    );
+
+    assert!(diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "duplicate")));
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("rooted/workspace"),
+        "multiple-versions = 'deny'\nroots = ['app', 'nope']",
+    );
+
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "duplicate")));
+
+    let unmatched: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "unmatched-root"))
+        .collect();
+
+    assert_eq!(unmatched.len(), 1);
+    assert_field_eq!(unmatched[0], "/fields/severity", "warning");
+}
+
+/// Ensures skips scoped to an ancestor only apply when the crate can't be
+/// reached except through the ancestor
+#[test]
+fn skips_only_under_ancestor() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("rooted/workspace"),
+        "multiple-versions = 'deny'\nskip = [{ crate = 'dup@0.1.0', under = 'app' }]",
+    );
+
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "duplicate")));
+    assert!(diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "skipped")));
+
+    // `experimental` depends on `app`, but `app` also pulls in the crate
+    // by itself as a workspace member
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("rooted/workspace"),
+        "multiple-versions = 'deny'\nskip = [{ crate = 'dup@0.1.0', under = 'experimental' }]",
+    );
+
+    assert!(diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "duplicate")));
+    assert!(diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "unmatched-skip")));
+}
+
+/// Ensures crates that require a newer compiler than the configured toolchain
+/// are reported at the configured level
+#[test]
+fn bans_newer_rust_versions() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("rust-version"),
+        "min-rust-version = '1.70'",
+    );
+
+    let too_new: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "rust-version-too-new"))
+        .collect();
+
+    assert_eq!(too_new.len(), 1);
+    assert_field_eq!(too_new[0], "/fields/severity", "error");
+    assert_field_eq!(
+        too_new[0],
+        "/fields/message",
+        "crate 'new-toolchain = 0.1.0' requires rust 1.80.1, which is newer than 1.70.0"
+    );
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("rust-version"),
+        "min-rust-version = '1.70'\nrust-version-too-new = 'warn'",
+    );
+
+    let too_new = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "rust-version-too-new"))
+        .expect("expected new-toolchain to be reported");
+    assert_field_eq!(too_new, "/fields/severity", "warning");
+}
+
+/// Ensures skip entries are generated for every version of a duplicate other
+/// than the highest, and appended to the config whatever form `skip` takes
+#[test]
+fn appends_skips_for_duplicates() {
+    use cargo_deny::bans;
+
+    let krates = KrateGather::new("rooted/workspace").gather();
+    let cfg = ConfigData::<bans::cfg::Config>::load_str("deny.toml", "").validate(|c| c);
+
+    let duplicates = bans::unskipped_duplicates(&krates, cfg);
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].name, "dup");
+    assert_eq!(duplicates[0].version, semver::Version::new(0, 1, 0));
+
+    assert_eq!(
+        bans::append_skips("[bans]\nskip = [\n    \"other@1.0.0\"\n]\n", &duplicates).unwrap(),
+        "[bans]\nskip = [\n    \"other@1.0.0\",\n    { crate = \"dup@0.1.0\", reason = \"duplicate that existed when the skip was generated\" },\n]\n"
+    );
+    assert_eq!(
+        bans::append_skips("[bans]\nmultiple-versions = \"deny\"", &duplicates).unwrap(),
+        "[bans]\nmultiple-versions = \"deny\"\n\n[[bans.skip]]\ncrate = \"dup@0.1.0\"\nreason = \"duplicate that existed when the skip was generated\"\n"
+    );
+
+    // Crates that are already skipped don't need another entry
+    let cfg = ConfigData::<bans::cfg::Config>::load_str("deny.toml", "skip = ['dup@0.1.0']")
+        .validate(|c| c);
+    assert!(bans::unskipped_duplicates(&krates, cfg).is_empty());
+
+    // Nor do crates that the check doesn't consider duplicates, eg. as they
+    // aren't reachable from the configured roots
+    let cfg =
+        ConfigData::<bans::cfg::Config>::load_str("deny.toml", "roots = ['app']").validate(|c| c);
+    assert!(bans::unskipped_duplicates(&krates, cfg).is_empty());
+}
+
+/// Ensures member overrides only apply to the crates reachable from that
+/// member, and only relax the config if every member a crate is reachable
+/// from relaxes it
+#[test]
+fn applies_member_overrides() {
+    let banned = |cfg: &str| -> Vec<String> {
+        gather_bans(func_name!(), KrateGather::new("rooted/workspace"), cfg)
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "banned"))
+            .map(|d| d.pointer("/fields/message").unwrap().to_string())
+            .collect()
+    };
+
+    // The older version is also reachable from `app`, which doesn't exempt it
+    assert_eq!(
+        banned("deny = ['dup']\n[overrides.experimental]\nexempt = ['dup']"),
+        ["\"crate 'dup = 0.1.0' is explicitly banned\""]
+    );
+    // The newer version is only reachable from `experimental`
+    assert_eq!(
+        banned("[overrides.app]\ndeny = ['dup']"),
+        ["\"crate 'dup = 0.1.0' is explicitly banned\""]
+    );
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("rooted/workspace"),
+        "multiple-versions = 'deny'\n[overrides.experimental]\nskip = ['dup@0.2.0']\n[overrides.nope]",
+    );
+
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "duplicate")));
+    assert!(diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "unmatched-override")));
+}
+
+/// Ensures crates that are both normal and build dependencies are detected,
+/// along with how they are reached as each
+#[test]
+fn detects_build_and_normal_dependencies() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("build-and-normal"),
+        "build-and-normal = 'warn'",
+    );
+
+    let twice: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "build-and-normal"))
+        .collect();
+
+    assert_eq!(twice.len(), 1);
+    assert_field_eq!(twice[0], "/fields/severity", "warning");
+    assert_field_eq!(
+        twice[0],
+        "/fields/message",
+        "crate 'shared = 0.1.0' is both a normal and a build dependency, and is compiled for each"
+    );
+    assert_field_eq!(
+        twice[0],
+        "/fields/notes/0",
+        "normal dependency: 'shared = 0.1.0' <- 'build-and-normal = 0.1.0'"
+    );
+    assert_field_eq!(
+        twice[0],
+        "/fields/notes/1",
+        "build dependency: 'shared = 0.1.0' <- 'builder = 0.1.0' <- 'build-and-normal = 0.1.0'"
+    );
+}
+
+/// Ensures features that workspace members enable directly on their
+/// dependencies can be denied, unless the member is an allowed wrapper
+#[test]
+fn denies_member_features() {
+    let cfg = r#"
+deny-member-features = [
+    "reqwest/brotli",
+    "windows-sys/Win32_System_LibraryLoader",
+    # Disabled via `default-features = false`
+    "git2/default",
+    { feature = "reqwest/cookies", wrappers = ["features-galore"] },
+    { feature = "rgb/serde", reason = "only enabled via a feature" },
+]
+"#;
+
+    let diags = gather_bans(func_name!(), KrateGather::new("features-galore"), cfg);
+
+    let denied: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "denied-member-feature"))
+        .collect();
+
+    assert_eq!(denied.len(), 2);
+    assert_field_eq!(
+        denied[0],
+        "/fields/message",
+        "workspace member 'features-galore' enables denied feature 'reqwest/brotli'"
+    );
+    assert_field_eq!(
+        denied[1],
+        "/fields/message",
+        "workspace member 'features-galore' enables denied feature 'windows-sys/Win32_System_LibraryLoader'"
+    );
+}
+
+/// Ensures crates with names that are near misses of popular crates are
+/// flagged, but the workspace itself is not
+#[test]
+fn detects_possible_typosquats() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("typosquat/app"),
+        "typosquatting = 'warn'",
+    );
+
+    let mut typosquats: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "possible-typosquat"))
+        .map(|d| d["fields"]["message"].as_str().unwrap())
+        .collect();
+    typosquats.sort_unstable();
+
+    assert_eq!(
+        typosquats,
+        [
+            "crate 'serd = 0.1.0' has a name similar to the popular crate 'serde'",
+            "crate 'tokoi = 0.1.0' has a name similar to the popular crate 'tokio'",
+        ]
+    );
+}
+
+/// Ensures crates owned by a denied publisher are reported at the configured
+/// level
+#[test]
+fn denies_publishers() {
+    let cfg = format!(
+        "deny-publishers = ['Amanieu', 'sfackler']\npublishers-file = '{}/tests/cfg/publishers.toml'",
+        env!("CARGO_MANIFEST_DIR")
+    );
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("features-galore"),
+        cfg.as_str(),
+    );
+
+    let denied: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "denied-publisher"))
+        .collect();
+
+    assert_eq!(denied.len(), 1);
+    assert_field_eq!(
+        denied[0],
+        "/fields/message",
+        "crate 'parking_lot_core = 0.9.3' is owned by denied publisher 'Amanieu'"
+    );
+    assert_field_eq!(denied[0], "/fields/severity", "error");
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("features-galore"),
+        format!("{cfg}\ndenied-publisher = 'warn'"),
+    );
+
+    let denied = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "denied-publisher"))
+        .expect("expected parking_lot_core to be reported");
+    assert_field_eq!(denied, "/fields/severity", "warning");
+}
+
+/// Ensures banned crates that are optional dependencies note the features of
+/// the parent that enabled them
+#[test]
+fn notes_features_enabling_banned_crates() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather {
+            name: "features-galore",
+            features: &["tls"],
+            ..Default::default()
+        },
+        "deny = ['rustls']",
+    );
+
+    let banned = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "banned"))
+        .expect("failed to find banned crate");
+
+    let notes = banned["fields"]["notes"].as_array().unwrap();
+    let note = notes
+        .iter()
+        .filter_map(|n| n.as_str())
+        .find(|n| n.starts_with("optional dependency 'features-galore = 0.1.0' -> 'rustls"))
+        .expect("failed to find feature note");
+
+    assert!(
+        note.ends_with(
+            "is enabled by the 'rustls', 'tls', 'tls-no-reqwest' feature(s) of 'features-galore = 0.1.0'"
+        ),
+        "{note}"
+    );
+}
+
+/// Ensures features can be denied for every crate in the graph, along with
+/// what enabled them
+#[test]
+fn denies_features_globally() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("features-galore"),
+        "deny-features-global = ['Win32_System_LibraryLoader', 'git2/ssh']",
+    );
+
+    let denied: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "feature-banned-globally"))
+        .collect();
+
+    // `git2` has its default features disabled
+    assert!(denied
+        .iter()
+        .all(|d| !field_eq!(d, "/fields/graphs/0/Krate/name", "git2")));
+
+    let windows_sys = denied
+        .iter()
+        .find(|d| {
+            field_eq!(
+                d,
+                "/fields/message",
+                "feature 'Win32_System_LibraryLoader' for crate 'windows-sys = 0.42.0' is denied for every crate"
+            )
+        })
+        .expect("failed to find denied feature");
+
+    assert!(windows_sys["fields"]["notes"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!(
+            "enabled by the dependency declaration of 'features-galore = 0.1.0'"
+        )));
+}
+
+/// Ensures crates only sourced from git are flagged, but not git crates that
+/// patch a registry dependency
+#[test]
+fn detects_git_only_crates() {
+    let git_only = |kg: KrateGather<'_>| -> Vec<_> {
+        gather_bans(func_name!(), kg, "git-only = 'deny'")
+            .into_iter()
+            .filter(|d| field_eq!(d, "/fields/code", "git-only"))
+            .collect()
+    };
+
+    let diags = git_only(KrateGather::new("wildcards/allow-git"));
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+    assert_field_eq!(
+        diags[0],
+        "/fields/message",
+        "crate 'krates = 0.16.6' is only available from a git repository"
+    );
+
+    // `anyhow` is patched in place of the crates.io version
+    assert!(git_only(KrateGather::new("sources"))
+        .iter()
+        .all(|d| !field_eq!(d, "/fields/graphs/0/Krate/name", "anyhow")));
+}
+
+/// Ensures workspace members may not depend directly on crates that are only
+/// allowed as transitive dependencies, and are reported at the configured level
+#[test]
+fn denies_direct_dependencies() {
+    let cfg = "deny-direct = ['parking_lot_core', '*-sys']";
+
+    let diags: Vec<_> = gather_bans(func_name!(), KrateGather::new("features-galore"), cfg)
+        .into_iter()
+        .filter(|d| field_eq!(d, "/fields/code", "denied-direct-dependency"))
+        .collect();
+
+    let denied: Vec<_> = diags
+        .iter()
+        .map(|d| d.pointer("/fields/message").unwrap().as_str().unwrap())
+        .collect();
+
+    // `libgit2-sys` is only a transitive dependency, through `git2`
+    assert_eq!(
+        denied,
+        [
+            "workspace member 'features-galore' depends on 'parking_lot_core = 0.9.3' directly, which is only allowed as a transitive dependency",
+            "workspace member 'features-galore' depends on 'windows-sys = 0.42.0' directly, which is only allowed as a transitive dependency",
+        ]
+    );
+
+    assert_field_eq!(diags[0], "/fields/labels/0/message", "declared here");
+    assert_field_eq!(
+        diags[0],
+        "/fields/labels/0/span",
+        "parking_lot_core = '=0.9.3'"
+    );
+    assert_field_eq!(diags[0], "/fields/labels/1/span", "parking_lot_core");
+    assert_field_eq!(diags[0], "/fields/severity", "error");
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("features-galore"),
+        format!("{cfg}\ndenied-direct-dependency = 'warn'"),
+    );
+
+    let denied = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "denied-direct-dependency"))
+        .expect("expected parking_lot_core to be reported");
+    assert_field_eq!(denied, "/fields/severity", "warning");
+}
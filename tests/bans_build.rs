@@ -1,4 +1,4 @@
-use cargo_deny::{field_eq, func_name, test_utils::*};
+use cargo_deny::{assert_field_eq, field_eq, func_name, test_utils::*};
 
 /// Verifies we can detect and error on builtin globs
 #[test]
@@ -98,6 +98,77 @@ include-dependencies = true
     insta::assert_json_snapshot!(diags);
 }
 
+/// Verifies every crate is scanned for native executables when `include-all`
+/// is enabled, not just those that execute at build time
+#[test]
+fn detects_native_executables_in_all_crates() {
+    let kg = || KrateGather::new("prebuilt/app");
+
+    let diags = gather_bans(
+        func_name!(),
+        kg(),
+        Config::new("[build]\ninclude-dependencies = true"),
+    );
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "detected-executable")));
+
+    let diags = gather_bans(
+        func_name!(),
+        kg(),
+        Config::new("[build]\ninclude-all = true"),
+    );
+    let detected: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "detected-executable"))
+        .collect();
+
+    assert_eq!(detected.len(), 1);
+    assert_field_eq!(detected[0], "/fields/graphs/0/Krate/name", "vendored-blob");
+}
+
+/// Verifies paths matching a denied glob are reported, unless they are allowed
+/// for the crate
+#[test]
+fn detects_denied_globs() {
+    let kg = || KrateGather::new("prebuilt/app");
+
+    let diags = gather_bans(
+        func_name!(),
+        kg(),
+        Config::new("[build]\ninclude-all = true\ndeny-globs = ['**/*.wasm']"),
+    );
+    let denied: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "denied-by-glob"))
+        .collect();
+
+    assert_eq!(denied.len(), 1);
+    assert!(denied[0]["fields"]["notes"][0]
+        .as_str()
+        .unwrap()
+        .ends_with("assets/module.wasm'"));
+
+    let diags = gather_bans(
+        func_name!(),
+        kg(),
+        Config::new(
+            r#"
+[build]
+include-all = true
+deny-globs = ['**/*.wasm']
+
+[[build.bypass]]
+crate = "vendored-blob"
+allow-globs = ["assets/*.wasm"]
+"#,
+        ),
+    );
+    assert!(!diags
+        .iter()
+        .any(|d| field_eq!(d, "/fields/code", "denied-by-glob")));
+}
+
 /// Verifies user provided builscript checksums are always validated correctly
 #[test]
 fn detects_build_script_mismatch() {
@@ -187,6 +258,53 @@ build-script = "1a850d791184374f614d01c86c8d6c9ba0500e64cb746edc9720ceaaa1cd8eaf
     insta::assert_json_snapshot!(diags);
 }
 
+/// Verifies proc macros are denied if not allowed, at the configured level
+#[test]
+fn allows_proc_macros() {
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("proc-macros"),
+        Config::new(
+            r#"
+[build]
+allow-proc-macros = ["allowed-macro"]
+executables = "allow"
+"#,
+        ),
+    );
+
+    let denied: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "proc-macro-not-allowed"))
+        .collect();
+    assert_eq!(denied.len(), 1);
+    assert_field_eq!(
+        denied[0],
+        "/fields/message",
+        "crate 'denied-macro = 0.1.0' is a proc macro but is not allowed to be one"
+    );
+    assert_field_eq!(denied[0], "/fields/severity", "error");
+
+    let diags = gather_bans(
+        func_name!(),
+        KrateGather::new("proc-macros"),
+        Config::new(
+            r#"
+[build]
+allow-proc-macros = ["allowed-macro"]
+proc-macros = "warn"
+executables = "allow"
+"#,
+        ),
+    );
+
+    let denied = diags
+        .iter()
+        .find(|d| field_eq!(d, "/fields/code", "proc-macro-not-allowed"))
+        .expect("expected denied-macro to be reported");
+    assert_field_eq!(denied, "/fields/severity", "warning");
+}
+
 /// Verifies executables are allowed by glob patterns
 #[test]
 fn allows_by_glob() {
@@ -1,5 +1,5 @@
 use cargo_deny::{
-    diag, field_eq, func_name,
+    assert_field_eq, diag, field_eq, func_name,
     licenses::{self, cfg::Config},
     test_utils as tu, Krates,
 };
@@ -52,6 +52,7 @@ pub fn gather_licenses_with_overrides(
                 summary,
                 diag::ErrorSink {
                     overrides: overrides.map(Arc::new),
+                    waivers: None,
                     channel: tx,
                 },
             );
@@ -179,6 +180,7 @@ fn lax_fallback() {
                 summary,
                 diag::ErrorSink {
                     overrides: None,
+                    waivers: None,
                     channel: tx,
                 },
             );
@@ -245,6 +247,7 @@ license-files = [
                 summary,
                 diag::ErrorSink {
                     overrides: None,
+                    waivers: None,
                     channel: tx,
                 },
             );
@@ -274,6 +277,52 @@ include-dev = true
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures copyleft licenses can be denied, or warned about, by their strength
+/// even if they are explicitly allowed
+#[test]
+fn restricts_copyleft_by_strength() {
+    let simple_ecs = |copyleft: &str| {
+        let cfg = tu::Config::new(format!(
+            "version = 2
+allow = ['GPL-3.0']
+include-dev = true
+{copyleft}"
+        ));
+
+        gather_licenses_with_overrides(func_name!(), cfg, None)
+            .into_iter()
+            .find(|d| field_eq!(d, "/fields/graphs/0/Krate/name", "simple_ecs"))
+            .unwrap()
+    };
+
+    let diag = simple_ecs("");
+    assert_field_eq!(diag, "/fields/code", "accepted");
+    assert_field_eq!(diag, "/fields/severity", "help");
+
+    // GPL-3.0 is strong copyleft, so weak copyleft settings don't apply to it
+    let diag = simple_ecs("weak-copyleft = 'deny'");
+    assert_field_eq!(diag, "/fields/code", "accepted");
+
+    let diag = simple_ecs("strong-copyleft = 'warn'");
+    assert_field_eq!(diag, "/fields/code", "accepted");
+    assert_field_eq!(diag, "/fields/severity", "warning");
+
+    let diag = simple_ecs("strong-copyleft = 'deny'");
+    assert_field_eq!(diag, "/fields/code", "rejected");
+    assert_field_eq!(diag, "/fields/severity", "error");
+    assert_field_eq!(
+        diag,
+        "/fields/notes",
+        [
+            "GPL-3.0 - GNU General Public License v3.0 only:",
+            "  - **DEPRECATED**",
+            "  - OSI approved",
+            "  - FSF Free/Libre",
+            "  - Copyleft (strong)"
+        ]
+    );
+}
+
 /// Ensures that an Apache-2.0 licenses without the appendix are not misidentified
 /// as Pixar, because Pixar is an almost exact copy of Apache-2.0. Fuck I hate licenses so much.
 #[test]
@@ -307,6 +356,7 @@ fn forces_apache_over_pixar() {
                 summary,
                 diag::ErrorSink {
                     overrides: None,
+                    waivers: None,
                     channel: tx,
                 },
             );
@@ -315,3 +365,52 @@ fn forces_apache_over_pixar() {
 
     insta::assert_json_snapshot!(diags);
 }
+
+/// Ensures a license file whose hash matches a custom license reference is
+/// attributed to it, and can then be allowed like any other license
+#[test]
+fn accepts_license_refs() {
+    let mut cmd = krates::Cmd::new();
+    cmd.manifest_path("tests/test_data/license-ref/Cargo.toml");
+
+    let krates: Krates = krates::Builder::new()
+        .build(cmd, krates::NoneFilter)
+        .unwrap();
+
+    let gatherer = licenses::Gatherer::default()
+        .with_store(store())
+        .with_confidence_threshold(0.8);
+
+    let cfg = tu::Config::new(
+        r#"
+    allow = ['LicenseRef-Acme-Proprietary']
+
+    [[license-refs]]
+    id = "LicenseRef-Acme-Proprietary"
+    hash = 0x9a6caa50
+    "#,
+    );
+
+    let diags = tu::gather_diagnostics_with_files::<Config, _, _>(
+        &krates,
+        func_name!(),
+        cfg,
+        codespan::Files::new(),
+        |ctx, _cs, tx, files| {
+            let summary = gatherer.gather(ctx.krates, files, Some(&ctx.cfg));
+            crate::licenses::check(
+                ctx,
+                summary,
+                diag::ErrorSink {
+                    overrides: None,
+                    waivers: None,
+                    channel: tx,
+                },
+            );
+        },
+    );
+
+    assert_eq!(diags.len(), 1);
+    assert_field_eq!(diags[0], "/fields/code", "accepted");
+    assert_field_eq!(diags[0], "/fields/graphs/0/Krate/name", "license-ref");
+}
@@ -1,5 +1,5 @@
 use cargo_deny::{
-    func_name,
+    assert_field_eq, field_eq, func_name,
     sources::{self, cfg::Config},
     test_utils::{self as tu, KrateGather},
 };
@@ -29,6 +29,38 @@ fn fails_unknown_git() {
     insta::assert_json_snapshot!(diags);
 }
 
+/// Ensures a crate with a well known name is flagged if it doesn't come from
+/// the source it is expected to, while the real crate passes
+#[test]
+fn flags_unexpected_sources() {
+    let cfg = "unknown-git = 'allow'
+    unknown-registry = 'allow'
+    expected-sources = [
+        { crate = 'rand', source = 'https://github.com/rust-lang/crates.io-index' },
+    ]";
+
+    let diags = src_check(func_name!(), KrateGather::new("expected-sources"), cfg);
+
+    let unexpected: Vec<_> = diags
+        .iter()
+        .filter(|d| field_eq!(d, "/fields/code", "unexpected-source"))
+        .collect();
+    assert_eq!(unexpected.len(), 1);
+
+    let shadow = unexpected[0];
+    assert_field_eq!(shadow, "/fields/severity", "error");
+    assert_field_eq!(
+        shadow,
+        "/fields/message",
+        "crate 'rand = 0.8.5' is not from its expected source"
+    );
+    assert!(shadow
+        .pointer("/fields/notes/0")
+        .and_then(|n| n.as_str())
+        .unwrap()
+        .starts_with("actual source: git+https://github.com/rust-random/rand"));
+}
+
 #[test]
 fn allows_git() {
     let cfg = "unknown-git = 'deny'